@@ -1836,6 +1836,7 @@ mod __test {
             doc_tests: DocTests::No,
             bless: false,
             compare_mode: None,
+            diagnostic_coverage: false,
         };
 
         let build = Build::new(config);
@@ -1877,6 +1878,7 @@ mod __test {
             doc_tests: DocTests::No,
             bless: false,
             compare_mode: None,
+            diagnostic_coverage: false,
         };
 
         let build = Build::new(config);
@@ -62,6 +62,8 @@ pub enum Subcommand {
         /// Whether to automatically update stderr/stdout files
         bless: bool,
         compare_mode: Option<String>,
+        /// Whether to report the error codes and lints triggered by ui and compile-fail tests
+        diagnostic_coverage: bool,
         test_args: Vec<String>,
         rustc_args: Vec<String>,
         fail_fast: bool,
@@ -198,6 +200,11 @@ To learn more about a subcommand, run `./x.py <subcommand> -h`"
                     "mode describing what file the actual ui output will be compared to",
                     "COMPARE MODE",
                 );
+                opts.optflag(
+                    "",
+                    "diagnostic-coverage",
+                    "report the error codes and lints not triggered by ui and compile-fail tests",
+                );
             }
             "bench" => {
                 opts.optmulti("", "test-args", "extra arguments", "ARGS");
@@ -295,7 +302,9 @@ Arguments:
         ./x.py test src/libstd --test-args hash_map
         ./x.py test src/libstd --stage 0 --no-doc
         ./x.py test src/test/ui --bless
+        ./x.py test src/test/mir-opt --bless
         ./x.py test src/test/ui --compare-mode nll
+        ./x.py test src/test/ui src/test/compile-fail --diagnostic-coverage
 
     Note that `test src/test/* --stage N` does NOT depend on `build src/rustc --stage N`;
     just like `build src/libstd --stage N` it tests the compiler produced by the previous
@@ -370,6 +379,7 @@ Arguments:
                 paths,
                 bless: matches.opt_present("bless"),
                 compare_mode: matches.opt_str("compare-mode"),
+                diagnostic_coverage: matches.opt_present("diagnostic-coverage"),
                 test_args: matches.opt_strs("test-args"),
                 rustc_args: matches.opt_strs("rustc-args"),
                 fail_fast: !matches.opt_present("no-fail-fast"),
@@ -485,6 +495,13 @@ impl Subcommand {
             _ => None,
         }
     }
+
+    pub fn diagnostic_coverage(&self) -> bool {
+        match *self {
+            Subcommand::Test { diagnostic_coverage, .. } => diagnostic_coverage,
+            _ => false,
+        }
+    }
 }
 
 fn split(s: &[String]) -> Vec<String> {
@@ -1023,6 +1023,11 @@ impl Step for Compiletest {
             cmd.arg("--bless");
         }
 
+        if builder.config.cmd.diagnostic_coverage() && (mode == "ui" || mode == "compile-fail") {
+            cmd.arg("--diagnostic-coverage")
+                .arg(testdir(builder, compiler.host).join("diagnostic-coverage"));
+        }
+
         let compare_mode = builder.config.cmd.compare_mode().or(self.compare_mode);
 
         if let Some(ref nodejs) = builder.config.nodejs {
@@ -822,6 +822,57 @@ impl DepGraph {
         }
     }
 
+    // This method emits the diagnostics of the previous compilation session
+    // that have not been replayed yet, so that warnings do not get lost when
+    // nothing needed the queries that emitted them anymore. Every such node
+    // is marked green if possible, which emits its diagnostics and stores them
+    // for the next session again. The diagnostics emitted outside of queries
+    // are associated with the `Krate` node and are emitted if it is green,
+    // that is, if neither the crate, its dependencies nor the command line
+    // changed. The `Handler` skips the ones this session emitted already.
+    //
+    // The driver calls this once the analysis of the crate is done, so that
+    // the replayed diagnostics are emitted with the ones of this session and
+    // before codegen, rather than after all output has been written. It has to
+    // run before the query result cache and the dep-graph are saved, since it
+    // can add nodes to the graph.
+    pub fn emit_previous_diagnostics<'tcx>(&self, tcx: TyCtxt<'_, 'tcx, 'tcx>) {
+        let data = self.data.as_ref().unwrap();
+        let krate = DepNode::new_no_params(DepKind::Krate);
+
+        for prev_index in tcx.queries.on_disk_cache.prev_diagnostics_indices() {
+            let dep_node = data.previous.index_to_node(prev_index);
+
+            // Anonymous and eval-always nodes are only ever recomputed, and
+            // the only input with diagnostics is `Krate`.
+            if dep_node.kind.is_anon() ||
+               dep_node.kind.is_eval_always() ||
+               dep_node.kind.is_input() {
+                continue;
+            }
+
+            // Marking a node green marks its dependencies too, so the color
+            // is only known here.
+            if data.colors.borrow().get(prev_index).is_none() {
+                debug!("emit_previous_diagnostics() --- trying to mark {:?} green",
+                       dep_node);
+                self.try_mark_green(tcx, &dep_node);
+            }
+        }
+
+        if self.is_green(&krate) {
+            let prev_index = data.previous.node_to_index(&krate);
+            let diagnostics = tcx.queries.on_disk_cache.load_diagnostics(tcx, prev_index);
+            let handle = tcx.sess.diagnostic();
+
+            // Emitting them outside of a query stores them for the next
+            // session again.
+            for diagnostic in diagnostics {
+                DiagnosticBuilder::new_diagnostic(handle, diagnostic).emit();
+            }
+        }
+    }
+
     pub fn mark_loaded_from_cache(&self, dep_node_index: DepNodeIndex, state: bool) {
         debug!("mark_loaded_from_cache({:?}, {})",
                self.data.as_ref().unwrap().current.borrow().nodes[dep_node_index],
@@ -0,0 +1,401 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A JSON serialization of the items of the HIR, written by `-Z unpretty=hir-json` for tools
+//! that analyze crates outside of the compiler.
+//!
+//! The schema is documented in the unstable book, in `compiler-flags/unpretty.md`. It
+//! describes items down to their signatures, but not their bodies; types, paths and bounds are
+//! written the way `-Z unpretty=hir` prints them. Items refer to each other by their node ids,
+//! which are the ones `-Z unpretty=hir,identified` shows.
+
+use hir::{self, map as hir_map};
+use hir::print::{self, NO_ANN};
+use serialize::json::{object, Json, ToJson};
+use std::io::{self, Write};
+use syntax::ast::{Attribute, NodeId};
+use syntax::print::pprust;
+use syntax::source_map::SourceMap;
+use syntax_pos::Span;
+
+/// The version of the schema, bumped whenever it changes in an incompatible way.
+const SCHEMA_VERSION: u64 = 1;
+
+/// Write the given items, or all the items of the crate, as a JSON document. The ids that are
+/// not those of items, trait items, impl items or foreign items are skipped.
+pub fn write_crate_json(map: &hir_map::Map,
+                        source_map: &SourceMap,
+                        crate_name: &str,
+                        ids: Option<&[NodeId]>,
+                        w: &mut dyn Write)
+                        -> io::Result<()> {
+    let cx = HirJson { map, source_map };
+    let items = match ids {
+        Some(ids) => {
+            ids.iter().filter_map(|&id| {
+                match map.find(id) {
+                    Some(hir::Node::Item(item)) => Some(cx.item(item)),
+                    Some(hir::Node::TraitItem(item)) => Some(cx.trait_item(item)),
+                    Some(hir::Node::ImplItem(item)) => Some(cx.impl_item(item)),
+                    Some(hir::Node::ForeignItem(item)) => Some(cx.foreign_item(item)),
+                    _ => None,
+                }
+            }).collect()
+        }
+        None => map.krate().items.values().map(|item| cx.item(item)).collect(),
+    };
+
+    let document = object(vec![
+        ("version", SCHEMA_VERSION.to_json()),
+        ("crate", crate_name.to_json()),
+        ("items", Json::Array(items)),
+    ]);
+    writeln!(w, "{}", document.pretty())
+}
+
+struct HirJson<'a, 'hir: 'a> {
+    map: &'a hir_map::Map<'hir>,
+    source_map: &'a SourceMap,
+}
+
+impl<'a, 'hir> HirJson<'a, 'hir> {
+    fn item(&self, item: &hir::Item) -> Json {
+        let (kind, mut fields) = match item.node {
+            hir::ItemKind::ExternCrate(orig_name) => {
+                ("extern_crate", vec![
+                    ("original_name", orig_name.map(|name| name.to_string()).to_json()),
+                ])
+            }
+            hir::ItemKind::Use(ref path, use_kind) => {
+                let use_kind = match use_kind {
+                    hir::UseKind::Single => "single",
+                    hir::UseKind::Glob => "glob",
+                    hir::UseKind::ListStem => "list_stem",
+                };
+                ("use", vec![
+                    ("path", print::to_string(NO_ANN, |s| s.print_path(path, false)).to_json()),
+                    ("use_kind", use_kind.to_json()),
+                ])
+            }
+            hir::ItemKind::Static(ref ty, mutbl, _) => {
+                ("static", vec![
+                    ("ty", type_to_string(ty).to_json()),
+                    ("mutable", (mutbl == hir::MutMutable).to_json()),
+                ])
+            }
+            hir::ItemKind::Const(ref ty, _) => {
+                ("const", vec![("ty", type_to_string(ty).to_json())])
+            }
+            hir::ItemKind::Fn(ref decl, header, ref generics, _) => {
+                ("fn", vec![
+                    ("generics", generics_to_json(generics)),
+                    ("signature", fn_sig_to_json(decl, header)),
+                ])
+            }
+            hir::ItemKind::Mod(ref module) => {
+                let items = module.item_ids.iter().map(|item_id| node_id_to_json(item_id.id));
+                ("mod", vec![("items", Json::Array(items.collect()))])
+            }
+            hir::ItemKind::ForeignMod(ref foreign_mod) => {
+                let items = foreign_mod.items.iter().map(|item| self.foreign_item(item));
+                ("foreign_mod", vec![
+                    ("abi", foreign_mod.abi.name().to_json()),
+                    ("items", Json::Array(items.collect())),
+                ])
+            }
+            hir::ItemKind::GlobalAsm(_) => ("global_asm", vec![]),
+            hir::ItemKind::Ty(ref ty, ref generics) => {
+                ("type", vec![
+                    ("generics", generics_to_json(generics)),
+                    ("ty", type_to_string(ty).to_json()),
+                ])
+            }
+            hir::ItemKind::Existential(ref exist_ty) => {
+                ("existential", vec![
+                    ("generics", generics_to_json(&exist_ty.generics)),
+                    ("bounds", bounds_to_string(&exist_ty.bounds).to_json()),
+                ])
+            }
+            hir::ItemKind::Enum(ref enum_def, ref generics) => {
+                let variants = enum_def.variants.iter().map(|variant| {
+                    let mut fields = vec![
+                        ("name", variant.node.name.to_string().to_json()),
+                        ("span", span_to_json(self.source_map, variant.span)),
+                        ("attributes", attributes_to_json(&variant.node.attrs)),
+                    ];
+                    fields.extend(self.variant_data(&variant.node.data));
+                    object(fields)
+                });
+                ("enum", vec![
+                    ("generics", generics_to_json(generics)),
+                    ("variants", Json::Array(variants.collect())),
+                ])
+            }
+            hir::ItemKind::Struct(ref variant_data, ref generics) => {
+                let mut fields = vec![("generics", generics_to_json(generics))];
+                fields.extend(self.variant_data(variant_data));
+                ("struct", fields)
+            }
+            hir::ItemKind::Union(ref variant_data, ref generics) => {
+                let mut fields = vec![("generics", generics_to_json(generics))];
+                fields.extend(self.variant_data(variant_data));
+                ("union", fields)
+            }
+            hir::ItemKind::Trait(is_auto, unsafety, ref generics, ref bounds, ref item_refs) => {
+                let krate = self.map.krate();
+                let items = item_refs.iter().map(|item_ref| {
+                    self.trait_item(krate.trait_item(item_ref.id))
+                });
+                ("trait", vec![
+                    ("auto", (is_auto == hir::IsAuto::Yes).to_json()),
+                    ("unsafe", (unsafety == hir::Unsafety::Unsafe).to_json()),
+                    ("generics", generics_to_json(generics)),
+                    ("bounds", bounds_to_string(bounds).to_json()),
+                    ("items", Json::Array(items.collect())),
+                ])
+            }
+            hir::ItemKind::TraitAlias(ref generics, ref bounds) => {
+                ("trait_alias", vec![
+                    ("generics", generics_to_json(generics)),
+                    ("bounds", bounds_to_string(bounds).to_json()),
+                ])
+            }
+            hir::ItemKind::Impl(unsafety,
+                                polarity,
+                                defaultness,
+                                ref generics,
+                                ref trait_ref,
+                                ref self_ty,
+                                ref item_refs) => {
+                let krate = self.map.krate();
+                let items = item_refs.iter().map(|item_ref| {
+                    self.impl_item(krate.impl_item(item_ref.id))
+                });
+                let trait_ref = trait_ref.as_ref().map(|trait_ref| {
+                    print::to_string(NO_ANN, |s| s.print_trait_ref(trait_ref))
+                });
+                ("impl", vec![
+                    ("unsafe", (unsafety == hir::Unsafety::Unsafe).to_json()),
+                    ("negative", (polarity == hir::ImplPolarity::Negative).to_json()),
+                    ("default", defaultness.is_default().to_json()),
+                    ("generics", generics_to_json(generics)),
+                    ("trait", trait_ref.to_json()),
+                    ("self_ty", type_to_string(self_ty).to_json()),
+                    ("items", Json::Array(items.collect())),
+                ])
+            }
+        };
+
+        let mut common = self.common(item.id, item.name.to_string(), kind, item.span, &item.attrs);
+        common.push(("visibility", visibility_to_json(&item.vis)));
+        common.append(&mut fields);
+        object(common)
+    }
+
+    fn trait_item(&self, item: &hir::TraitItem) -> Json {
+        let (kind, mut fields) = match item.node {
+            hir::TraitItemKind::Const(ref ty, default) => {
+                ("const", vec![
+                    ("ty", type_to_string(ty).to_json()),
+                    ("has_default", default.is_some().to_json()),
+                ])
+            }
+            hir::TraitItemKind::Method(ref sig, ref trait_method) => {
+                let has_default = match *trait_method {
+                    hir::TraitMethod::Required(_) => false,
+                    hir::TraitMethod::Provided(_) => true,
+                };
+                ("method", vec![
+                    ("signature", fn_sig_to_json(&sig.decl, sig.header)),
+                    ("has_default", has_default.to_json()),
+                ])
+            }
+            hir::TraitItemKind::Type(ref bounds, ref default) => {
+                ("type", vec![
+                    ("bounds", bounds_to_string(bounds).to_json()),
+                    ("default", default.as_ref().map(|ty| type_to_string(ty)).to_json()),
+                ])
+            }
+        };
+
+        let mut common = self.common(item.id, item.ident.to_string(), kind, item.span, &item.attrs);
+        common.push(("generics", generics_to_json(&item.generics)));
+        common.append(&mut fields);
+        object(common)
+    }
+
+    fn impl_item(&self, item: &hir::ImplItem) -> Json {
+        let (kind, mut fields) = match item.node {
+            hir::ImplItemKind::Const(ref ty, _) => {
+                ("const", vec![("ty", type_to_string(ty).to_json())])
+            }
+            hir::ImplItemKind::Method(ref sig, _) => {
+                ("method", vec![("signature", fn_sig_to_json(&sig.decl, sig.header))])
+            }
+            hir::ImplItemKind::Type(ref ty) => {
+                ("type", vec![("ty", type_to_string(ty).to_json())])
+            }
+            hir::ImplItemKind::Existential(ref bounds) => {
+                ("existential", vec![("bounds", bounds_to_string(bounds).to_json())])
+            }
+        };
+
+        let mut common = self.common(item.id, item.ident.to_string(), kind, item.span, &item.attrs);
+        common.push(("visibility", visibility_to_json(&item.vis)));
+        common.push(("default", item.defaultness.is_default().to_json()));
+        common.push(("generics", generics_to_json(&item.generics)));
+        common.append(&mut fields);
+        object(common)
+    }
+
+    fn foreign_item(&self, item: &hir::ForeignItem) -> Json {
+        let (kind, mut fields) = match item.node {
+            hir::ForeignItemKind::Fn(ref decl, _, ref generics) => {
+                let header = hir::FnHeader {
+                    unsafety: hir::Unsafety::Unsafe,
+                    constness: hir::Constness::NotConst,
+                    asyncness: hir::IsAsync::NotAsync,
+                    abi: self.map.get_foreign_abi(item.id),
+                };
+                ("fn", vec![
+                    ("generics", generics_to_json(generics)),
+                    ("signature", fn_sig_to_json(decl, header)),
+                ])
+            }
+            hir::ForeignItemKind::Static(ref ty, mutable) => {
+                ("static", vec![
+                    ("ty", type_to_string(ty).to_json()),
+                    ("mutable", mutable.to_json()),
+                ])
+            }
+            hir::ForeignItemKind::Type => ("type", vec![]),
+        };
+
+        let mut common = self.common(item.id, item.name.to_string(), kind, item.span, &item.attrs);
+        common.push(("visibility", visibility_to_json(&item.vis)));
+        common.append(&mut fields);
+        object(common)
+    }
+
+    /// The fields every kind of item has.
+    fn common(&self,
+              id: NodeId,
+              name: String,
+              kind: &'static str,
+              span: Span,
+              attrs: &[Attribute])
+              -> Vec<(&'static str, Json)> {
+        let def_path = self.map.def_path_from_id(id).map(|path| {
+            path.data
+                .into_iter()
+                .map(|elem| elem.data.to_string())
+                .collect::<Vec<_>>()
+                .join("::")
+        });
+        vec![
+            ("id", node_id_to_json(id)),
+            ("def_path", def_path.to_json()),
+            ("name", name.to_json()),
+            ("kind", kind.to_json()),
+            ("span", span_to_json(self.source_map, span)),
+            ("attributes", attributes_to_json(attrs)),
+        ]
+    }
+
+    /// The fields of a struct, union or enum variant.
+    fn variant_data(&self, variant_data: &hir::VariantData) -> Vec<(&'static str, Json)> {
+        let ctor_kind = if variant_data.is_struct() {
+            "struct"
+        } else if variant_data.is_tuple() {
+            "tuple"
+        } else {
+            "unit"
+        };
+        let fields = variant_data.fields().iter().map(|field| {
+            object(vec![
+                ("id", node_id_to_json(field.id)),
+                ("name", field.ident.to_string().to_json()),
+                ("ty", type_to_string(&field.ty).to_json()),
+                ("visibility", visibility_to_json(&field.vis)),
+                ("span", span_to_json(self.source_map, field.span)),
+                ("attributes", attributes_to_json(&field.attrs)),
+            ])
+        });
+        vec![
+            ("ctor_kind", ctor_kind.to_json()),
+            ("fields", Json::Array(fields.collect())),
+        ]
+    }
+}
+
+/// The location of a span, with 1-based lines and columns counted in characters, like in the
+/// JSON diagnostics. Dummy spans are `null`.
+pub fn span_to_json(source_map: &SourceMap, span: Span) -> Json {
+    if span.is_dummy() {
+        return Json::Null;
+    }
+    let lo = source_map.lookup_char_pos(span.lo());
+    let hi = source_map.lookup_char_pos(span.hi());
+    object(vec![
+        ("file_name", lo.file.name.to_string().to_json()),
+        ("line_start", lo.line.to_json()),
+        ("column_start", (lo.col.0 + 1).to_json()),
+        ("line_end", hi.line.to_json()),
+        ("column_end", (hi.col.0 + 1).to_json()),
+    ])
+}
+
+fn fn_sig_to_json(decl: &hir::FnDecl, header: hir::FnHeader) -> Json {
+    let inputs = decl.inputs.iter().map(|ty| type_to_string(ty).to_json()).collect();
+    let output = match decl.output {
+        hir::DefaultReturn(_) => None,
+        hir::Return(ref ty) => Some(type_to_string(ty)),
+    };
+    object(vec![
+        ("unsafe", (header.unsafety == hir::Unsafety::Unsafe).to_json()),
+        ("const", (header.constness == hir::Constness::Const).to_json()),
+        ("async", (header.asyncness == hir::IsAsync::Async).to_json()),
+        ("abi", header.abi.name().to_json()),
+        ("inputs", Json::Array(inputs)),
+        ("output", output.to_json()),
+        ("variadic", decl.variadic.to_json()),
+    ])
+}
+
+fn generics_to_json(generics: &hir::Generics) -> Json {
+    let params = generics.params.iter().map(|param| {
+        print::to_string(NO_ANN, |s| s.print_generic_param(param)).to_json()
+    }).collect();
+    let where_clause = print::to_string(NO_ANN, |s| s.print_where_clause(&generics.where_clause));
+    object(vec![
+        ("params", Json::Array(params)),
+        ("where_clause", where_clause.trim().to_json()),
+    ])
+}
+
+fn type_to_string(ty: &hir::Ty) -> String {
+    print::to_string(NO_ANN, |s| s.print_type(ty))
+}
+
+fn bounds_to_string(bounds: &[hir::GenericBound]) -> String {
+    print::to_string(NO_ANN, |s| s.print_bounds("", bounds)).trim().to_owned()
+}
+
+fn visibility_to_json(vis: &hir::Visibility) -> Json {
+    print::visibility_qualified(vis, "").trim().to_json()
+}
+
+fn attributes_to_json(attrs: &[Attribute]) -> Json {
+    Json::Array(attrs.iter().map(|attr| pprust::attribute_to_string(attr).to_json()).collect())
+}
+
+fn node_id_to_json(id: NodeId) -> Json {
+    id.as_u32().to_json()
+}
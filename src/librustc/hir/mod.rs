@@ -63,6 +63,7 @@ pub mod check_attr;
 pub mod def;
 pub mod def_id;
 pub mod intravisit;
+pub mod json;
 pub mod itemlikevisit;
 pub mod lowering;
 pub mod map;
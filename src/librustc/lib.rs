@@ -167,6 +167,7 @@ pub mod util {
     pub mod nodemap;
     pub mod time_graph;
     pub mod profiling;
+    pub mod item_times;
     pub mod bug;
 }
 
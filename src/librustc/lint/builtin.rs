@@ -318,6 +318,13 @@ declare_lint! {
     "warn about missing code example in an item's documentation"
 }
 
+declare_lint! {
+    pub INVALID_CODEBLOCK_SYNTAX,
+    Warn,
+    "warn about Rust code blocks in documentation whose tokens cannot be lexed or whose \
+     delimiters do not balance"
+}
+
 declare_lint! {
     pub WHERE_CLAUSES_OBJECT_SAFETY,
     Warn,
@@ -415,6 +422,7 @@ impl LintPass for HardwiredLints {
             DUPLICATE_MACRO_EXPORTS,
             INTRA_DOC_LINK_RESOLUTION_FAILURE,
             MISSING_DOC_CODE_EXAMPLES,
+            INVALID_CODEBLOCK_SYNTAX,
             WHERE_CLAUSES_OBJECT_SAFETY,
             PROC_MACRO_DERIVE_RESOLUTION_FALLBACK,
             MACRO_USE_EXTERN_CRATE,
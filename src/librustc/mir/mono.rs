@@ -41,6 +41,16 @@ impl<'tcx> MonoItem<'tcx> {
             MonoItem::GlobalAsm(_) => 1,
         }
     }
+
+    /// The item this is an instance of. Shims, like drop glue, are instances of the item they
+    /// are generated for, like `drop_in_place`.
+    pub fn def_id<'a>(&self, tcx: TyCtxt<'a, 'tcx, 'tcx>) -> DefId {
+        match *self {
+            MonoItem::Fn(instance) => instance.def_id(),
+            MonoItem::Static(def_id) => def_id,
+            MonoItem::GlobalAsm(node_id) => tcx.hir.local_def_id(node_id),
+        }
+    }
 }
 
 impl<'a, 'tcx> HashStable<StableHashingContext<'a>> for MonoItem<'tcx> {
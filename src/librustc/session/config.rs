@@ -214,6 +214,8 @@ pub enum ErrorOutputType {
     HumanReadable(ColorConfig),
     Json(bool),
     Short(ColorConfig),
+    /// The color is only used for the problems reported before the SARIF log is written.
+    Sarif(ColorConfig),
 }
 
 impl Default for ErrorOutputType {
@@ -1286,6 +1288,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "emit Retagging MIR statements, interpreted e.g. by miri; implies -Zmir-opt-level=0"),
     perf_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some performance-related statistics"),
+    time_items: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "print the N items that took the longest to check, optimize and codegen"),
+    time_items_json: bool = (false, parse_bool, [UNTRACKED],
+        "print the report of `-Z time-items` as JSON"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about AST and HIR"),
     mir_stats: bool = (false, parse_bool, [UNTRACKED],
@@ -1381,6 +1387,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "run the self profiler"),
     profile_json: bool = (false, parse_bool, [UNTRACKED],
         "output a json file with profiler results"),
+    self_profile_events: bool = (false, parse_bool, [UNTRACKED],
+        "with -Z self-profile, record every query and output a Chrome trace file of them"),
     emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED],
         "emits a section containing stack size metadata"),
     plt: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1874,6 +1882,7 @@ pub fn build_session_options_and_crate_config(
             Some("json") => ErrorOutputType::Json(false),
             Some("pretty-json") => ErrorOutputType::Json(true),
             Some("short") => ErrorOutputType::Short(color),
+            Some("sarif") => ErrorOutputType::Sarif(color),
             None => ErrorOutputType::HumanReadable(color),
 
             Some(arg) => early_error(
@@ -1904,6 +1913,13 @@ pub fn build_session_options_and_crate_config(
         );
     }
 
+    if !debugging_opts.unstable_options && error_format == ErrorOutputType::Sarif(color) {
+        early_error(
+            ErrorOutputType::HumanReadable(color),
+            "--error-format=sarif is unstable",
+        );
+    }
+
     if debugging_opts.pgo_gen.is_some() && !debugging_opts.pgo_use.is_empty() {
         early_error(
             error_format,
@@ -34,9 +34,11 @@ use syntax::ast::{self, NodeId};
 use syntax::edition::Edition;
 use syntax::feature_gate::{self, AttributeType};
 use syntax::json::JsonEmitter;
+use syntax::sarif::SarifEmitter;
 use syntax::source_map;
 use syntax::parse::{self, ParseSess};
 use syntax_pos::{MultiSpan, Span};
+use util::item_times::ItemTimes;
 use util::profiling::SelfProfiler;
 
 use rustc_target::spec::{PanicStrategy, RelroLevel, Target, TargetTriple};
@@ -137,6 +139,9 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: Lock<CodeStats>,
 
+    /// The time spent on each item of the crate, gathered for `-Z time-items`.
+    pub item_times: ItemTimes,
+
     next_node_id: OneThread<Cell<ast::NodeId>>,
 
     /// If -zfuel=crate=n is specified, Some(crate).
@@ -830,6 +835,17 @@ impl Session {
         f(&mut profiler);
     }
 
+    /// Renders a query key for the events of `-Z self-profile-events`, if they are recorded.
+    ///
+    /// This must be done before locking the profiler, since printing a key can run queries.
+    pub fn profiler_query_key<K: fmt::Debug>(&self, key: &K) -> Option<String> {
+        if self.opts.debugging_opts.self_profile && self.opts.debugging_opts.self_profile_events {
+            Some(format!("{:?}", key))
+        } else {
+            None
+        }
+    }
+
     pub fn print_profiler_results(&self) {
         let mut profiler = self.self_profiling.borrow_mut();
         profiler.print_results(&self.opts);
@@ -840,6 +856,11 @@ impl Session {
         profiler.save_results(&self.opts);
     }
 
+    pub fn save_profiler_trace(&self, crate_name: &str) {
+        let profiler = self.self_profiling.borrow();
+        profiler.save_trace(crate_name);
+    }
+
     pub fn print_perf_stats(&self) {
         println!(
             "Total time spent computing symbol hashes:      {}",
@@ -1075,6 +1096,12 @@ pub fn build_session_with_source_map(
             (config::ErrorOutputType::Short(_), Some(dst)) => {
                 Box::new(EmitterWriter::new(dst, Some(source_map.clone()), true, false))
             }
+            (config::ErrorOutputType::Sarif(_), None) => Box::new(
+                SarifEmitter::stderr(Some(registry), source_map.clone()),
+            ),
+            (config::ErrorOutputType::Sarif(_), Some(dst)) => Box::new(
+                SarifEmitter::new(dst, Some(registry), source_map.clone()),
+            ),
         };
 
     let diagnostic_handler = errors::Handler::with_emitter_and_flags(
@@ -1136,6 +1163,15 @@ pub fn build_session_(
         CguReuseTracker::new_disabled()
     };
 
+    let item_times = if sopts.debugging_opts.time_items.is_some() {
+        ItemTimes::new()
+    } else {
+        ItemTimes::new_disabled()
+    };
+
+    let record_profiler_events =
+        sopts.debugging_opts.self_profile && sopts.debugging_opts.self_profile_events;
+
     let sess = Session {
         target: target_cfg,
         host,
@@ -1166,7 +1202,7 @@ pub fn build_session_(
         imported_macro_spans: OneThread::new(RefCell::new(FxHashMap::default())),
         incr_comp_session: OneThread::new(RefCell::new(IncrCompSession::NotInitialized)),
         cgu_reuse_tracker,
-        self_profiling: Lock::new(SelfProfiler::new()),
+        self_profiling: Lock::new(SelfProfiler::new(record_profiler_events)),
         profile_channel: Lock::new(None),
         perf_stats: PerfStats {
             symbol_hash_time: Lock::new(Duration::from_secs(0)),
@@ -1176,6 +1212,7 @@ pub fn build_session_(
             normalize_projection_ty: AtomicUsize::new(0),
         },
         code_stats: Default::default(),
+        item_times,
         optimization_fuel_crate,
         optimization_fuel_limit,
         print_fuel_crate,
@@ -1308,6 +1345,11 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
         config::ErrorOutputType::Short(color_config) => {
             Box::new(EmitterWriter::stderr(color_config, None, true, false))
         }
+        // A SARIF log covers the whole compilation and is written by the session's emitter, so
+        // problems found before there is a session are reported in human-readable form instead.
+        config::ErrorOutputType::Sarif(color_config) => {
+            Box::new(EmitterWriter::stderr(color_config, None, false, false))
+        }
     };
     let handler = errors::Handler::with_emitter(true, false, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Fatal);
@@ -1323,6 +1365,10 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
         config::ErrorOutputType::Short(color_config) => {
             Box::new(EmitterWriter::stderr(color_config, None, true, false))
         }
+        // See `early_error`.
+        config::ErrorOutputType::Sarif(color_config) => {
+            Box::new(EmitterWriter::stderr(color_config, None, false, false))
+        }
     };
     let handler = errors::Handler::with_emitter(true, false, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Warning);
@@ -11,7 +11,7 @@
 //! type context book-keeping
 
 use dep_graph::DepGraph;
-use dep_graph::{DepNode, DepConstructor};
+use dep_graph::{DepNode, DepKind, DepConstructor};
 use errors::DiagnosticBuilder;
 use session::Session;
 use session::config::{BorrowckMode, OutputFilenames};
@@ -2000,11 +2000,18 @@ pub mod tls {
     /// This is a callback from libsyntax as it cannot access the implicit state
     /// in librustc otherwise. It is used to when diagnostic messages are
     /// emitted and stores them in the current query, if there is one.
+    /// Diagnostics emitted outside of queries, like most lints, are stored
+    /// for the crate as a whole when compiling incrementally.
     fn track_diagnostic(diagnostic: &Diagnostic) {
         with_context_opt(|icx| {
             if let Some(icx) = icx {
                 if let Some(ref query) = icx.query {
                     query.diagnostics.lock().push(diagnostic.clone());
+                } else if icx.tcx.dep_graph.is_fully_enabled() {
+                    let krate = DepNode::new_no_params(DepKind::Krate);
+                    let dep_node_index = icx.tcx.dep_graph.dep_node_index_of(&krate);
+                    icx.tcx.queries.on_disk_cache
+                       .store_untracked_diagnostic(dep_node_index, diagnostic);
                 }
             }
         })
@@ -359,6 +359,32 @@ impl<'sess> OnDiskCache<'sess> {
         debug_assert!(prev.is_none());
     }
 
+    /// Store a diagnostic emitted outside of any query during the current
+    /// compilation session, like the lints of `librustc_lint`. These are
+    /// associated with the `Krate` dep-node, since they can depend on anything
+    /// in the crate.
+    pub fn store_untracked_diagnostic(&self,
+                                      krate_dep_node_index: DepNodeIndex,
+                                      diagnostic: &Diagnostic) {
+        let mut current_diagnostics = self.current_diagnostics.borrow_mut();
+        let diagnostics = current_diagnostics.entry(krate_dep_node_index)
+                                             .or_default();
+
+        // The diagnostics replayed from the previous session and the ones
+        // emitted again by this session are tracked alike.
+        if !diagnostics.contains(diagnostic) {
+            diagnostics.push(diagnostic.clone());
+        }
+    }
+
+    /// Returns the dep-nodes of the previous compilation session that have
+    /// diagnostics associated with them, in the order they were created in.
+    pub fn prev_diagnostics_indices(&self) -> Vec<SerializedDepNodeIndex> {
+        let mut indices: Vec<_> = self.prev_diagnostics_index.keys().cloned().collect();
+        indices.sort_unstable();
+        indices
+    }
+
     /// Returns the cached query result if there is something in the cache for
     /// the given SerializedDepNodeIndex. Otherwise returns None.
     pub fn try_load_query_result<'tcx, T>(&self,
@@ -387,7 +387,11 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
 
         if dep_node.kind.is_anon() {
             profq_msg!(self, ProfileQueriesMsg::ProviderBegin);
-            self.sess.profiler(|p| p.start_activity(Q::CATEGORY));
+            let profiler_key = self.sess.profiler_query_key(&key);
+            self.sess.profiler(|p| {
+                p.start_activity(Q::CATEGORY);
+                p.start_query(Q::NAME, Q::CATEGORY, profiler_key);
+            });
 
             let res = job.start(self, |tcx| {
                 tcx.dep_graph.with_anon_task(dep_node.kind, || {
@@ -395,7 +399,10 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                 })
             });
 
-            self.sess.profiler(|p| p.end_activity(Q::CATEGORY));
+            self.sess.profiler(|p| {
+                p.end_query(Q::NAME, Q::CATEGORY);
+                p.end_activity(Q::CATEGORY);
+            });
             profq_msg!(self, ProfileQueriesMsg::ProviderEnd);
             let ((result, dep_node_index), diagnostics) = res;
 
@@ -472,6 +479,8 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
             // The diagnostics for this query have already been
             // promoted to the current session during
             // try_mark_green(), so we can ignore them here.
+            let profiler_key = self.sess.profiler_query_key(&key);
+            self.sess.profiler(|p| p.start_query(Q::NAME, Q::CATEGORY, profiler_key));
             let (result, _) = job.start(self, |tcx| {
                 // The dep-graph for this computation is already in
                 // place
@@ -479,6 +488,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                     Q::compute(tcx, key)
                 })
             });
+            self.sess.profiler(|p| p.end_query(Q::NAME, Q::CATEGORY));
             result
         };
 
@@ -535,9 +545,11 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                 key, dep_node);
 
         profq_msg!(self, ProfileQueriesMsg::ProviderBegin);
+        let profiler_key = self.sess.profiler_query_key(&key);
         self.sess.profiler(|p| {
             p.start_activity(Q::CATEGORY);
             p.record_query(Q::CATEGORY);
+            p.start_query(Q::NAME, Q::CATEGORY, profiler_key);
         });
 
         let res = job.start(self, |tcx| {
@@ -554,7 +566,10 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
             }
         });
 
-        self.sess.profiler(|p| p.end_activity(Q::CATEGORY));
+        self.sess.profiler(|p| {
+            p.end_query(Q::NAME, Q::CATEGORY);
+            p.end_activity(Q::CATEGORY);
+        });
         profq_msg!(self, ProfileQueriesMsg::ProviderEnd);
 
         let ((result, dep_node_index), diagnostics) = res;
@@ -265,7 +265,7 @@ pub fn record_time<T, F>(accu: &Lock<Duration>, f: F) -> T where
 
 // Memory reporting
 #[cfg(unix)]
-fn get_resident() -> Option<usize> {
+pub fn get_resident() -> Option<usize> {
     use std::fs;
 
     let field = 1;
@@ -277,7 +277,7 @@ fn get_resident() -> Option<usize> {
 }
 
 #[cfg(windows)]
-fn get_resident() -> Option<usize> {
+pub fn get_resident() -> Option<usize> {
     type BOOL = i32;
     type DWORD = u32;
     type HANDLE = *mut u8;
@@ -0,0 +1,353 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Attributing the time the compiler spends to the items of the crate, for `-Z time-items`.
+//!
+//! Type checking, borrow checking and MIR optimization are measured around the queries doing them
+//! for an item. Time spent on other measured items while one is running, like the type checking
+//! of its closures, is not counted for it, so every item only gets the time of its own work.
+//! Generating LLVM IR is measured for each mono item, and counted for the item it is an instance
+//! of. LLVM works on whole codegen units, on other threads, so the time it spends on a codegen
+//! unit is split between its items in proportion to their estimated sizes.
+//!
+//! Along with the time, the growth of the resident set of the process is recorded, except for
+//! LLVM. This is a rough measure: memory freed by one item can be reused by the next one, so the
+//! growth can also be negative.
+
+use hir::def_id::DefId;
+use hir::json::span_to_json;
+use session::Session;
+use ty::TyCtxt;
+use util::common::{duration_to_secs_str, get_resident};
+use util::nodemap::FxHashMap;
+
+use serialize::json::{object, Json, ToJson};
+use std::cmp;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+use syntax_pos::{Span, DUMMY_SP};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ItemTimeCategory {
+    Typeck,
+    Borrowck,
+    MirOptimization,
+    Codegen,
+    Llvm,
+}
+
+const CATEGORIES: [ItemTimeCategory; 5] = [
+    ItemTimeCategory::Typeck,
+    ItemTimeCategory::Borrowck,
+    ItemTimeCategory::MirOptimization,
+    ItemTimeCategory::Codegen,
+    ItemTimeCategory::Llvm,
+];
+
+impl ItemTimeCategory {
+    fn name(self) -> &'static str {
+        match self {
+            ItemTimeCategory::Typeck => "typeck",
+            ItemTimeCategory::Borrowck => "borrowck",
+            ItemTimeCategory::MirOptimization => "mir_opt",
+            ItemTimeCategory::Codegen => "codegen",
+            ItemTimeCategory::Llvm => "llvm",
+        }
+    }
+}
+
+struct ItemData {
+    description: String,
+    span: SendSpan,
+    times: [Duration; 5],
+    /// How much the resident set grew while the item was worked on, in bytes.
+    memory: i64,
+}
+
+impl ItemData {
+    fn total_time(&self) -> Duration {
+        self.times.iter().fold(Duration::from_secs(0), |total, &time| total + time)
+    }
+}
+
+/// An item that is being worked on.
+struct Measurement {
+    start: Instant,
+    start_resident: Option<usize>,
+    /// The time and memory already counted for the items measured while this one is running.
+    nested_time: Duration,
+    nested_memory: i64,
+}
+
+struct ItemTimesData {
+    items: FxHashMap<DefId, ItemData>,
+    /// The items being worked on by each thread, innermost last.
+    measurements: FxHashMap<ThreadId, Vec<Measurement>>,
+    /// The items LLVM IR was generated for in each codegen unit, with their estimated sizes.
+    cgu_items: FxHashMap<String, Vec<(DefId, usize)>>,
+    /// The time LLVM spent on each codegen unit.
+    llvm_times: FxHashMap<String, Duration>,
+}
+
+// Span does not implement `Send`, which the data has to be to be shared with the codegen
+// worker threads. Like in `CguReuseTracker`, the spans are only ever accessed from the main
+// thread, so it is fine to mark them explicitly as `Send`.
+struct SendSpan(Span);
+unsafe impl Send for SendSpan {}
+
+#[derive(Clone)]
+pub struct ItemTimes {
+    data: Option<Arc<Mutex<ItemTimesData>>>,
+}
+
+impl ItemTimes {
+    pub fn new() -> ItemTimes {
+        let data = ItemTimesData {
+            items: Default::default(),
+            measurements: Default::default(),
+            cgu_items: Default::default(),
+            llvm_times: Default::default(),
+        };
+
+        ItemTimes {
+            data: Some(Arc::new(Mutex::new(data))),
+        }
+    }
+
+    pub fn new_disabled() -> ItemTimes {
+        ItemTimes {
+            data: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.data.is_some()
+    }
+
+    fn is_known(&self, def_id: DefId) -> bool {
+        match self.data {
+            Some(ref data) => data.lock().unwrap().items.contains_key(&def_id),
+            None => false,
+        }
+    }
+
+    /// Starts a measurement on the current thread. This reads the resident set, so it is only
+    /// called if the items are timed.
+    fn start(&self) {
+        if let Some(ref data) = self.data {
+            let measurement = Measurement {
+                start: Instant::now(),
+                start_resident: get_resident(),
+                nested_time: Duration::from_secs(0),
+                nested_memory: 0,
+            };
+            data.lock()
+                .unwrap()
+                .measurements
+                .entry(thread::current().id())
+                .or_default()
+                .push(measurement);
+        }
+    }
+
+    /// Ends the innermost measurement of the current thread, counting it for `def_id`.
+    /// `description` is only used if the item has not been measured before.
+    fn end(&self,
+           category: ItemTimeCategory,
+           def_id: DefId,
+           description: Option<(String, Span)>) {
+        let data = match self.data {
+            Some(ref data) => data,
+            None => return,
+        };
+
+        let end_resident = get_resident();
+        let mut data = data.lock().unwrap();
+        let ItemTimesData { ref mut items, ref mut measurements, .. } = *data;
+
+        let measurements = measurements.get_mut(&thread::current().id()).unwrap();
+        let measurement = measurements.pop().unwrap();
+        let time = measurement.start.elapsed();
+        let memory = match (measurement.start_resident, end_resident) {
+            (Some(start), Some(end)) => end as i64 - start as i64,
+            _ => 0,
+        };
+        if let Some(parent) = measurements.last_mut() {
+            parent.nested_time += time;
+            parent.nested_memory += memory;
+        }
+
+        let item = items.entry(def_id).or_insert_with(|| {
+            let (description, span) = description.unwrap_or_else(|| {
+                (format!("{:?}", def_id), DUMMY_SP)
+            });
+            ItemData {
+                description,
+                span: SendSpan(span),
+                times: Default::default(),
+                memory: 0,
+            }
+        });
+        item.times[category as usize] +=
+            time.checked_sub(measurement.nested_time).unwrap_or(Duration::from_secs(0));
+        item.memory += memory - measurement.nested_memory;
+    }
+
+    /// Records the items of a codegen unit, with their estimated sizes, so that the time LLVM
+    /// spends on it can be split between them.
+    pub fn record_cgu_items(&self, cgu_name: &str, items: Vec<(DefId, usize)>) {
+        if let Some(ref data) = self.data {
+            data.lock().unwrap().cgu_items.insert(cgu_name.to_string(), items);
+        }
+    }
+
+    pub fn record_llvm_time(&self, cgu_name: &str, time: Duration) {
+        if let Some(ref data) = self.data {
+            *data.lock().unwrap().llvm_times.entry(cgu_name.to_string()).or_default() += time;
+        }
+    }
+
+    /// Splits the time LLVM spent on each codegen unit between its items, and returns the items
+    /// that took the longest, along with the LLVM time that could not be split. That is the time
+    /// spent on codegen units that were not generated in this session, like the ones merged by
+    /// fat LTO.
+    fn top_items(&self, count: usize) -> (Vec<ItemData>, Duration) {
+        let data = match self.data {
+            Some(ref data) => data,
+            None => return (vec![], Duration::from_secs(0)),
+        };
+        let mut data = data.lock().unwrap();
+        let ItemTimesData { ref mut items, ref cgu_items, ref llvm_times, .. } = *data;
+
+        let mut unattributed = Duration::from_secs(0);
+        for (cgu_name, &time) in llvm_times {
+            let cgu_items = match cgu_items.get(cgu_name) {
+                Some(cgu_items) if !cgu_items.is_empty() => cgu_items,
+                _ => {
+                    unattributed += time;
+                    continue;
+                }
+            };
+            // Items without any statements are still worth something.
+            let total_size: u64 = cgu_items.iter().map(|&(_, size)| cmp::max(size, 1) as u64).sum();
+            let nanos = time.as_secs() * 1_000_000_000 + time.subsec_nanos() as u64;
+            for &(def_id, size) in cgu_items {
+                if let Some(item) = items.get_mut(&def_id) {
+                    let share = nanos as f64 * cmp::max(size, 1) as f64 / total_size as f64;
+                    item.times[ItemTimeCategory::Llvm as usize] +=
+                        Duration::from_nanos(share as u64);
+                }
+            }
+        }
+
+        let mut top_items: Vec<_> = items.drain().map(|(_, item)| item).collect();
+        top_items.sort_by(|a, b| {
+            b.total_time().cmp(&a.total_time()).then_with(|| a.description.cmp(&b.description))
+        });
+        top_items.truncate(count);
+        (top_items, unattributed)
+    }
+
+    /// Prints the `count` items that took the longest to compile, for `-Z time-items`.
+    pub fn print(&self, sess: &Session, count: usize) {
+        let (items, unattributed) = self.top_items(count);
+        println!("time-items: the {} items that took the longest to compile", items.len());
+        for item in &items {
+            let times: Vec<_> = CATEGORIES.iter().map(|&category| {
+                let time = item.times[category as usize];
+                format!("{} {}s", category.name(), duration_to_secs_str(time))
+            }).collect();
+            println!("time-items: {}s {} ({})",
+                     duration_to_secs_str(item.total_time()),
+                     item.description,
+                     sess.source_map().span_to_string(item.span.0));
+            println!("time-items:     {}, memory {:+} kB", times.join(", "), item.memory / 1024);
+        }
+        if unattributed > Duration::from_secs(0) {
+            println!("time-items: {}s of LLVM time could not be attributed to items",
+                     duration_to_secs_str(unattributed));
+        }
+    }
+
+    /// Prints the report of `print` as JSON, for `-Z time-items-json`.
+    pub fn print_json(&self, sess: &Session, count: usize) {
+        let (items, unattributed) = self.top_items(count);
+        let items = items.iter().map(|item| {
+            let times = CATEGORIES.iter().map(|&category| {
+                (category.name(), secs(item.times[category as usize]).to_json())
+            }).collect();
+            object(vec![
+                ("item", item.description.to_json()),
+                ("span", span_to_json(sess.source_map(), item.span.0)),
+                ("total", secs(item.total_time()).to_json()),
+                ("times", object(times)),
+                ("memory", item.memory.to_json()),
+            ])
+        }).collect();
+        let report = object(vec![
+            ("items", Json::Array(items)),
+            ("unattributed_llvm", secs(unattributed).to_json()),
+        ]);
+        println!("{}", report.pretty());
+    }
+}
+
+/// Counts the time until it is dropped for an item, see `TyCtxt::item_timer`.
+pub struct ItemTimer {
+    /// `None` if `-Z time-items` is not passed, in which case nothing is measured.
+    measurement: Option<(ItemTimes, ItemTimeCategory, DefId, Option<(String, Span)>)>,
+}
+
+impl Drop for ItemTimer {
+    fn drop(&mut self) {
+        if let Some((item_times, category, def_id, description)) = self.measurement.take() {
+            item_times.end(category, def_id, description);
+        }
+    }
+}
+
+impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
+    /// Starts measuring the work of `category` for `def_id` if `-Z time-items` is passed. The
+    /// time is counted for the item when the returned timer is dropped.
+    pub fn item_timer(self, category: ItemTimeCategory, def_id: DefId) -> ItemTimer {
+        let item_times = &self.sess.item_times;
+        // Neither the clock nor the resident set are sampled unless the items are timed.
+        if !item_times.is_enabled() {
+            return ItemTimer { measurement: None };
+        }
+
+        let description = if item_times.is_known(def_id) {
+            None
+        } else {
+            // This can run in the middle of a query, which must not depend on the lookups.
+            Some(self.dep_graph.with_ignore(|| {
+                (self.item_path_str(def_id), self.def_span(def_id))
+            }))
+        };
+        item_times.start();
+        ItemTimer {
+            measurement: Some((item_times.clone(), category, def_id, description)),
+        }
+    }
+
+    /// Runs `f`, which does the work of `category` for `def_id`, and counts the time it takes for
+    /// the item if `-Z time-items` is passed.
+    pub fn time_item<F, R>(self, category: ItemTimeCategory, def_id: DefId, f: F) -> R
+        where F: FnOnce() -> R
+    {
+        let _timer = self.item_timer(category, def_id);
+        f()
+    }
+}
+
+fn secs(time: Duration) -> f64 {
+    time.as_secs() as f64 + time.subsec_nanos() as f64 / 1_000_000_000.0
+}
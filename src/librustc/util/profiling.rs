@@ -12,7 +12,12 @@ use session::config::Options;
 
 use std::fs;
 use std::io::{self, StdoutLock, Write};
-use std::time::Instant;
+use std::process;
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+use serialize::json::as_json;
+use util::nodemap::FxHashMap;
 
 macro_rules! define_categories {
     ($($name:ident,)*) => {
@@ -121,18 +126,47 @@ define_categories! {
     Other,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EventKind {
+    Start,
+    End,
+}
+
+/// The start or the end of a query invocation, recorded with `-Z self-profile-events`.
+struct ProfilerEvent {
+    kind: EventKind,
+    query: &'static str,
+    category: ProfileCategory,
+    /// The query key rendered with `Debug`, only recorded for start events.
+    key: Option<String>,
+    /// The index of the thread in `SelfProfiler::threads`.
+    thread: usize,
+    /// The time since the profiler was created.
+    time: Duration,
+}
+
 pub struct SelfProfiler {
     timer_stack: Vec<ProfileCategory>,
     data: CategoryData,
     current_timer: Instant,
+    start_time: Instant,
+    /// The recorded query events, or `None` if events are not recorded.
+    events: Option<Vec<ProfilerEvent>>,
+    /// The threads that ran queries, in the order they were first seen.
+    threads: FxHashMap<ThreadId, usize>,
 }
 
 impl SelfProfiler {
-    pub fn new() -> SelfProfiler {
+    /// Creates a profiler; with `record_events`, it also records an event for every start and end
+    /// of a query, to be written out with `save_trace`.
+    pub fn new(record_events: bool) -> SelfProfiler {
         let mut profiler = SelfProfiler {
             timer_stack: Vec::new(),
             data: CategoryData::new(),
             current_timer: Instant::now(),
+            start_time: Instant::now(),
+            events: if record_events { Some(Vec::new()) } else { None },
+            threads: FxHashMap::default(),
         };
 
         profiler.start_activity(ProfileCategory::Other);
@@ -196,6 +230,37 @@ impl SelfProfiler {
         self.data.times.set(category, new_time);
     }
 
+    /// Records that the query `query` started running for `key`, as rendered by
+    /// `Session::profiler_query_key`.
+    pub fn start_query(&mut self,
+                       query: &'static str,
+                       category: ProfileCategory,
+                       key: Option<String>) {
+        if self.events.is_some() {
+            self.record_event(EventKind::Start, query, category, key);
+        }
+    }
+
+    /// Records that the query `query` that was last started on this thread finished.
+    pub fn end_query(&mut self, query: &'static str, category: ProfileCategory) {
+        if self.events.is_some() {
+            self.record_event(EventKind::End, query, category, None);
+        }
+    }
+
+    fn record_event(&mut self,
+                    kind: EventKind,
+                    query: &'static str,
+                    category: ProfileCategory,
+                    key: Option<String>) {
+        let time = self.start_time.elapsed();
+        let thread_count = self.threads.len();
+        let thread = *self.threads.entry(thread::current().id()).or_insert(thread_count);
+        if let Some(ref mut events) = self.events {
+            events.push(ProfilerEvent { kind, query, category, key, thread, time });
+        }
+    }
+
     fn stop_timer(&mut self) -> u64 {
         let elapsed = self.current_timer.elapsed();
 
@@ -245,4 +310,44 @@ impl SelfProfiler {
 
         fs::write("self_profiler_results.json", json).unwrap();
     }
+
+    /// Writes the recorded query events in the Chrome trace event format, which trace viewers
+    /// like `chrome://tracing` can load, to `self_profiler_trace-<crate name>-<pid>.json`.
+    ///
+    /// Every query invocation becomes a slice named after the query, with the key in its
+    /// arguments, and nested in the slices of the queries that called it.
+    pub fn save_trace(&self, crate_name: &str) {
+        let events = match self.events {
+            Some(ref events) => events,
+            None => return,
+        };
+
+        let pid = process::id();
+        let mut json = String::from("{ \"traceEvents\": [");
+        for (i, event) in events.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let micros = event.time.as_secs() as f64 * 1_000_000.0 +
+                         event.time.subsec_nanos() as f64 / 1_000.0;
+            json.push_str(&format!(
+                "\n{{ \"name\": \"{}\", \"cat\": \"{:?}\", \"ph\": \"{}\", \"ts\": {:.3}, \
+                 \"pid\": {}, \"tid\": {}",
+                event.query,
+                event.category,
+                if event.kind == EventKind::Start { "B" } else { "E" },
+                micros,
+                pid,
+                event.thread,
+            ));
+            if let Some(ref key) = event.key {
+                json.push_str(&format!(", \"args\": {{ \"key\": {} }}", as_json(key)));
+            }
+            json.push_str(" }");
+        }
+        json.push_str("\n], \"displayTimeUnit\": \"ms\" }\n");
+
+        let file_name = format!("self_profiler_trace-{}-{}.json", crate_name, pid);
+        fs::write(file_name, json).unwrap();
+    }
 }
@@ -35,6 +35,7 @@ use rustc::middle::cstore::{EncodedMetadata};
 use rustc::ty::TyCtxt;
 use rustc::middle::exported_symbols;
 use rustc::session::config::{self, DebugInfo};
+use rustc::util::item_times::ItemTimeCategory;
 use builder::Builder;
 use common;
 use context::CodegenCx;
@@ -185,7 +186,15 @@ pub fn compile_codegen_unit<'ll, 'tcx>(tcx: TyCtxt<'ll, 'tcx, 'tcx>,
 
             // ... and now that we have everything pre-defined, fill out those definitions.
             for &(mono_item, _) in &mono_items {
-                mono_item.define::<Builder>(&cx);
+                tcx.time_item(ItemTimeCategory::Codegen, mono_item.def_id(tcx), || {
+                    mono_item.define::<Builder>(&cx);
+                });
+            }
+            if tcx.sess.item_times.is_enabled() {
+                let items = mono_items.iter().map(|&(mono_item, _)| {
+                    (mono_item.def_id(tcx), mono_item.size_estimate(&tcx))
+                }).collect();
+                tcx.sess.item_times.record_cgu_items(&cgu_name.as_str(), items);
             }
 
             // If this codegen unit contains the main function, also create the
@@ -25,6 +25,7 @@ use rustc::middle::cstore::EncodedMetadata;
 use rustc::session::config::{self, OutputFilenames, OutputType, Passes, Sanitizer, Lto};
 use rustc::session::Session;
 use rustc::util::nodemap::FxHashMap;
+use rustc::util::item_times::ItemTimes;
 use rustc::util::time_graph::{self, TimeGraph, Timeline};
 use traits::*;
 use rustc::hir::def_id::{CrateNum, LOCAL_CRATE};
@@ -227,6 +228,8 @@ pub struct CodegenContext<B: WriteBackendMethods> {
     pub incr_comp_session_dir: Option<PathBuf>,
     // Used to update CGU re-use information during the thinlto phase.
     pub cgu_reuse_tracker: CguReuseTracker,
+    // Used to record the time LLVM spends on each codegen unit for `-Z time-items`.
+    pub item_times: ItemTimes,
     // Channel back to the main control thread to send messages to
     pub coordinator_send: Sender<Box<dyn Any + Send>>,
     // A reference to the TimeGraph so we can register timings. None means that
@@ -683,7 +686,15 @@ fn execute_work_item<B: ExtraBackendMethods>(
 ) -> Result<WorkItemResult<B::Module>, FatalError> {
     let module_config = cgcx.config(work_item.module_kind());
 
-    match work_item {
+    // Copying a module from the cache is not work LLVM does on its items.
+    let cgu_name = match work_item {
+        WorkItem::Optimize(ref m) => Some(m.name.clone()),
+        WorkItem::CopyPostLtoArtifacts(_) => None,
+        WorkItem::LTO(ref m) => Some(m.name().to_string()),
+    };
+    let start = Instant::now();
+
+    let result = match work_item {
         WorkItem::Optimize(module) => {
             execute_optimize_work_item(cgcx, module, module_config, timeline)
         }
@@ -693,7 +704,12 @@ fn execute_work_item<B: ExtraBackendMethods>(
         WorkItem::LTO(module) => {
             execute_lto_work_item(cgcx, module, module_config, timeline)
         }
+    };
+
+    if let Some(cgu_name) = cgu_name {
+        cgcx.item_times.record_llvm_time(&cgu_name, start.elapsed());
     }
+    result
 }
 
 fn execute_optimize_work_item<B: ExtraBackendMethods>(
@@ -965,6 +981,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
         worker: 0,
         incr_comp_session_dir: sess.incr_comp_session_dir_opt().map(|r| r.clone()),
         cgu_reuse_tracker: sess.cgu_reuse_tracker.clone(),
+        item_times: sess.item_times.clone(),
         coordinator_send,
         diag_emitter: shared_emitter.clone(),
         time_graph,
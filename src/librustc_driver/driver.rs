@@ -154,7 +154,7 @@ pub fn compile_input(
     // We need nested scopes here, because the intermediate results can keep
     // large chunks of memory alive and we want to free them as soon as
     // possible to keep the peak memory usage low
-    let (outputs, ongoing_codegen, dep_graph) = {
+    let (outputs, ongoing_codegen, dep_graph, crate_name) = {
         let krate = match phase_1_parse_input(control, sess, input) {
             Ok(krate) => krate,
             Err(mut parse_error) => {
@@ -339,7 +339,7 @@ pub fn compile_input(
                     }
                 }
 
-                Ok((outputs.clone(), ongoing_codegen, tcx.dep_graph.clone()))
+                Ok((outputs.clone(), ongoing_codegen, tcx.dep_graph.clone(), crate_name.clone()))
             },
         )??
     };
@@ -354,12 +354,24 @@ pub fn compile_input(
         sess.print_perf_stats();
     }
 
+    if let Some(count) = sess.opts.debugging_opts.time_items {
+        if sess.opts.debugging_opts.time_items_json {
+            sess.item_times.print_json(sess, count);
+        } else {
+            sess.item_times.print(sess, count);
+        }
+    }
+
     if sess.opts.debugging_opts.self_profile {
         sess.print_profiler_results();
 
         if sess.opts.debugging_opts.profile_json {
             sess.save_json_results();
         }
+
+        if sess.opts.debugging_opts.self_profile_events {
+            sess.save_profiler_trace(&crate_name);
+        }
     }
 
     controller_entry_point!(
@@ -1352,6 +1364,14 @@ where
 
             time(sess, "lint checking", || lint::check_crate(tcx));
 
+            // The warnings of the previous session that nothing has emitted
+            // again so far follow the ones of this session, before codegen.
+            if tcx.dep_graph.is_fully_enabled() && sess.opts.debugging_opts.incremental_queries {
+                time(sess, "emit previous diagnostics", || {
+                    tcx.dep_graph.emit_previous_diagnostics(tcx)
+                });
+            }
+
             return Ok(f(tcx, analysis, rx, tcx.sess.compile_status()));
         },
     )
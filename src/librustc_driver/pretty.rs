@@ -27,7 +27,7 @@ use rustc_borrowck::graphviz as borrowck_dot;
 use rustc_data_structures::thin_vec::ThinVec;
 use rustc_metadata::cstore::CStore;
 
-use rustc_mir::util::{write_mir_pretty, write_mir_graphviz};
+use rustc_mir::util::{write_mir_pretty, write_mir_graphviz, write_mir_json};
 
 use syntax::ast::{self, BlockCheckMode};
 use syntax::fold::{self, Folder};
@@ -77,9 +77,11 @@ pub enum PpMode {
     PpmSource(PpSourceMode),
     PpmHir(PpSourceMode),
     PpmHirTree(PpSourceMode),
+    PpmHirJson,
     PpmFlowGraph(PpFlowGraphMode),
     PpmMir,
     PpmMirCFG,
+    PpmMirJson,
 }
 
 impl PpMode {
@@ -94,8 +96,10 @@ impl PpMode {
             PpmSource(PpmExpandedHygiene) |
             PpmHir(_) |
             PpmHirTree(_) |
+            PpmHirJson |
             PpmMir |
             PpmMirCFG |
+            PpmMirJson |
             PpmFlowGraph(_) => true,
             PpmSource(PpmTyped) => panic!("invalid state"),
         }
@@ -103,7 +107,7 @@ impl PpMode {
 
     pub fn needs_analysis(&self) -> bool {
         match *self {
-            PpmMir | PpmMirCFG | PpmFlowGraph(_) => true,
+            PpmMir | PpmMirCFG | PpmMirJson | PpmFlowGraph(_) => true,
             _ => false,
         }
     }
@@ -127,8 +131,10 @@ pub fn parse_pretty(sess: &Session,
         ("hir,identified", true) => PpmHir(PpmIdentified),
         ("hir,typed", true) => PpmHir(PpmTyped),
         ("hir-tree", true) => PpmHirTree(PpmNormal),
+        ("hir-json", true) => PpmHirJson,
         ("mir", true) => PpmMir,
         ("mir-cfg", true) => PpmMirCFG,
+        ("mir-json", true) => PpmMirJson,
         ("flowgraph", true) => PpmFlowGraph(PpFlowGraphMode::Default),
         ("flowgraph,unlabelled", true) => PpmFlowGraph(PpFlowGraphMode::UnlabelledEdges),
         _ => {
@@ -136,7 +142,8 @@ pub fn parse_pretty(sess: &Session,
                 sess.fatal(&format!("argument to `unpretty` must be one of `normal`, \
                                      `expanded`, `flowgraph[,unlabelled]=<nodeid>`, \
                                      `identified`, `expanded,identified`, `everybody_loops`, \
-                                     `hir`, `hir,identified`, `hir,typed`, or `mir`; got {}",
+                                     `hir`, `hir,identified`, `hir,typed`, `hir-json`, `mir`, \
+                                     or `mir-json`; got {}",
                                     name));
             } else {
                 sess.fatal(&format!("argument to `pretty` must be one of `normal`, `expanded`, \
@@ -1116,6 +1123,17 @@ pub fn print_after_hir_lowering<'tcx, 'a: 'tcx>(sess: &'a Session,
                 })
             }
 
+            (PpmHirJson, opt_uii) => {
+                let node_ids = opt_uii.map(|uii| {
+                    uii.all_matching_node_ids(hir_map).collect::<Vec<_>>()
+                });
+                hir::json::write_crate_json(hir_map,
+                                            sess.source_map(),
+                                            crate_name,
+                                            node_ids.as_ref().map(|ids| &ids[..]),
+                                            &mut out)
+            }
+
             _ => unreachable!(),
         }
         .unwrap();
@@ -1162,18 +1180,20 @@ fn print_with_analysis<'tcx, 'a: 'tcx>(sess: &'a Session,
                                                      output_filenames,
                                                      |tcx, _, _, _| {
         match ppm {
-            PpmMir | PpmMirCFG => {
+            PpmMir | PpmMirCFG | PpmMirJson => {
                 if let Some(nodeid) = nodeid {
                     let def_id = tcx.hir.local_def_id(nodeid);
                     match ppm {
                         PpmMir => write_mir_pretty(tcx, Some(def_id), &mut out),
                         PpmMirCFG => write_mir_graphviz(tcx, Some(def_id), &mut out),
+                        PpmMirJson => write_mir_json(tcx, Some(def_id), &mut out),
                         _ => unreachable!(),
                     }?;
                 } else {
                     match ppm {
                         PpmMir => write_mir_pretty(tcx, None, &mut out),
                         PpmMirCFG => write_mir_graphviz(tcx, None, &mut out),
+                        PpmMirJson => write_mir_json(tcx, None, &mut out),
                         _ => unreachable!(),
                     }?;
                 }
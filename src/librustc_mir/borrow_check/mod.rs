@@ -24,6 +24,7 @@ use rustc::mir::{Field, Projection, ProjectionElem, Rvalue, Statement, Statement
 use rustc::mir::{Terminator, TerminatorKind};
 use rustc::ty::query::Providers;
 use rustc::ty::{self, TyCtxt};
+use rustc::util::item_times::ItemTimeCategory;
 
 use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder, Level};
 use rustc_data_structures::bit_set::BitSet;
@@ -118,9 +119,11 @@ fn mir_borrowck<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> BorrowC
         };
     }
 
-    let opt_closure_req = tcx.infer_ctxt().enter(|infcx| {
-        let input_mir: &Mir = &input_mir.borrow();
-        do_mir_borrowck(&infcx, input_mir, def_id)
+    let opt_closure_req = tcx.time_item(ItemTimeCategory::Borrowck, def_id, || {
+        tcx.infer_ctxt().enter(|infcx| {
+            let input_mir: &Mir = &input_mir.borrow();
+            do_mir_borrowck(&infcx, input_mir, def_id)
+        })
     });
     debug!("mir_borrowck done");
 
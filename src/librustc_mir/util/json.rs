@@ -0,0 +1,431 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A JSON serialization of MIR bodies, written by `-Z unpretty=mir-json` for tools that analyze
+//! MIR outside of the compiler.
+//!
+//! Unlike the textual MIR, which is meant for humans, this follows a schema that is documented
+//! in the unstable book, in `compiler-flags/unpretty.md`. Locals, source scopes and basic
+//! blocks are referred to by their index in the arrays of their body. Types, regions and
+//! constants are written the way the compiler displays them.
+
+use rustc::hir;
+use rustc::hir::json::span_to_json;
+use rustc::hir::def_id::{DefId, LOCAL_CRATE};
+use rustc::mir::*;
+use rustc::ty::{TyCtxt, item_path};
+use rustc_data_structures::indexed_vec::Idx;
+use rustc_serialize::json::{object, Json, ToJson};
+use std::io::{self, Write};
+use syntax_pos::Span;
+use super::pretty::dump_mir_def_ids;
+use transform::MirSource;
+
+/// The version of the schema, bumped whenever it changes in an incompatible way.
+const SCHEMA_VERSION: u64 = 1;
+
+/// Write the MIR of the given item, or of all the items of the crate, as a JSON document.
+pub fn write_mir_json<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    single: Option<DefId>,
+    w: &mut dyn Write,
+) -> io::Result<()> {
+    let mut bodies = vec![];
+    for def_id in dump_mir_def_ids(tcx, single) {
+        let mir = &tcx.optimized_mir(def_id);
+        bodies.push(MirJson { tcx, mir }.body(MirSource::item(def_id)));
+
+        for (i, mir) in mir.promoted.iter_enumerated() {
+            let src = MirSource {
+                def_id,
+                promoted: Some(i),
+            };
+            bodies.push(MirJson { tcx, mir }.body(src));
+        }
+    }
+
+    let crate_name = tcx.crate_name(LOCAL_CRATE);
+    let document = object(vec![
+        ("version", SCHEMA_VERSION.to_json()),
+        ("crate", crate_name.to_string().to_json()),
+        ("bodies", Json::Array(bodies)),
+    ]);
+    writeln!(w, "{}", document.pretty())
+}
+
+struct MirJson<'a, 'gcx: 'tcx, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &'a Mir<'tcx>,
+}
+
+impl<'a, 'gcx, 'tcx> MirJson<'a, 'gcx, 'tcx> {
+    fn body(&self, src: MirSource) -> Json {
+        let mir = self.mir;
+        let id = self.tcx.hir.as_local_node_id(src.def_id).unwrap();
+        let kind = match (self.tcx.hir.body_owner_kind(id), src.promoted) {
+            (_, Some(_)) => "promoted",
+            (hir::BodyOwnerKind::Fn, _) => "fn",
+            (hir::BodyOwnerKind::Const, _) => "const",
+            (hir::BodyOwnerKind::Static(hir::MutImmutable), _) => "static",
+            (hir::BodyOwnerKind::Static(hir::MutMutable), _) => "static mut",
+        };
+        // see notes on #41697 in `pretty.rs`
+        let def_path = item_path::with_forced_impl_filename_line(|| {
+            self.tcx.item_path_str(src.def_id)
+        });
+
+        let source_scopes = mir.source_scopes.iter().map(|scope| {
+            object(vec![
+                ("parent", scope.parent_scope.map(|parent| parent.index()).to_json()),
+                ("span", self.span(scope.span)),
+            ])
+        }).collect();
+
+        let locals = mir.local_decls.iter_enumerated().map(|(local, decl)| {
+            let kind = match mir.local_kind(local) {
+                LocalKind::ReturnPointer => "return",
+                LocalKind::Arg => "arg",
+                LocalKind::Var => "var",
+                LocalKind::Temp => "temp",
+            };
+            object(vec![
+                ("kind", kind.to_json()),
+                ("name", decl.name.map(|name| name.to_string()).to_json()),
+                ("ty", decl.ty.to_string().to_json()),
+                ("mutable", (decl.mutability == Mutability::Mut).to_json()),
+                ("source_info", self.source_info(decl.source_info)),
+                ("visibility_scope", decl.visibility_scope.index().to_json()),
+            ])
+        }).collect();
+
+        let basic_blocks = mir.basic_blocks().iter().map(|data| {
+            object(vec![
+                ("is_cleanup", data.is_cleanup.to_json()),
+                ("statements", Json::Array(data.statements.iter()
+                                                          .map(|s| self.statement(s))
+                                                          .collect())),
+                ("terminator", self.terminator(data.terminator())),
+            ])
+        }).collect();
+
+        object(vec![
+            ("def_path", def_path.to_json()),
+            ("kind", kind.to_json()),
+            ("promoted", src.promoted.map(|promoted| promoted.index()).to_json()),
+            ("span", self.span(mir.span)),
+            ("arg_count", mir.arg_count.to_json()),
+            ("return_ty", mir.return_ty().to_string().to_json()),
+            ("yield_ty", mir.yield_ty.map(|ty| ty.to_string()).to_json()),
+            ("source_scopes", Json::Array(source_scopes)),
+            ("locals", Json::Array(locals)),
+            ("basic_blocks", Json::Array(basic_blocks)),
+        ])
+    }
+
+    fn statement(&self, statement: &Statement<'tcx>) -> Json {
+        let (kind, mut fields) = match statement.kind {
+            StatementKind::Assign(ref place, ref rvalue) => {
+                ("Assign", vec![("place", self.place(place)), ("rvalue", self.rvalue(rvalue))])
+            }
+            StatementKind::FakeRead(cause, ref place) => {
+                ("FakeRead", vec![
+                    ("cause", format!("{:?}", cause).to_json()),
+                    ("place", self.place(place)),
+                ])
+            }
+            StatementKind::SetDiscriminant { ref place, variant_index } => {
+                ("SetDiscriminant", vec![
+                    ("place", self.place(place)),
+                    ("variant_index", variant_index.index().to_json()),
+                ])
+            }
+            StatementKind::StorageLive(local) => {
+                ("StorageLive", vec![("local", local.index().to_json())])
+            }
+            StatementKind::StorageDead(local) => {
+                ("StorageDead", vec![("local", local.index().to_json())])
+            }
+            StatementKind::InlineAsm { ref outputs, ref inputs, .. } => {
+                ("InlineAsm", vec![
+                    ("outputs", Json::Array(outputs.iter().map(|p| self.place(p)).collect())),
+                    ("inputs", Json::Array(inputs.iter().map(|&(_, ref o)| self.operand(o))
+                                                        .collect())),
+                ])
+            }
+            StatementKind::Retag { fn_entry, ref place } => {
+                ("Retag", vec![("fn_entry", fn_entry.to_json()), ("place", self.place(place))])
+            }
+            StatementKind::EscapeToRaw(ref operand) => {
+                ("EscapeToRaw", vec![("operand", self.operand(operand))])
+            }
+            StatementKind::EndRegion(_) => ("EndRegion", vec![]),
+            StatementKind::AscribeUserType(ref place, variance, _) => {
+                ("AscribeUserType", vec![
+                    ("place", self.place(place)),
+                    ("variance", format!("{:?}", variance).to_json()),
+                ])
+            }
+            StatementKind::Nop => ("Nop", vec![]),
+        };
+
+        fields.insert(0, ("kind", kind.to_json()));
+        fields.push(("source_info", self.source_info(statement.source_info)));
+        fields.push(("text", format!("{:?}", statement).to_json()));
+        object(fields)
+    }
+
+    fn terminator(&self, terminator: &Terminator<'tcx>) -> Json {
+        let (kind, mut fields) = match terminator.kind {
+            TerminatorKind::Goto { .. } => ("Goto", vec![]),
+            TerminatorKind::SwitchInt { ref discr, switch_ty, ref values, .. } => {
+                // The values do not all fit into JSON numbers.
+                let values = values.iter().map(|value| value.to_string().to_json()).collect();
+                ("SwitchInt", vec![
+                    ("discr", self.operand(discr)),
+                    ("switch_ty", switch_ty.to_string().to_json()),
+                    ("values", Json::Array(values)),
+                ])
+            }
+            TerminatorKind::Resume => ("Resume", vec![]),
+            TerminatorKind::Abort => ("Abort", vec![]),
+            TerminatorKind::Return => ("Return", vec![]),
+            TerminatorKind::Unreachable => ("Unreachable", vec![]),
+            TerminatorKind::Drop { ref location, .. } => {
+                ("Drop", vec![("place", self.place(location))])
+            }
+            TerminatorKind::DropAndReplace { ref location, ref value, .. } => {
+                ("DropAndReplace", vec![
+                    ("place", self.place(location)),
+                    ("value", self.operand(value)),
+                ])
+            }
+            TerminatorKind::Call { ref func, ref args, ref destination, .. } => {
+                ("Call", vec![
+                    ("func", self.operand(func)),
+                    ("args", Json::Array(args.iter().map(|arg| self.operand(arg)).collect())),
+                    ("destination", destination.as_ref()
+                                               .map_or(Json::Null, |&(ref p, _)| self.place(p))),
+                ])
+            }
+            TerminatorKind::Assert { ref cond, expected, ref msg, .. } => {
+                ("Assert", vec![
+                    ("cond", self.operand(cond)),
+                    ("expected", expected.to_json()),
+                    ("message", msg.description().to_json()),
+                ])
+            }
+            TerminatorKind::Yield { ref value, .. } => {
+                ("Yield", vec![("value", self.operand(value))])
+            }
+            TerminatorKind::GeneratorDrop => ("GeneratorDrop", vec![]),
+            TerminatorKind::FalseEdges { .. } => ("FalseEdges", vec![]),
+            TerminatorKind::FalseUnwind { .. } => ("FalseUnwind", vec![]),
+        };
+
+        let labels = terminator.kind.fmt_successor_labels();
+        let successors = terminator.successors().zip(labels).map(|(target, label)| {
+            object(vec![
+                ("target", target.index().to_json()),
+                ("label", label.to_json()),
+            ])
+        }).collect();
+
+        let mut text = String::new();
+        terminator.kind.fmt_head(&mut text).unwrap();
+
+        fields.insert(0, ("kind", kind.to_json()));
+        fields.push(("successors", Json::Array(successors)));
+        fields.push(("source_info", self.source_info(terminator.source_info)));
+        fields.push(("text", text.to_json()));
+        object(fields)
+    }
+
+    fn rvalue(&self, rvalue: &Rvalue<'tcx>) -> Json {
+        let (kind, mut fields) = match *rvalue {
+            Rvalue::Use(ref operand) => ("Use", vec![("operand", self.operand(operand))]),
+            Rvalue::Repeat(ref operand, count) => {
+                ("Repeat", vec![("operand", self.operand(operand)), ("count", count.to_json())])
+            }
+            Rvalue::Ref(region, borrow_kind, ref place) => {
+                ("Ref", vec![
+                    ("region", region.to_string().to_json()),
+                    ("borrow_kind", format!("{:?}", borrow_kind).to_json()),
+                    ("place", self.place(place)),
+                ])
+            }
+            Rvalue::Len(ref place) => ("Len", vec![("place", self.place(place))]),
+            Rvalue::Cast(cast_kind, ref operand, ty) => {
+                ("Cast", vec![
+                    ("cast_kind", format!("{:?}", cast_kind).to_json()),
+                    ("operand", self.operand(operand)),
+                    ("target_ty", ty.to_string().to_json()),
+                ])
+            }
+            Rvalue::BinaryOp(op, ref lhs, ref rhs) => {
+                ("BinaryOp", vec![
+                    ("op", format!("{:?}", op).to_json()),
+                    ("lhs", self.operand(lhs)),
+                    ("rhs", self.operand(rhs)),
+                ])
+            }
+            Rvalue::CheckedBinaryOp(op, ref lhs, ref rhs) => {
+                ("CheckedBinaryOp", vec![
+                    ("op", format!("{:?}", op).to_json()),
+                    ("lhs", self.operand(lhs)),
+                    ("rhs", self.operand(rhs)),
+                ])
+            }
+            Rvalue::NullaryOp(op, ty) => {
+                ("NullaryOp", vec![
+                    ("op", format!("{:?}", op).to_json()),
+                    ("operand_ty", ty.to_string().to_json()),
+                ])
+            }
+            Rvalue::UnaryOp(op, ref operand) => {
+                ("UnaryOp", vec![
+                    ("op", format!("{:?}", op).to_json()),
+                    ("operand", self.operand(operand)),
+                ])
+            }
+            Rvalue::Discriminant(ref place) => ("Discriminant", vec![("place", self.place(place))]),
+            Rvalue::Aggregate(ref aggregate_kind, ref operands) => {
+                ("Aggregate", vec![
+                    ("aggregate_kind", self.aggregate_kind(aggregate_kind)),
+                    ("operands", Json::Array(operands.iter().map(|o| self.operand(o)).collect())),
+                ])
+            }
+        };
+
+        fields.insert(0, ("kind", kind.to_json()));
+        fields.push(("ty", rvalue.ty(self.mir, self.tcx).to_string().to_json()));
+        object(fields)
+    }
+
+    fn aggregate_kind(&self, aggregate_kind: &AggregateKind<'tcx>) -> Json {
+        let (kind, mut fields) = match *aggregate_kind {
+            AggregateKind::Array(ty) => ("Array", vec![("element_ty", ty.to_string().to_json())]),
+            AggregateKind::Tuple => ("Tuple", vec![]),
+            AggregateKind::Adt(adt_def, variant_index, _, _, active_field) => {
+                ("Adt", vec![
+                    ("def_path", self.tcx.item_path_str(adt_def.did).to_json()),
+                    ("variant", adt_def.variants[variant_index].name.to_string().to_json()),
+                    ("variant_index", variant_index.index().to_json()),
+                    ("active_field", active_field.to_json()),
+                ])
+            }
+            AggregateKind::Closure(def_id, _) => {
+                ("Closure", vec![("def_path", self.tcx.item_path_str(def_id).to_json())])
+            }
+            AggregateKind::Generator(def_id, ..) => {
+                ("Generator", vec![("def_path", self.tcx.item_path_str(def_id).to_json())])
+            }
+        };
+
+        fields.insert(0, ("kind", kind.to_json()));
+        object(fields)
+    }
+
+    fn operand(&self, operand: &Operand<'tcx>) -> Json {
+        let (kind, place) = match *operand {
+            Operand::Copy(ref place) => ("Copy", self.place(place)),
+            Operand::Move(ref place) => ("Move", self.place(place)),
+            Operand::Constant(ref constant) => {
+                return object(vec![
+                    ("kind", "Constant".to_json()),
+                    ("ty", constant.ty.to_string().to_json()),
+                    ("span", self.span(constant.span)),
+                    ("text", format!("{:?}", constant).to_json()),
+                ]);
+            }
+        };
+        object(vec![("kind", kind.to_json()), ("place", place)])
+    }
+
+    fn place(&self, place: &Place<'tcx>) -> Json {
+        // The projections are applied from the base outwards, which is the inverse of how they
+        // are nested.
+        let mut projections = vec![];
+        let mut base = place;
+        while let Place::Projection(ref projection) = *base {
+            projections.push(self.projection_elem(&projection.elem));
+            base = &projection.base;
+        }
+        projections.reverse();
+
+        let base = match *base {
+            Place::Local(local) => {
+                object(vec![("kind", "Local".to_json()), ("local", local.index().to_json())])
+            }
+            Place::Static(ref static_) => {
+                object(vec![
+                    ("kind", "Static".to_json()),
+                    ("def_path", self.tcx.item_path_str(static_.def_id).to_json()),
+                ])
+            }
+            Place::Promoted(ref promoted) => {
+                object(vec![
+                    ("kind", "Promoted".to_json()),
+                    ("promoted", promoted.0.index().to_json()),
+                ])
+            }
+            Place::Projection(..) => unreachable!(),
+        };
+
+        object(vec![
+            ("base", base),
+            ("projections", Json::Array(projections)),
+            ("ty", place.ty(self.mir, self.tcx).to_ty(self.tcx).to_string().to_json()),
+            ("text", format!("{:?}", place).to_json()),
+        ])
+    }
+
+    fn projection_elem(&self, elem: &PlaceElem<'tcx>) -> Json {
+        let (kind, mut fields) = match *elem {
+            ProjectionElem::Deref => ("Deref", vec![]),
+            ProjectionElem::Field(field, ty) => {
+                ("Field", vec![
+                    ("field", field.index().to_json()),
+                    ("ty", ty.to_string().to_json()),
+                ])
+            }
+            ProjectionElem::Index(local) => ("Index", vec![("local", local.index().to_json())]),
+            ProjectionElem::ConstantIndex { offset, min_length, from_end } => {
+                ("ConstantIndex", vec![
+                    ("offset", offset.to_json()),
+                    ("min_length", min_length.to_json()),
+                    ("from_end", from_end.to_json()),
+                ])
+            }
+            ProjectionElem::Subslice { from, to } => {
+                ("Subslice", vec![("from", from.to_json()), ("to", to.to_json())])
+            }
+            ProjectionElem::Downcast(adt_def, variant_index) => {
+                ("Downcast", vec![
+                    ("variant", adt_def.variants[variant_index].name.to_string().to_json()),
+                    ("variant_index", variant_index.index().to_json()),
+                ])
+            }
+        };
+
+        fields.insert(0, ("kind", kind.to_json()));
+        object(fields)
+    }
+
+    fn source_info(&self, source_info: SourceInfo) -> Json {
+        object(vec![
+            ("span", self.span(source_info.span)),
+            ("scope", source_info.scope.index().to_json()),
+        ])
+    }
+
+    fn span(&self, span: Span) -> Json {
+        span_to_json(self.tcx.sess.source_map(), span)
+    }
+}
@@ -19,6 +19,7 @@ pub mod patch;
 
 mod alignment;
 mod graphviz;
+mod json;
 pub(crate) mod pretty;
 pub mod liveness;
 pub mod collect_writes;
@@ -26,6 +27,7 @@ pub mod collect_writes;
 pub use self::alignment::is_disaligned;
 pub use self::pretty::{dump_enabled, dump_mir, write_mir_pretty, PassWhere};
 pub use self::graphviz::{write_mir_graphviz};
+pub use self::json::write_mir_json;
 pub use self::graphviz::write_node_label as write_graphviz_node_label;
 
 /// If possible, suggest replacing `ref` with `ref mut`.
@@ -103,6 +103,7 @@ use rustc::ty::adjustment::{Adjust, Adjustment, AllowTwoPhase, AutoBorrow, AutoB
 use rustc::ty::fold::TypeFoldable;
 use rustc::ty::query::Providers;
 use rustc::ty::util::{Representability, IntTypeExt, Discr};
+use rustc::util::item_times::ItemTimeCategory;
 use rustc::ty::layout::VariantIdx;
 use rustc_data_structures::indexed_vec::Idx;
 use errors::{Applicability, DiagnosticBuilder, DiagnosticId};
@@ -840,6 +841,13 @@ fn typeck_tables_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         return tcx.typeck_tables_of(outer_def_id);
     }
 
+    tcx.time_item(ItemTimeCategory::Typeck, def_id, || typeck_item_tables(tcx, def_id))
+}
+
+/// Type-checks the body of an item that is not a closure, along with its closures.
+fn typeck_item_tables<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                def_id: DefId)
+                                -> &'tcx ty::TypeckTables<'tcx> {
     let id = tcx.hir.as_local_node_id(def_id).unwrap();
     let span = tcx.hir.span(id);
 
@@ -0,0 +1,808 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The public API diff printed by `--api-diff`.
+//!
+//! Both versions of a crate are compared in the format of the JSON backend (see `json`). The old
+//! version is always read from a file written by `--output-format json`. The new one is either the
+//! crate being documented, after the usual passes have stripped private items and
+//! `collect-trait-impls` has gathered its trait impls, or another such file.
+//!
+//! Items are matched up by the path they are reachable under, with fields, variants and
+//! associated items below their parent, and trait impls under `impl Trait for Type`. A moved item
+//! therefore shows up as removed at its old path and added at the new one. Every difference is
+//! classified as breaking or not by the semver rules for Rust libraries, erring on the side of
+//! calling a change breaking when the JSON doesn't tell enough to be sure.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use errors;
+use serialize::json::{Json, ToJson};
+
+use clean;
+use config::OutputFormat;
+use html::render::RenderInfo;
+use json::{krate_json, FORMAT_VERSION};
+
+static NULL: Json = Json::Null;
+
+fn field<'a>(json: &'a Json, key: &str) -> &'a Json {
+    json.find(key).unwrap_or(&NULL)
+}
+
+fn str_field<'a>(json: &'a Json, key: &str) -> &'a str {
+    field(json, key).as_string().unwrap_or("")
+}
+
+fn bool_field(json: &Json, key: &str) -> bool {
+    field(json, key).as_boolean().unwrap_or(false)
+}
+
+fn array_field<'a>(json: &'a Json, key: &str) -> &'a [Json] {
+    field(json, key).as_array().map_or(&[][..], |a| &a[..])
+}
+
+/// One version of the crate, as written by the JSON backend.
+struct Krate<'a> {
+    index: &'a Json,
+    paths: &'a Json,
+    /// Every public item, by path.
+    items: BTreeMap<String, &'a Json>,
+}
+
+impl<'a> Krate<'a> {
+    fn new(json: &'a Json) -> Krate<'a> {
+        let mut krate = Krate {
+            index: field(json, "index"),
+            paths: field(json, "paths"),
+            items: BTreeMap::new(),
+        };
+        let name = str_field(field(json, "crate"), "name").to_string();
+        if let Some(root) = krate.item(field(json, "root")) {
+            krate.add_module(root, &name);
+        }
+        krate
+    }
+
+    /// Looks up the item an id refers to.
+    fn item(&self, id: &Json) -> Option<&'a Json> {
+        id.as_string().and_then(|id| self.index.find(id))
+    }
+
+    fn children(&self, item: &'a Json, key: &str) -> Vec<&'a Json> {
+        array_field(field(item, "inner"), key).iter().filter_map(|id| self.item(id)).collect()
+    }
+
+    fn add_module(&mut self, module: &'a Json, path: &str) {
+        for item in self.children(module, "items") {
+            let name = str_field(item, "name");
+            let item_path = format!("{}::{}", path, name);
+            match str_field(item, "kind") {
+                "import" | "externcrate" => {}
+                "impl" => self.add_impl(item),
+                "mod" => {
+                    self.items.insert(item_path.clone(), item);
+                    self.add_module(item, &item_path);
+                }
+                _ => self.add_item(item, item_path),
+            }
+        }
+    }
+
+    /// Adds an item that isn't a module or an impl, and the fields, variants or associated items
+    /// in it.
+    fn add_item(&mut self, item: &'a Json, path: String) {
+        for key in &["fields", "variants", "items"] {
+            for child in self.children(item, key) {
+                let child_path = format!("{}::{}", path, str_field(child, "name"));
+                self.add_item(child, child_path);
+            }
+        }
+        self.items.insert(path, item);
+    }
+
+    fn add_impl(&mut self, item: &'a Json) {
+        let inner = field(item, "inner");
+        // Blanket impls of other crates come and go with their dependencies.
+        if !field(inner, "blanket_impl").is_null() {
+            return;
+        }
+        let self_type = field(inner, "for");
+        let trait_ = field(inner, "trait");
+        if trait_.is_null() {
+            // The items of inherent impls are part of the type's API, whichever impl they are in,
+            // so they go below the type's path.
+            let type_path = match str_field(self_type, "kind") {
+                "resolved_path" => self.path_of(field(self_type, "id")),
+                _ => None,
+            }.unwrap_or_else(|| self.render_type(self_type));
+            for child in self.children(item, "items") {
+                let child_path = format!("{}::{}", type_path, str_field(child, "name"));
+                self.add_item(child, child_path);
+            }
+        } else {
+            let negative = if bool_field(inner, "negative") { "!" } else { "" };
+            let path = format!("impl {}{} for {}",
+                               negative, self.render_type(trait_), self.render_type(self_type));
+            self.items.insert(path, item);
+        }
+    }
+
+    /// The full path of the item with the given id, if this version knows it.
+    fn path_of(&self, id: &Json) -> Option<String> {
+        let path = id.as_string().and_then(|id| self.paths.find(id))?;
+        let segments = array_field(path, "path").iter()
+                                                .filter_map(|s| s.as_string())
+                                                .collect::<Vec<_>>();
+        if segments.is_empty() { None } else { Some(segments.join("::")) }
+    }
+
+    /// Renders a type like it would be written in Rust, naming types by their full path where
+    /// that is known so that the same type is spelled the same way in both versions.
+    fn render_type(&self, ty: &Json) -> String {
+        let inner = |key| self.render_type(field(ty, key));
+        let list = |key| self.render_list(array_field(ty, key));
+        match str_field(ty, "kind") {
+            "resolved_path" => {
+                let name = if bool_field(ty, "is_generic") {
+                    None
+                } else {
+                    self.path_of(field(ty, "id"))
+                };
+                let name = name.unwrap_or_else(|| str_field(ty, "name").to_string());
+                format!("{}{}", name, self.render_args(field(ty, "args")))
+            }
+            "generic" | "primitive" => str_field(ty, "name").to_string(),
+            "tuple" if array_field(ty, "types").len() == 1 => format!("({},)", list("types")),
+            "tuple" => format!("({})", list("types")),
+            "slice" => format!("[{}]", inner("type")),
+            "array" => format!("[{}; {}]", inner("type"), str_field(ty, "len")),
+            "never" => "!".to_string(),
+            "infer" => "_".to_string(),
+            "unique" => format!("Box<{}>", inner("type")),
+            "raw_pointer" => {
+                let mutability = if bool_field(ty, "mutable") { "mut" } else { "const" };
+                format!("*{} {}", mutability, inner("type"))
+            }
+            "borrowed_ref" => {
+                let lifetime = match field(ty, "lifetime").as_string() {
+                    Some(lifetime) => format!("{} ", lifetime),
+                    None => String::new(),
+                };
+                let mutability = if bool_field(ty, "mutable") { "mut " } else { "" };
+                format!("&{}{}{}", lifetime, mutability, inner("type"))
+            }
+            "qualified_path" => {
+                format!("<{} as {}>::{}", inner("self_type"), inner("trait"), str_field(ty, "name"))
+            }
+            "impl_trait" => format!("impl {}", self.render_bounds(array_field(ty, "bounds"))),
+            "function_pointer" => {
+                let unsafety = if bool_field(ty, "unsafe") { "unsafe " } else { "" };
+                let abi = match str_field(ty, "abi") {
+                    "Rust" => String::new(),
+                    abi => format!("extern \"{}\" ", abi),
+                };
+                format!("{}{}{}", unsafety, abi, self.render_decl(field(ty, "decl")))
+            }
+            _ => "_".to_string(),
+        }
+    }
+
+    fn render_list(&self, types: &[Json]) -> String {
+        types.iter().map(|t| self.render_type(t)).collect::<Vec<_>>().join(", ")
+    }
+
+    fn render_args(&self, args: &Json) -> String {
+        if let Some(args) = args.find("angle_bracketed") {
+            let mut parts = array_field(args, "lifetimes").iter()
+                                                         .filter_map(|l| l.as_string())
+                                                         .map(|l| l.to_string())
+                                                         .collect::<Vec<_>>();
+            parts.extend(array_field(args, "types").iter().map(|t| self.render_type(t)));
+            parts.extend(array_field(args, "bindings").iter().map(|b| {
+                format!("{} = {}", str_field(b, "name"), self.render_type(field(b, "type")))
+            }));
+            if parts.is_empty() { String::new() } else { format!("<{}>", parts.join(", ")) }
+        } else if let Some(args) = args.find("parenthesized") {
+            let output = field(args, "output");
+            let output = if output.is_null() {
+                String::new()
+            } else {
+                format!(" -> {}", self.render_type(output))
+            };
+            format!("({}){}", self.render_list(array_field(args, "inputs")), output)
+        } else {
+            String::new()
+        }
+    }
+
+    fn render_bound(&self, bound: &Json) -> String {
+        match str_field(bound, "kind") {
+            "trait_bound" => {
+                let lifetimes = array_field(bound, "generic_params").iter()
+                                                                    .map(|p| str_field(p, "name"))
+                                                                    .collect::<Vec<_>>();
+                let for_ = if lifetimes.is_empty() {
+                    String::new()
+                } else {
+                    format!("for<{}> ", lifetimes.join(", "))
+                };
+                let maybe = if str_field(bound, "modifier") == "maybe" { "?" } else { "" };
+                format!("{}{}{}", for_, maybe, self.render_type(field(bound, "trait")))
+            }
+            _ => str_field(bound, "lifetime").to_string(),
+        }
+    }
+
+    fn render_bounds(&self, bounds: &[Json]) -> String {
+        bounds.iter().map(|b| self.render_bound(b)).collect::<Vec<_>>().join(" + ")
+    }
+
+    /// Renders a function signature without its argument names, which callers can't depend on.
+    fn render_decl(&self, decl: &Json) -> String {
+        let inputs = array_field(decl, "inputs").iter()
+                                                .map(|arg| self.render_type(field(arg, "type")))
+                                                .collect::<Vec<_>>();
+        let variadic = if bool_field(decl, "variadic") { ", ..." } else { "" };
+        let output = field(decl, "output");
+        let output = if output.is_null() {
+            String::new()
+        } else {
+            format!(" -> {}", self.render_type(output))
+        };
+        format!("fn({}{}){}", inputs.join(", "), variadic, output)
+    }
+
+    /// The names of the type parameters of `generics`, with whether they have a default.
+    fn type_params(&self, generics: &Json) -> BTreeMap<String, bool> {
+        array_field(generics, "params").iter()
+            .filter(|p| str_field(p, "kind") == "type" && !bool_field(p, "synthetic"))
+            .map(|p| (str_field(p, "name").to_string(), !field(p, "default").is_null()))
+            .collect()
+    }
+
+    /// Every bound in `generics`, whether written on a parameter or in the where clause, as
+    /// `Type: Bound`.
+    fn bounds(&self, generics: &Json) -> BTreeSet<String> {
+        let mut bounds = BTreeSet::new();
+        for param in array_field(generics, "params") {
+            for bound in array_field(param, "bounds") {
+                let name = str_field(param, "name");
+                bounds.insert(format!("{}: {}", name, self.render_bound(bound)));
+            }
+        }
+        for pred in array_field(generics, "where_predicates") {
+            let ty = match str_field(pred, "kind") {
+                "bound_predicate" => self.render_type(field(pred, "type")),
+                "region_predicate" => str_field(pred, "lifetime").to_string(),
+                _ => continue,
+            };
+            for bound in array_field(pred, "bounds") {
+                bounds.insert(format!("{}: {}", ty, self.render_bound(bound)));
+            }
+        }
+        bounds
+    }
+}
+
+/// One difference between the two versions of the crate.
+struct Change {
+    path: String,
+    kind: String,
+    breaking: bool,
+    description: String,
+}
+
+impl ToJson for Change {
+    fn to_json(&self) -> Json {
+        let mut obj = BTreeMap::new();
+        obj.insert("path".to_owned(), self.path.to_json());
+        obj.insert("kind".to_owned(), self.kind.to_json());
+        obj.insert("description".to_owned(), self.description.to_json());
+        Json::Object(obj)
+    }
+}
+
+struct Differ<'a, 'b: 'a> {
+    old: &'a Krate<'b>,
+    new: &'a Krate<'b>,
+    changes: Vec<Change>,
+}
+
+impl<'a, 'b> Differ<'a, 'b> {
+    fn push(&mut self, path: &str, item: &Json, breaking: bool, description: String) {
+        self.changes.push(Change {
+            path: path.to_string(),
+            kind: str_field(item, "kind").to_string(),
+            breaking,
+            description,
+        });
+    }
+
+    fn diff(&mut self) {
+        let (old_krate, new_krate) = (self.old, self.new);
+        for (path, &old) in &old_krate.items {
+            match new_krate.items.get(path) {
+                Some(&new) => self.diff_item(path, old, new),
+                // Only the outermost removed item is interesting.
+                None if !old_krate.items.contains_key(parent_path(path)) ||
+                        new_krate.items.contains_key(parent_path(path)) => {
+                    self.push(path, old, true, "removed".to_string());
+                }
+                None => {}
+            }
+        }
+        for (path, &new) in &new_krate.items {
+            if old_krate.items.contains_key(path) {
+                continue;
+            }
+            match old_krate.items.get(parent_path(path)) {
+                Some(&parent) => self.added_to(path, new, parent),
+                None if !new_krate.items.contains_key(parent_path(path)) => {
+                    self.push(path, new, false, "added".to_string());
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Classifies an item added to an existing item, which breaks code that relies on having seen
+    /// all of the items in the parent. Code outside the crate can't rely on that for
+    /// `#[non_exhaustive]` types.
+    fn added_to(&mut self, path: &str, new: &Json, parent: &Json) {
+        let parent_inner = field(parent, "inner");
+        let inner = field(new, "inner");
+        let exhaustive = !bool_field(parent, "non_exhaustive");
+        let (breaking, description) = match (str_field(parent, "kind"), str_field(new, "kind")) {
+            ("enum", "variant") if exhaustive => (true, "added to an exhaustive enum"),
+            ("struct", "structfield")
+                    if exhaustive && !bool_field(parent_inner, "fields_stripped") => {
+                (true, "added to a struct without private fields")
+            }
+            ("variant", "structfield")
+                    if exhaustive && !bool_field(parent_inner, "fields_stripped") => {
+                (true, "added to a variant without private fields")
+            }
+            ("trait", "tymethod") => (true, "added to a trait without a default"),
+            ("trait", "associatedtype") | ("trait", "associatedconstant")
+                    if field(inner, "default").is_null() => {
+                (true, "added to a trait without a default")
+            }
+            _ => (false, "added"),
+        };
+        self.push(path, new, breaking, description.to_string());
+    }
+
+    fn diff_item(&mut self, path: &str, old: &Json, new: &Json) {
+        let (old_kind, new_kind) = (str_field(old, "kind"), str_field(new, "kind"));
+        match (old_kind, new_kind) {
+            _ if old_kind == new_kind => {}
+            ("tymethod", "method") => {
+                self.push(path, new, false, "gained a default implementation".to_string());
+            }
+            ("method", "tymethod") => {
+                self.push(path, new, true, "lost its default implementation".to_string());
+            }
+            _ => {
+                let description = format!("changed from {} to {}", old_kind, new_kind);
+                self.push(path, new, true, description);
+                return;
+            }
+        }
+
+        self.diff_visibility(path, old, new);
+
+        match (bool_field(old, "non_exhaustive"), bool_field(new, "non_exhaustive")) {
+            (false, true) => self.push(path, new, true, "became non-exhaustive".to_string()),
+            (true, false) => self.push(path, new, false, "became exhaustive".to_string()),
+            _ => {}
+        }
+
+        let (old_inner, new_inner) = (field(old, "inner"), field(new, "inner"));
+        self.diff_generics(path, new, field(old_inner, "generics"), field(new_inner, "generics"));
+
+        match new_kind {
+            "fn" | "method" | "tymethod" => self.diff_fn(path, new, old_inner, new_inner),
+            "struct" | "union" => {
+                let (old_type, new_type) = (str_field(old_inner, "struct_type"),
+                                            str_field(new_inner, "struct_type"));
+                if old_type != new_type {
+                    let description = format!("changed from a {} {} to a {} {}",
+                                              old_type, new_kind, new_type, new_kind);
+                    self.push(path, new, true, description);
+                }
+                self.diff_fields_stripped(path, new, old_inner, new_inner);
+            }
+            "variant" => {
+                let (old_kind, new_kind) = (str_field(old_inner, "variant_kind"),
+                                            str_field(new_inner, "variant_kind"));
+                if old_kind != new_kind {
+                    let description = format!("changed from a {} variant to a {} variant",
+                                              old_kind, new_kind);
+                    self.push(path, new, true, description);
+                } else if new_kind == "tuple" {
+                    let old_fields = self.old.render_list(array_field(old_inner, "fields"));
+                    let new_fields = self.new.render_list(array_field(new_inner, "fields"));
+                    self.diff_rendered(path, new, "fields", old_fields, new_fields);
+                } else if new_kind == "struct" {
+                    self.diff_fields_stripped(path, new, old_inner, new_inner);
+                }
+            }
+            "structfield" | "type" | "constant" | "static" | "associatedconstant" => {
+                let old_type = self.old.render_type(field(old_inner, "type"));
+                let new_type = self.new.render_type(field(new_inner, "type"));
+                self.diff_rendered(path, new, "type", old_type, new_type);
+                if bool_field(old_inner, "mutable") != bool_field(new_inner, "mutable") {
+                    self.push(path, new, true, "changed mutability".to_string());
+                }
+            }
+            "trait" | "associatedtype" => {
+                if !bool_field(old_inner, "is_unsafe") && bool_field(new_inner, "is_unsafe") {
+                    self.push(path, new, true, "became unsafe to implement".to_string());
+                }
+                let what = if new_kind == "trait" { "supertrait" } else { "bound" };
+                let old_bounds = self.old.render_bounds(array_field(old_inner, "bounds"));
+                let new_bounds = self.new.render_bounds(array_field(new_inner, "bounds"));
+                self.diff_bounds(path, new, what, old_bounds, new_bounds);
+            }
+            _ => {}
+        }
+    }
+
+    fn diff_visibility(&mut self, path: &str, old: &Json, new: &Json) {
+        fn rank(vis: &Json) -> u8 {
+            match vis.as_string() {
+                Some("public") => 3,
+                Some("crate") => 2,
+                Some(_) => 0,
+                None => 1,
+            }
+        }
+        fn describe(vis: &Json) -> String {
+            match vis.as_string() {
+                Some(vis) => vis.to_string(),
+                None => format!("pub(in {})", str_field(field(vis, "restricted"), "path")),
+            }
+        }
+
+        let (old_vis, new_vis) = (field(old, "visibility"), field(new, "visibility"));
+        if old_vis != new_vis {
+            let description = format!("visibility changed from {} to {}",
+                                      describe(old_vis), describe(new_vis));
+            self.push(path, new, rank(new_vis) < rank(old_vis), description);
+        }
+    }
+
+    fn diff_fn(&mut self, path: &str, new: &Json, old_inner: &Json, new_inner: &Json) {
+        let old_sig = self.old.render_decl(field(old_inner, "decl"));
+        let new_sig = self.new.render_decl(field(new_inner, "decl"));
+        self.diff_rendered(path, new, "signature", old_sig, new_sig);
+
+        let (old_header, new_header) = (field(old_inner, "header"), field(new_inner, "header"));
+        match (bool_field(old_header, "unsafe"), bool_field(new_header, "unsafe")) {
+            (false, true) => self.push(path, new, true, "became unsafe".to_string()),
+            (true, false) => self.push(path, new, false, "is no longer unsafe".to_string()),
+            _ => {}
+        }
+        match (bool_field(old_header, "const"), bool_field(new_header, "const")) {
+            (true, false) => self.push(path, new, true, "is no longer const".to_string()),
+            (false, true) => self.push(path, new, false, "became const".to_string()),
+            _ => {}
+        }
+        let (old_abi, new_abi) = (str_field(old_header, "abi"), str_field(new_header, "abi"));
+        if old_abi != new_abi {
+            let description = format!("ABI changed from \"{}\" to \"{}\"", old_abi, new_abi);
+            self.push(path, new, true, description);
+        }
+    }
+
+    fn diff_fields_stripped(&mut self, path: &str, new: &Json,
+                            old_inner: &Json, new_inner: &Json) {
+        match (bool_field(old_inner, "fields_stripped"), bool_field(new_inner, "fields_stripped")) {
+            (false, true) => self.push(path, new, true, "gained private fields".to_string()),
+            (true, false) => self.push(path, new, false, "lost its private fields".to_string()),
+            _ => {}
+        }
+    }
+
+    fn diff_rendered(&mut self, path: &str, new: &Json, what: &str, old: String, new_: String) {
+        if old != new_ {
+            let description = format!("{} changed from `{}` to `{}`", what, old, new_);
+            self.push(path, new, true, description);
+        }
+    }
+
+    fn diff_generics(&mut self, path: &str, new: &Json,
+                     old_generics: &Json, new_generics: &Json) {
+        let old_params = self.old.type_params(old_generics);
+        let new_params = self.new.type_params(new_generics);
+        for (name, &has_default) in &new_params {
+            if !old_params.contains_key(name) {
+                let description = format!("new type parameter `{}`", name);
+                self.push(path, new, !has_default, description);
+            }
+        }
+        for name in old_params.keys() {
+            if !new_params.contains_key(name) {
+                let description = format!("removed type parameter `{}`", name);
+                self.push(path, new, true, description);
+            }
+        }
+
+        let old_bounds = self.old.bounds(old_generics);
+        let new_bounds = self.new.bounds(new_generics);
+        for bound in new_bounds.difference(&old_bounds) {
+            self.push(path, new, true, format!("new trait bound `{}`", bound));
+        }
+        for bound in old_bounds.difference(&new_bounds) {
+            self.push(path, new, false, format!("removed trait bound `{}`", bound));
+        }
+    }
+
+    /// Compares `A + B` lists of bounds: adding a bound asks more of whoever implements the trait
+    /// or the associated type, while removing one takes something away from its users.
+    fn diff_bounds(&mut self, path: &str, new: &Json, what: &str, old: String, new_: String) {
+        let old = old.split(" + ").filter(|b| !b.is_empty()).collect::<BTreeSet<_>>();
+        let new_bounds = new_.split(" + ").filter(|b| !b.is_empty()).collect::<BTreeSet<_>>();
+        for bound in new_bounds.difference(&old) {
+            self.push(path, new, true, format!("new {} `{}`", what, bound));
+        }
+        for bound in old.difference(&new_bounds) {
+            self.push(path, new, true, format!("removed {} `{}`", what, bound));
+        }
+    }
+}
+
+/// The path of the item containing the item at `path`, or an empty string at the top level.
+fn parent_path(path: &str) -> &str {
+    if path.starts_with("impl ") {
+        return "";
+    }
+    path.rfind("::").map_or("", |i| &path[..i])
+}
+
+/// Compares two JSON descriptions of a crate, returning the changes with breaking ones first.
+fn diff(old: &Json, new: &Json) -> Vec<Change> {
+    let old = Krate::new(old);
+    let new = Krate::new(new);
+    let mut differ = Differ { old: &old, new: &new, changes: Vec::new() };
+    differ.diff();
+    let mut changes = differ.changes;
+    changes.sort_by(|a, b| (!a.breaking, &a.path).cmp(&(!b.breaking, &b.path)));
+    changes
+}
+
+fn load(path: &Path, diag: &errors::Handler) -> Option<Json> {
+    let json = File::open(path).map_err(|e| e.to_string()).and_then(|f| {
+        Json::from_reader(&mut BufReader::new(f)).map_err(|e| e.to_string())
+    });
+    match json {
+        Ok(ref json) if json.find("format_version").and_then(|v| v.as_u64()) !=
+                        Some(FORMAT_VERSION) => {
+            diag.struct_err(&format!("{} was not written by this version of rustdoc's JSON \
+                                      backend", path.display()))
+                .note(&format!("expected format version {}", FORMAT_VERSION))
+                .emit();
+            None
+        }
+        Ok(json) => Some(json),
+        Err(e) => {
+            diag.struct_err(&format!("couldn't read {}: {}", path.display(), e)).emit();
+            None
+        }
+    }
+}
+
+fn print(changes: &[Change], format: OutputFormat) {
+    let (breaking, non_breaking): (Vec<_>, Vec<_>) = changes.iter().partition(|c| c.breaking);
+    match format {
+        OutputFormat::Json => {
+            let list = |changes: &[&Change]| {
+                Json::Array(changes.iter().map(|c| c.to_json()).collect())
+            };
+            let mut obj = BTreeMap::new();
+            obj.insert("breaking".to_owned(), list(&breaking));
+            obj.insert("non_breaking".to_owned(), list(&non_breaking));
+            println!("{}", Json::Object(obj));
+        }
+        OutputFormat::Html => {
+            for &(title, ref changes) in &[("Breaking changes:", &breaking),
+                                           ("Non-breaking changes:", &non_breaking)] {
+                if changes.is_empty() {
+                    continue;
+                }
+                println!("{}", title);
+                for change in changes.iter() {
+                    // The paths of trait impls already start with `impl`.
+                    if change.kind == "impl" {
+                        println!("    {}: {}", change.path, change.description);
+                    } else {
+                        println!("    {} {}: {}", change.kind, change.path, change.description);
+                    }
+                }
+                println!();
+            }
+            println!("{} breaking, {} non-breaking changes", breaking.len(), non_breaking.len());
+        }
+    }
+}
+
+/// Compares the crate being documented against the JSON description of an older version in
+/// `old`, and prints the changes to stdout.
+pub fn run(krate: &clean::Crate,
+           renderinfo: &RenderInfo,
+           old: &Path,
+           format: OutputFormat,
+           diag: &errors::Handler) -> isize {
+    let old = match load(old, diag) {
+        Some(old) => old,
+        None => return 1,
+    };
+    print(&diff(&old, &krate_json(krate, renderinfo)), format);
+    0
+}
+
+/// Compares two JSON descriptions of a crate, and prints the changes to stdout.
+pub fn run_files(old: &Path, new: &Path, format: OutputFormat, diag: &errors::Handler) -> isize {
+    match (load(old, diag), load(new, diag)) {
+        (Some(old), Some(new)) => {
+            print(&diff(&old, &new), format);
+            0
+        }
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serialize::json::Json;
+    use super::diff;
+
+    const U8: &str = r#"{"kind":"primitive","name":"u8"}"#;
+    const U16: &str = r#"{"kind":"primitive","name":"u16"}"#;
+    const NO_GENERICS: &str = r#"{"params":[],"where_predicates":[]}"#;
+
+    /// Builds the JSON description of a crate `foo` whose root module contains the items with
+    /// the ids in `root`.
+    fn krate(root: &[&str], items: &[(&str, String)]) -> Json {
+        let mut index = vec![format!(
+            r#""0:0":{{"name":"foo","kind":"mod","visibility":"public","inner":{{"items":{:?}}}}}"#,
+            root)];
+        index.extend(items.iter().map(|&(id, ref item)| format!("{:?}:{}", id, item)));
+        let json = format!(
+            r#"{{"format_version":1,"crate":{{"name":"foo"}},"root":"0:0","index":{{{}}},
+                "paths":{{}}}}"#,
+            index.join(","));
+        Json::from_str(&json).unwrap()
+    }
+
+    fn item(name: &str, kind: &str, inner: &str) -> String {
+        format!(r#"{{"name":"{}","kind":"{}","visibility":"public","inner":{}}}"#,
+                name, kind, inner)
+    }
+
+    fn field(ty: &str) -> String {
+        format!(r#"{{"type":{}}}"#, ty)
+    }
+
+    fn function(name: &str, kind: &str, input: &str, generics: &str) -> String {
+        item(name, kind, &format!(
+            r#"{{"decl":{{"inputs":[{{"name":"x","type":{}}}],"output":null,"variadic":false}},
+                 "generics":{},
+                 "header":{{"const":false,"unsafe":false,"async":false,"abi":"Rust"}}}}"#,
+            input, generics))
+    }
+
+    fn changes(old: &Json, new: &Json) -> Vec<(bool, String, String)> {
+        diff(old, new).into_iter().map(|c| (c.breaking, c.path, c.description)).collect()
+    }
+
+    fn change(breaking: bool, path: &str, description: &str) -> (bool, String, String) {
+        (breaking, path.to_string(), description.to_string())
+    }
+
+    #[test]
+    fn functions() {
+        let old = krate(&["0:1", "0:2"], &[
+            ("0:1", function("bar", "fn", U8, NO_GENERICS)),
+            ("0:2", function("gone", "fn", U8, NO_GENERICS)),
+        ]);
+        let new = krate(&["0:1", "0:3"], &[
+            ("0:1", function("bar", "fn", U16, NO_GENERICS)),
+            ("0:3", function("added", "fn", U8, NO_GENERICS)),
+        ]);
+        assert_eq!(changes(&old, &new), vec![
+            change(true, "foo::bar", "signature changed from `fn(u8)` to `fn(u16)`"),
+            change(true, "foo::gone", "removed"),
+            change(false, "foo::added", "added"),
+        ]);
+        assert_eq!(changes(&old, &old), vec![]);
+    }
+
+    #[test]
+    fn new_trait_bounds() {
+        let t = r#"{"kind":"generic","name":"T"}"#;
+        let unbounded = r#"{"params":[{"name":"T","kind":"type","bounds":[],"default":null,
+                                       "synthetic":false}],"where_predicates":[]}"#;
+        let bounded = r#"{"params":[{"name":"T","kind":"type","bounds":[],"default":null,
+                                     "synthetic":false}],
+                          "where_predicates":[{"kind":"bound_predicate",
+                                               "type":{"kind":"generic","name":"T"},
+                                               "bounds":[{"kind":"trait_bound",
+                                                          "trait":{"kind":"resolved_path",
+                                                                   "name":"Clone","id":"1:2",
+                                                                   "args":null,
+                                                                   "is_generic":false},
+                                                          "generic_params":[],
+                                                          "modifier":"none"}]}]}"#;
+        let old = krate(&["0:1"], &[("0:1", function("f", "fn", t, unbounded))]);
+        let new = krate(&["0:1"], &[("0:1", function("f", "fn", t, bounded))]);
+        assert_eq!(changes(&old, &new), vec![change(true, "foo::f", "new trait bound `T: Clone`")]);
+        assert_eq!(changes(&new, &old),
+                   vec![change(false, "foo::f", "removed trait bound `T: Clone`")]);
+    }
+
+    #[test]
+    fn additions_to_exhaustive_items() {
+        let old = krate(&["0:1", "0:3", "0:5"], &[
+            ("0:1", item("E", "enum", r#"{"generics":{},"variants":["0:2"]}"#)),
+            ("0:2", item("A", "variant", r#"{"variant_kind":"plain"}"#)),
+            ("0:3", item("S", "struct", r#"{"fields":["0:4"],"fields_stripped":false}"#)),
+            ("0:4", item("x", "structfield", &field(U8))),
+            ("0:5", item("T", "trait", r#"{"items":[],"bounds":[]}"#)),
+        ]);
+        let new = krate(&["0:1", "0:3", "0:5"], &[
+            ("0:1", item("E", "enum", r#"{"generics":{},"variants":["0:2","0:6"]}"#)),
+            ("0:2", item("A", "variant", r#"{"variant_kind":"plain"}"#)),
+            ("0:6", item("B", "variant", r#"{"variant_kind":"plain"}"#)),
+            ("0:3", item("S", "struct", r#"{"fields":["0:4","0:7"],"fields_stripped":false}"#)),
+            ("0:4", item("x", "structfield", &field(U8))),
+            ("0:7", item("y", "structfield", &field(U8))),
+            ("0:5", item("T", "trait", r#"{"items":["0:8","0:9"],"bounds":[]}"#)),
+            ("0:8", function("required", "tymethod", U8, NO_GENERICS)),
+            ("0:9", function("provided", "method", U8, NO_GENERICS)),
+        ]);
+        assert_eq!(changes(&old, &new), vec![
+            change(true, "foo::E::B", "added to an exhaustive enum"),
+            change(true, "foo::S::y", "added to a struct without private fields"),
+            change(true, "foo::T::required", "added to a trait without a default"),
+            change(false, "foo::T::provided", "added"),
+        ]);
+    }
+
+    #[test]
+    fn additions_to_non_exhaustive_items() {
+        let non_exhaustive = |item: String| {
+            item.replace(r#""visibility""#, r#""non_exhaustive":true,"visibility""#)
+        };
+        let old = krate(&["0:1", "0:3"], &[
+            ("0:1", non_exhaustive(item("E", "enum", r#"{"generics":{},"variants":["0:2"]}"#))),
+            ("0:2", item("A", "variant", r#"{"variant_kind":"plain"}"#)),
+            ("0:3", item("S", "struct", r#"{"fields":["0:4"],"fields_stripped":false}"#)),
+            ("0:4", item("x", "structfield", &field(U8))),
+        ]);
+        let new = krate(&["0:1", "0:3"], &[
+            ("0:1", non_exhaustive(item("E", "enum",
+                                        r#"{"generics":{},"variants":["0:2","0:5"]}"#))),
+            ("0:2", item("A", "variant", r#"{"variant_kind":"plain"}"#)),
+            ("0:5", item("B", "variant", r#"{"variant_kind":"plain"}"#)),
+            ("0:3", non_exhaustive(item("S", "struct",
+                                        r#"{"fields":["0:4"],"fields_stripped":false}"#))),
+            ("0:4", item("x", "structfield", &field(U8))),
+        ]);
+        assert_eq!(changes(&old, &new), vec![
+            change(true, "foo::S", "became non-exhaustive"),
+            change(false, "foo::E::B", "added"),
+        ]);
+    }
+}
@@ -78,6 +78,12 @@ pub struct Options {
     pub should_test: bool,
     /// List of arguments to pass to the test harness, if running tests.
     pub test_args: Vec<String>,
+    /// Whether to compile doctests that allow it together as a single crate, rather than
+    /// compiling every doctest on its own.
+    pub combine_doctests: bool,
+    /// Optional path to keep the source and executable of every doctest in, instead of throwing
+    /// them away after the test ran.
+    pub persist_doctests: Option<PathBuf>,
 
     // Options that affect the documentation process
 
@@ -92,6 +98,12 @@ pub struct Options {
     /// Whether to display warnings during doc generation or while gathering doctests. By default,
     /// all non-rustdoc-specific lints are allowed when generating docs.
     pub display_warnings: bool,
+    /// Whether to print a report of how many public items are documented instead of generating
+    /// docs. The report is a table, or JSON with `--output-format json`.
+    pub show_coverage: bool,
+    /// If present, the JSON description of an older version of the crate to compare the public API
+    /// against, printing the changes instead of generating docs.
+    pub api_diff: Option<PathBuf>,
 
     // Options that alter generated documentation pages
 
@@ -131,20 +143,35 @@ impl fmt::Debug for Options {
             .field("lint_cap", &self.lint_cap)
             .field("should_test", &self.should_test)
             .field("test_args", &self.test_args)
+            .field("combine_doctests", &self.combine_doctests)
+            .field("persist_doctests", &self.persist_doctests)
             .field("default_passes", &self.default_passes)
             .field("manual_passes", &self.manual_passes)
             .field("display_warnings", &self.display_warnings)
+            .field("show_coverage", &self.show_coverage)
+            .field("api_diff", &self.api_diff)
             .field("crate_version", &self.crate_version)
             .field("render_options", &self.render_options)
             .finish()
     }
 }
 
+/// The kind of documentation rustdoc generates, selected with `--output-format`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// A tree of HTML pages, with a search index. The default.
+    Html,
+    /// A single JSON file describing the crate. Unstable; see `json.rs` for the schema.
+    Json,
+}
+
 /// Configuration options for the HTML page-creation process.
 #[derive(Clone, Debug)]
 pub struct RenderOptions {
     /// Output directory to generate docs into. Defaults to `doc`.
     pub output: PathBuf,
+    /// Which backend writes the documentation. Defaults to HTML.
+    pub output_format: OutputFormat,
     /// External files to insert into generated pages.
     pub external_html: ExternalHtml,
     /// A pre-populated `IdMap` with the default headings and any headings added by Markdown files
@@ -222,6 +249,14 @@ impl Options {
             for &name in passes::DEFAULT_PRIVATE_PASSES {
                 println!("{:>20}", name);
             }
+            println!("\nPasses run with `--show-coverage`:");
+            for &name in passes::DEFAULT_COVERAGE_PASSES {
+                println!("{:>20}", name);
+            }
+            println!("\nPasses run with `--show-coverage --document-private-items`:");
+            for &name in passes::PRIVATE_COVERAGE_PASSES {
+                println!("{:>20}", name);
+            }
             return Err(0);
         }
 
@@ -321,6 +356,8 @@ impl Options {
                                               .collect();
 
         let should_test = matches.opt_present("test");
+        let combine_doctests = matches.opt_present("combine-doctests");
+        let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
 
         let output = matches.opt_str("o")
                             .map(|s| PathBuf::from(&s))
@@ -388,13 +425,22 @@ impl Options {
             }
         }
 
-        match matches.opt_str("w").as_ref().map(|s| &**s) {
-            Some("html") | None => {}
+        let output_format = match matches.opt_str("w").as_ref().map(|s| &**s) {
+            Some("html") | None => OutputFormat::Html,
+            Some("json") => {
+                if !nightly_options::is_unstable_enabled(matches) {
+                    diag.struct_err("the JSON output format is unstable")
+                        .help("pass `-Z unstable-options` to enable it")
+                        .emit();
+                    return Err(1);
+                }
+                OutputFormat::Json
+            }
             Some(s) => {
                 diag.struct_err(&format!("unknown output format: {}", s)).emit();
                 return Err(1);
             }
-        }
+        };
 
         let index_page = matches.opt_str("index-page").map(|s| PathBuf::from(&s));
         if let Some(ref index_page) = index_page {
@@ -412,9 +458,17 @@ impl Options {
             }
         });
 
+        let show_coverage = matches.opt_present("show-coverage");
+        let api_diff = matches.opt_str("api-diff").map(PathBuf::from);
+        let document_private = matches.opt_present("document-private-items");
+
         let default_passes = if matches.opt_present("no-defaults") {
             passes::DefaultPassOption::None
-        } else if matches.opt_present("document-private-items") {
+        } else if show_coverage && document_private {
+            passes::DefaultPassOption::PrivateCoverage
+        } else if show_coverage {
+            passes::DefaultPassOption::Coverage
+        } else if document_private {
             passes::DefaultPassOption::Private
         } else {
             passes::DefaultPassOption::Default
@@ -455,12 +509,17 @@ impl Options {
             lint_cap,
             should_test,
             test_args,
+            combine_doctests,
+            persist_doctests,
             default_passes,
             manual_passes,
             display_warnings,
+            show_coverage,
+            api_diff,
             crate_version,
             render_options: RenderOptions {
                 output,
+                output_format,
                 external_html,
                 id_map,
                 playground_url,
@@ -496,6 +555,10 @@ fn check_deprecated_options(matches: &getopts::Matches, diag: &errors::Handler)
     ];
 
     for flag in deprecated_flags.into_iter() {
+        // `--output-format json` selects the JSON backend, so it is not deprecated.
+        if *flag == "output-format" && matches.opt_str(flag).map_or(false, |f| f == "json") {
+            continue;
+        }
         if matches.opt_present(flag) {
             let mut err = diag.struct_warn(&format!("the '{}' flag is considered deprecated",
                                                     flag));
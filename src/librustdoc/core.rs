@@ -30,6 +30,7 @@ use syntax::ast::{self, Ident, NodeId};
 use syntax::source_map;
 use syntax::feature_gate::UnstableFeatures;
 use syntax::json::JsonEmitter;
+use syntax::sarif::SarifEmitter;
 use syntax::ptr::P;
 use syntax::symbol::keywords;
 use syntax_pos::DUMMY_SP;
@@ -268,8 +269,8 @@ impl DocAccessLevels for AccessLevels<DefId> {
 
 /// Creates a new diagnostic `Handler` that can be used to emit warnings and errors.
 ///
-/// If the given `error_format` is `ErrorOutputType::Json` and no `SourceMap` is given, a new one
-/// will be created for the handler.
+/// If the given `error_format` is `ErrorOutputType::Json` or `ErrorOutputType::Sarif` and no
+/// `SourceMap` is given, a new one will be created for the handler.
 pub fn new_handler(error_format: ErrorOutputType,
                    source_map: Option<Lrc<source_map::SourceMap>>,
                    treat_err_as_bug: bool,
@@ -305,6 +306,11 @@ pub fn new_handler(error_format: ErrorOutputType,
                 true,
                 false)
         ),
+        ErrorOutputType::Sarif(_) => {
+            let source_map = source_map.unwrap_or_else(
+                || Lrc::new(source_map::SourceMap::new(sessopts.file_path_mapping())));
+            Box::new(SarifEmitter::stderr(None, source_map))
+        }
     };
 
     errors::Handler::with_emitter_and_flags(
@@ -351,13 +357,15 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
     let warnings_lint_name = lint::builtin::WARNINGS.name;
     let missing_docs = rustc_lint::builtin::MISSING_DOCS.name;
     let missing_doc_example = rustc_lint::builtin::MISSING_DOC_CODE_EXAMPLES.name;
+    let invalid_codeblock_syntax = lint::builtin::INVALID_CODEBLOCK_SYNTAX.name;
 
     // In addition to those specific lints, we also need to whitelist those given through
     // command line, otherwise they'll get ignored and we don't want that.
     let mut whitelisted_lints = vec![warnings_lint_name.to_owned(),
                                      intra_link_resolution_failure_name.to_owned(),
                                      missing_docs.to_owned(),
-                                     missing_doc_example.to_owned()];
+                                     missing_doc_example.to_owned(),
+                                     invalid_codeblock_syntax.to_owned()];
 
     whitelisted_lints.extend(lint_opts.iter().map(|(lint, _)| lint).cloned());
 
@@ -366,7 +374,8 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                     .chain(rustc_lint::SoftLints.get_lints().into_iter())
                     .filter_map(|lint| {
                         if lint.name == warnings_lint_name ||
-                           lint.name == intra_link_resolution_failure_name {
+                           lint.name == intra_link_resolution_failure_name ||
+                           lint.name == invalid_codeblock_syntax {
                             None
                         } else {
                             Some((lint.name_lower(), lint::Allow))
@@ -599,6 +608,8 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                 if attr.is_word() && name == Some("document_private_items") {
                     if default_passes == passes::DefaultPassOption::Default {
                         default_passes = passes::DefaultPassOption::Private;
+                    } else if default_passes == passes::DefaultPassOption::Coverage {
+                        default_passes = passes::DefaultPassOption::PrivateCoverage;
                     }
                 }
             }
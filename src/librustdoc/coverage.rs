@@ -0,0 +1,222 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The documentation coverage report printed by `--show-coverage`.
+//!
+//! This runs on the cleaned crate after the coverage passes (see
+//! `passes::DEFAULT_COVERAGE_PASSES`) have stripped hidden and private items, so everything left is
+//! part of the public API. Impls are not counted themselves, and neither are the items of trait
+//! impls, since those show the documentation of the trait. Re-exports and positional fields are
+//! skipped too, matching what the `missing_docs` lint asks for.
+
+use std::collections::BTreeMap;
+use std::ops;
+
+use serialize::json::{Json, ToJson};
+
+use clean;
+use config::OutputFormat;
+use doctree;
+use html::markdown::{find_testable_code, ErrorCodes, LangString};
+use test::Tester;
+
+/// Width of the file name column of the table; longer names are cut at the front.
+const FILE_COLUMN_WIDTH: usize = 35;
+
+#[derive(Default, Copy, Clone)]
+struct ItemCount {
+    total: u64,
+    with_docs: u64,
+    with_examples: u64,
+}
+
+impl ItemCount {
+    fn count_item(&mut self, has_docs: bool, has_examples: bool) {
+        self.total += 1;
+        if has_docs {
+            self.with_docs += 1;
+        }
+        if has_examples {
+            self.with_examples += 1;
+        }
+    }
+
+    fn percentage(&self, n: u64) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (n as f64 * 100.0) / self.total as f64
+        }
+    }
+}
+
+impl ops::AddAssign for ItemCount {
+    fn add_assign(&mut self, rhs: Self) {
+        self.total += rhs.total;
+        self.with_docs += rhs.with_docs;
+        self.with_examples += rhs.with_examples;
+    }
+}
+
+impl ToJson for ItemCount {
+    fn to_json(&self) -> Json {
+        let mut obj = BTreeMap::new();
+        obj.insert("total".to_owned(), self.total.to_json());
+        obj.insert("with_docs".to_owned(), self.with_docs.to_json());
+        obj.insert("with_examples".to_owned(), self.with_examples.to_json());
+        Json::Object(obj)
+    }
+}
+
+/// Counts the Rust code blocks in a doc string.
+struct CodeBlocks(usize);
+
+impl Tester for CodeBlocks {
+    fn add_test(&mut self, _test: String, _config: LangString, _line: usize) {
+        self.0 += 1;
+    }
+}
+
+#[derive(Default)]
+struct CoverageCalculator {
+    items: BTreeMap<String, ItemCount>,
+}
+
+impl CoverageCalculator {
+    fn visit(&mut self, item: &clean::Item) {
+        match item.inner {
+            clean::StrippedItem(..) |
+            clean::ImportItem(..) |
+            clean::ExternCrateItem(..) => return,
+            clean::ImplItem(ref i) => {
+                // Trait impls show the docs of the trait's items, so only inherent impls count.
+                if i.trait_.is_none() {
+                    for item in &i.items {
+                        self.visit(item);
+                    }
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        self.count(item);
+
+        match item.inner {
+            clean::ModuleItem(ref m) => self.visit_all(&m.items),
+            clean::StructItem(clean::Struct { struct_type, ref fields, .. }) |
+            clean::UnionItem(clean::Union { struct_type, ref fields, .. }) |
+            clean::VariantItem(clean::Variant {
+                kind: clean::VariantKind::Struct(clean::VariantStruct {
+                    struct_type, ref fields, ..
+                }),
+            }) => {
+                match struct_type {
+                    doctree::Tuple => {}
+                    _ => self.visit_all(fields),
+                }
+            }
+            clean::EnumItem(ref e) => self.visit_all(&e.variants),
+            clean::TraitItem(ref t) => self.visit_all(&t.items),
+            _ => {}
+        }
+    }
+
+    fn visit_all<'a, I>(&mut self, items: I)
+        where I: IntoIterator<Item = &'a clean::Item>
+    {
+        for item in items {
+            self.visit(item);
+        }
+    }
+
+    fn count(&mut self, item: &clean::Item) {
+        let doc = item.attrs.collapsed_doc_value().unwrap_or_default();
+        let has_docs = !doc.trim().is_empty();
+        let mut blocks = CodeBlocks(0);
+        // Malformed code blocks are reported when documenting; here they just don't count.
+        let _ = find_testable_code(&doc, &mut blocks, ErrorCodes::No);
+
+        debug!("coverage: counting {:?} {:?} in {}: docs {}, examples {}",
+               item.type_(), item.name, item.source.filename, has_docs, blocks.0);
+        self.items.entry(item.source.filename.to_string())
+                  .or_default()
+                  .count_item(has_docs, blocks.0 > 0);
+    }
+
+    fn total(&self) -> ItemCount {
+        let mut total = ItemCount::default();
+        for count in self.items.values() {
+            total += *count;
+        }
+        total
+    }
+
+    fn to_json(&self) -> Json {
+        let mut files = BTreeMap::new();
+        for (file, count) in &self.items {
+            files.insert(file.clone(), count.to_json());
+        }
+        let mut obj = BTreeMap::new();
+        obj.insert("files".to_owned(), Json::Object(files));
+        obj.insert("total".to_owned(), self.total().to_json());
+        Json::Object(obj)
+    }
+
+    fn print_table(&self) {
+        let line = format!("+-{0:->1$}-+-{0:->2$}-+-{0:->2$}-+-{0:->2$}-+-{0:->2$}-+",
+                           "", FILE_COLUMN_WIDTH, 10);
+        let print_row = |name: &str, count: &ItemCount| {
+            println!("| {:<width$} | {:>10} | {:>10} | {:>9.1}% | {:>10} |",
+                     limit_filename_len(name),
+                     count.with_docs,
+                     count.total - count.with_docs,
+                     count.percentage(count.with_docs),
+                     count.with_examples,
+                     width = FILE_COLUMN_WIDTH);
+        };
+
+        println!("{}", line);
+        println!("| {:<width$} | {:>10} | {:>10} | {:>10} | {:>10} |",
+                 "File", "Documented", "Missing", "Percentage", "Examples",
+                 width = FILE_COLUMN_WIDTH);
+        println!("{}", line);
+        for (file, count) in &self.items {
+            print_row(file, count);
+        }
+        println!("{}", line);
+        print_row("Total", &self.total());
+        println!("{}", line);
+    }
+}
+
+/// Cuts the front of file names that don't fit in the table, keeping the more specific end.
+fn limit_filename_len(name: &str) -> String {
+    let len = name.chars().count();
+    if len > FILE_COLUMN_WIDTH {
+        let keep = FILE_COLUMN_WIDTH - 3;
+        format!("...{}", name.chars().skip(len - keep).collect::<String>())
+    } else {
+        name.to_owned()
+    }
+}
+
+/// Prints the documentation coverage of `krate` to stdout, as a table or as JSON.
+pub fn run(krate: &clean::Crate, format: OutputFormat) {
+    let mut calc = CoverageCalculator::default();
+    if let Some(ref module) = krate.module {
+        calc.visit(module);
+    }
+
+    match format {
+        OutputFormat::Html => calc.print_table(),
+        OutputFormat::Json => println!("{}", calc.to_json()),
+    }
+}
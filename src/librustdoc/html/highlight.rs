@@ -21,6 +21,9 @@ use std::fmt::Display;
 use std::io;
 use std::io::prelude::*;
 
+use errors::emitter::EmitterWriter;
+use errors::Handler;
+use rustc_data_structures::sync::Lrc;
 use syntax::source_map::{SourceMap, FilePathMapping};
 use syntax::parse::lexer::{self, TokenAndSpan};
 use syntax::parse::token;
@@ -35,7 +38,12 @@ pub fn render_with_highlighting(
     tooltip: Option<(&str, &str)>,
 ) -> String {
     debug!("highlighting: ================\n{}\n==============", src);
-    let sess = parse::ParseSess::new(FilePathMapping::empty());
+    // Code blocks that don't lex are reported by the `check-code-block-syntax` pass, so the
+    // errors are not printed again here.
+    let cm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+    let emitter = EmitterWriter::new(box io::sink(), None, false, false);
+    let handler = Handler::with_emitter(false, false, box emitter);
+    let sess = parse::ParseSess::with_span_handler(handler, cm);
     let fm = sess.source_map().new_source_file(FileName::Custom("stdin".to_string()),
                                                src.to_string());
 
@@ -47,22 +55,14 @@ pub fn render_with_highlighting(
     }
     write_header(class, &mut out).unwrap();
 
-    let lexer = match lexer::StringReader::new_without_err(&sess, fm, None, "Output from rustc:") {
+    let lexer = match lexer::StringReader::new_or_buffered_errs(&sess, fm, None) {
         Ok(l) => l,
-        Err(_) => {
-            let first_line = src.lines().next().unwrap_or_else(|| "");
-            let mut err = sess.span_diagnostic
-                              .struct_warn(&format!("Invalid doc comment starting with: `{}`\n\
-                                                     (Ignoring this codeblock)",
-                                                    first_line));
-            err.emit();
-            return String::new();
-        }
+        Err(_) => return format!("<pre>{}</pre>", Escape(src)),
     };
     let mut classifier = Classifier::new(lexer, sess.source_map());
     if classifier.write_source(&mut out).is_err() {
-        classifier.lexer.emit_fatal_errors();
-        return format!("<pre>{}</pre>", src);
+        classifier.lexer.buffer_fatal_errors();
+        return format!("<pre>{}</pre>", Escape(src));
     }
 
     if let Some(extension) = extension {
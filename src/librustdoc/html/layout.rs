@@ -133,7 +133,10 @@ pub fn render<T: fmt::Display, S: fmt::Display>(
                 </p>\
                 <p>\
                     Search functions by type signature (e.g. \
-                    <code>vec -> usize</code> or <code>* -> vec</code>)\
+                    <code>Vec&lt;T&gt; -> usize</code>, \
+                    <code>&amp;str -> Result&lt;_, _&gt;</code> or <code>* -> vec</code>). \
+                    <code>_</code> matches any type, and single letters like <code>T</code> \
+                    are type variables.\
                 </p>\
                 <p>\
                     Search multiple things at once by splitting your query with comma (e.g. \
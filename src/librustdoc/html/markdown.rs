@@ -636,7 +636,7 @@ impl LangString {
         }
     }
 
-    fn parse(string: &str, allow_error_code_check: ErrorCodes) -> LangString {
+    pub(crate) fn parse(string: &str, allow_error_code_check: ErrorCodes) -> LangString {
         let allow_error_code_check = allow_error_code_check.as_bool();
         let mut seen_rust_tags = false;
         let mut seen_other_tags = false;
@@ -855,6 +855,23 @@ pub fn plain_summary_line(md: &str) -> String {
     s
 }
 
+/// Returns the range of `s` in `md`, if `s` is a slice of `md`.
+fn locate_in(md: &str, s: &str) -> Option<Range<usize>> {
+    unsafe {
+        let s_start = s.as_ptr();
+        let s_end = s_start.add(s.len());
+        let md_start = md.as_ptr();
+        let md_end = md_start.add(md.len());
+        if md_start <= s_start && s_end <= md_end {
+            let start = s_start.offset_from(md_start) as usize;
+            let end = s_end.offset_from(md_start) as usize;
+            Some(start..end)
+        } else {
+            None
+        }
+    }
+}
+
 pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     if md.is_empty() {
         return vec![];
@@ -868,19 +885,7 @@ pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     let shortcut_links = RefCell::new(vec![]);
 
     {
-        let locate = |s: &str| unsafe {
-            let s_start = s.as_ptr();
-            let s_end = s_start.add(s.len());
-            let md_start = md.as_ptr();
-            let md_end = md_start.add(md.len());
-            if md_start <= s_start && s_end <= md_end {
-                let start = s_start.offset_from(md_start) as usize;
-                let end = s_end.offset_from(md_start) as usize;
-                Some(start..end)
-            } else {
-                None
-            }
-        };
+        let locate = |s: &str| locate_in(md, s);
 
         let push = |_: &str, s: &str| {
             shortcut_links.borrow_mut().push((s.to_owned(), locate(s)));
@@ -911,6 +916,74 @@ pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     links
 }
 
+/// A Rust code block found in some documentation.
+pub struct RustCodeBlock {
+    /// The range of the code inside the block, in the markdown it was found in. `None` when the
+    /// code doesn't appear verbatim in the markdown.
+    pub range: Option<Range<usize>>,
+    /// The code itself, with the `# ` of hidden lines removed.
+    pub code: String,
+    pub lang: LangString,
+}
+
+/// Returns the code blocks of `md` that are Rust code, whether they are tested or not.
+pub fn rust_code_blocks(md: &str) -> Vec<RustCodeBlock> {
+    let mut blocks = vec![];
+    if md.is_empty() {
+        return blocks;
+    }
+
+    let mut p = Parser::new(md);
+    while let Some(event) = p.next() {
+        if let Event::Start(Tag::CodeBlock(syntax)) = event {
+            let lang = if syntax.is_empty() {
+                LangString::all_false()
+            } else {
+                LangString::parse(&*syntax, ErrorCodes::No)
+            };
+
+            let mut code = String::new();
+            let mut range = None;
+            let mut located = true;
+            while let Some(event) = p.next() {
+                match event {
+                    Event::End(Tag::CodeBlock(_)) => break,
+                    Event::Text(text) => {
+                        code.push_str(&text);
+                        let text_range = match text {
+                            Cow::Borrowed(s) => locate_in(md, s),
+                            Cow::Owned(_) => None,
+                        };
+                        match (range.take(), text_range) {
+                            (Some(prev), Some(r)) => range = Some(prev.start..r.end),
+                            (None, Some(r)) => range = Some(r),
+                            (_, None) => located = false,
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            if !lang.rust {
+                continue;
+            }
+            // The last line of the code is followed by the newline before the closing fence.
+            let range = if located {
+                range.map(|r| {
+                    let code = md[r.clone()].trim_end_matches(|c| c == '\n' || c == '\r');
+                    r.start..r.start + code.len()
+                })
+            } else {
+                None
+            };
+            let code = code.lines().map(|l| map_line(l).for_code()).collect::<Vec<_>>().join("\n");
+            blocks.push(RustCodeBlock { range, code, lang });
+        }
+    }
+
+    blocks
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct IdMap {
     map: FxHashMap<String, usize>,
@@ -970,7 +1043,7 @@ fn test_unique_id() {
 #[cfg(test)]
 mod tests {
     use super::{ErrorCodes, LangString, Markdown, MarkdownHtml, IdMap};
-    use super::plain_summary_line;
+    use super::{plain_summary_line, rust_code_blocks};
     use std::cell::RefCell;
     use syntax::edition::Edition;
 
@@ -1091,4 +1164,15 @@ mod tests {
         t("Struct<'a, T>", "<p>Struct&lt;'a, T&gt;</p>\n");
         t("Struct<br>", "<p>Struct&lt;br&gt;</p>\n");
     }
+
+    #[test]
+    fn test_rust_code_blocks() {
+        let md = "Some text.\n\n```\nlet x = 1;\n# let y = 2;\n```\n\n```text\nnot rust\n```\n";
+        let blocks = rust_code_blocks(md);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].code, "let x = 1;\nlet y = 2;");
+        let range = blocks[0].range.clone().unwrap();
+        assert_eq!(&md[range], "let x = 1;\n# let y = 2;");
+        assert!(!blocks[0].lang.ignore);
+    }
 }
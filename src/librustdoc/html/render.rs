@@ -324,6 +324,7 @@ pub struct Cache {
     stack: Vec<String>,
     parent_stack: Vec<DefId>,
     parent_is_trait_impl: bool,
+    signature_parent: Option<SignatureParent>,
     search_index: Vec<IndexItem>,
     stripped_mod: bool,
     deref_trait_did: Option<DefId>,
@@ -336,7 +337,7 @@ pub struct Cache {
     // then the fully qualified name of the structure isn't presented in `paths`
     // yet when its implementation methods are being indexed. Caches such methods
     // and their parent id here and indexes them at the end of crate parsing.
-    orphan_impl_items: Vec<(DefId, clean::Item)>,
+    orphan_impl_items: Vec<(DefId, clean::Item, Option<IndexItemSignature>)>,
 
     // Similarly to `orphan_impl_items`, sometimes trait impls are picked up
     // even though the trait itself is not exported. This can happen if a trait
@@ -400,19 +401,23 @@ struct IndexItem {
     parent: Option<DefId>,
     parent_idx: Option<usize>,
     search_type: Option<IndexItemFunctionType>,
+    signature: Option<IndexItemSignature>,
 }
 
 impl ToJson for IndexItem {
     fn to_json(&self) -> Json {
         assert_eq!(self.parent.is_some(), self.parent_idx.is_some());
 
-        let mut data = Vec::with_capacity(6);
+        let mut data = Vec::with_capacity(7);
         data.push((self.ty as usize).to_json());
         data.push(self.name.to_json());
         data.push(self.path.to_json());
         data.push(self.desc.to_json());
         data.push(self.parent_idx.to_json());
         data.push(self.search_type.to_json());
+        if let Some(ref signature) = self.signature {
+            data.push(signature.to_json());
+        }
 
         Json::Array(data)
     }
@@ -464,6 +469,69 @@ impl ToJson for IndexItemFunctionType {
     }
 }
 
+/// A type in the normalized signature of a function, as used by the type signature search.
+///
+/// References and pointers are left out, since the search doesn't tell `&T` from `T`, and type
+/// parameters are replaced by their position in the signature's list of generics, so that the
+/// frontend can unify them with the query.
+#[derive(Debug)]
+enum SignatureType {
+    /// A type with a name, like `Vec<T>`, `usize` or `slice`, and its type arguments.
+    Named(String, Vec<SignatureType>),
+    /// A type parameter.
+    Param(usize),
+    /// Anything the search can't express, like function pointers or associated types.
+    Unknown,
+}
+
+impl ToJson for SignatureType {
+    fn to_json(&self) -> Json {
+        match *self {
+            SignatureType::Named(ref name, ref args) => {
+                let mut data = Vec::with_capacity(2);
+                data.push(name.to_json());
+                if !args.is_empty() {
+                    data.push(args.to_json());
+                }
+                Json::Array(data)
+            }
+            SignatureType::Param(index) => index.to_json(),
+            SignatureType::Unknown => Json::Null,
+        }
+    }
+}
+
+/// Full normalized signature of a function or method for the type signature search.
+#[derive(Debug)]
+struct IndexItemSignature {
+    inputs: Vec<SignatureType>,
+    /// The return type, `()` for functions without one.
+    output: SignatureType,
+    /// The names of the traits bounding each type parameter, including those of the surrounding
+    /// impl or trait and `Self` in a trait.
+    bounds: Vec<Vec<String>>,
+}
+
+impl ToJson for IndexItemSignature {
+    fn to_json(&self) -> Json {
+        let mut data = Vec::with_capacity(3);
+        data.push(self.inputs.to_json());
+        data.push(self.output.to_json());
+        data.push(self.bounds.to_json());
+        Json::Array(data)
+    }
+}
+
+/// The impl or trait a method is in, which the type signature search needs to know what `Self`
+/// and the parameters of the impl or trait are.
+#[derive(Clone, Debug)]
+struct SignatureParent {
+    /// The type of an impl; `None` in a trait, where `Self` is a parameter bounded by the trait.
+    self_type: Option<clean::Type>,
+    trait_name: Option<String>,
+    generics: clean::Generics,
+}
+
 thread_local!(static CACHE_KEY: RefCell<Arc<Cache>> = Default::default());
 thread_local!(pub static CURRENT_LOCATION_KEY: RefCell<Vec<String>> = RefCell::new(Vec::new()));
 
@@ -608,6 +676,7 @@ pub fn run(mut krate: clean::Crate,
         parent_stack: Vec::new(),
         search_index: Vec::new(),
         parent_is_trait_impl: false,
+        signature_parent: None,
         extern_locations: Default::default(),
         primitive_locations: Default::default(),
         stripped_mod: false,
@@ -687,12 +756,12 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
     let mut crate_paths = Vec::<Json>::new();
 
     let Cache { ref mut search_index,
-                ref orphan_impl_items,
+                ref mut orphan_impl_items,
                 ref mut paths, .. } = *cache;
 
     // Attach all orphan items to the type's definition if the type
     // has since been learned.
-    for &(did, ref item) in orphan_impl_items {
+    for (did, item, signature) in orphan_impl_items.drain(..) {
         if let Some(&(ref fqp, _)) = paths.get(&did) {
             search_index.push(IndexItem {
                 ty: item.type_(),
@@ -702,6 +771,7 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
                 parent: Some(did),
                 parent_idx: None,
                 search_type: get_index_search_type(&item),
+                signature,
             });
         }
     }
@@ -1405,13 +1475,16 @@ impl DocFolder for Cache {
                             parent,
                             parent_idx: None,
                             search_type: get_index_search_type(&item),
+                            signature: get_index_signature(&item,
+                                                           self.signature_parent.as_ref()),
                         });
                     }
                 }
                 (Some(parent), None) if is_inherent_impl_item => {
                     // We have a parent, but we don't know where they're
                     // defined yet. Wait for later to index this item.
-                    self.orphan_impl_items.push((parent, item.clone()));
+                    let signature = get_index_signature(&item, self.signature_parent.as_ref());
+                    self.orphan_impl_items.push((parent, item.clone(), signature));
                 }
                 _ => {}
             }
@@ -1467,6 +1540,25 @@ impl DocFolder for Cache {
 
         // Maintain the parent stack
         let orig_parent_is_trait_impl = self.parent_is_trait_impl;
+        let orig_signature_parent = match item.inner {
+            clean::TraitItem(ref t) => {
+                let parent = SignatureParent {
+                    self_type: None,
+                    trait_name: item.name.clone(),
+                    generics: t.generics.clone(),
+                };
+                Some(mem::replace(&mut self.signature_parent, Some(parent)))
+            }
+            clean::ImplItem(ref i) => {
+                let parent = SignatureParent {
+                    self_type: Some(i.for_.clone()),
+                    trait_name: None,
+                    generics: i.generics.clone(),
+                };
+                Some(mem::replace(&mut self.signature_parent, Some(parent)))
+            }
+            _ => None,
+        };
         let parent_pushed = match item.inner {
             clean::TraitItem(..) | clean::EnumItem(..) | clean::ForeignTypeItem |
             clean::StructItem(..) | clean::UnionItem(..) => {
@@ -1556,6 +1648,9 @@ impl DocFolder for Cache {
         if parent_pushed { self.parent_stack.pop().unwrap(); }
         self.stripped_mod = orig_stripped_mod;
         self.parent_is_trait_impl = orig_parent_is_trait_impl;
+        if let Some(orig) = orig_signature_parent {
+            self.signature_parent = orig;
+        }
         ret
     }
 }
@@ -1597,6 +1692,7 @@ impl<'a> Cache {
                                 parent: None,
                                 parent_idx: None,
                                 search_type: get_index_search_type(&item),
+                                signature: get_index_signature(&item, None),
                             });
             }
         }
@@ -4783,6 +4879,137 @@ fn get_index_type(clean_type: &clean::Type) -> Type {
     t
 }
 
+fn get_index_signature(item: &clean::Item,
+                       parent: Option<&SignatureParent>) -> Option<IndexItemSignature> {
+    let (decl, generics) = match item.inner {
+        clean::FunctionItem(ref f) => (&f.decl, &f.generics),
+        clean::MethodItem(ref m) => (&m.decl, &m.generics),
+        clean::TyMethodItem(ref m) => (&m.decl, &m.generics),
+        _ => return None
+    };
+
+    let mut builder = SignatureBuilder {
+        self_type: parent.and_then(|p| p.self_type.as_ref()),
+        params: Vec::new(),
+        bounds: Vec::new(),
+    };
+    if let Some(parent) = parent {
+        if parent.self_type.is_none() {
+            let trait_name = parent.trait_name.clone().unwrap_or_default();
+            builder.params.push("Self".to_string());
+            builder.bounds.push(vec![trait_name.to_ascii_lowercase()]);
+        }
+        builder.add_generics(&parent.generics);
+    }
+    builder.add_generics(generics);
+
+    let inputs = decl.inputs.values.iter().map(|arg| builder.convert(&arg.type_)).collect();
+    let output = match decl.output {
+        clean::FunctionRetTy::Return(ref return_type) => builder.convert(return_type),
+        clean::FunctionRetTy::DefaultReturn => SignatureType::Named("()".to_string(), Vec::new()),
+    };
+
+    Some(IndexItemSignature { inputs, output, bounds: builder.bounds })
+}
+
+/// Normalizes the types of a signature into `SignatureType`s, numbering its type parameters.
+struct SignatureBuilder<'a> {
+    self_type: Option<&'a clean::Type>,
+    params: Vec<String>,
+    bounds: Vec<Vec<String>>,
+}
+
+impl<'a> SignatureBuilder<'a> {
+    fn add_generics(&mut self, generics: &clean::Generics) {
+        for param in &generics.params {
+            if let clean::GenericParamDefKind::Type { ref bounds, .. } = param.kind {
+                self.params.push(param.name.clone());
+                self.bounds.push(bound_names(bounds));
+            }
+        }
+        for pred in &generics.where_predicates {
+            if let clean::WherePredicate::BoundPredicate {
+                ty: clean::Generic(ref name), ref bounds
+            } = *pred {
+                if let Some(index) = self.params.iter().position(|p| p == name) {
+                    self.bounds[index].extend(bound_names(bounds));
+                }
+            }
+        }
+    }
+
+    fn convert(&mut self, ty: &clean::Type) -> SignatureType {
+        match *ty {
+            clean::Generic(ref name) => {
+                match self.self_type {
+                    Some(self_type) if name == "Self" => self.convert(self_type),
+                    _ => match self.params.iter().position(|p| p == name) {
+                        Some(index) => SignatureType::Param(index),
+                        None => SignatureType::Unknown,
+                    },
+                }
+            }
+            clean::ResolvedPath { is_generic: true, .. } => SignatureType::Unknown,
+            clean::ResolvedPath { ref path, .. } => {
+                let segment = match path.segments.last() {
+                    Some(segment) => segment,
+                    None => return SignatureType::Unknown,
+                };
+                if let Some(self_type) = self.self_type {
+                    if segment.name == "Self" {
+                        return self.convert(self_type);
+                    }
+                }
+                let args = match segment.args {
+                    clean::GenericArgs::AngleBracketed { ref types, .. } => {
+                        types.iter().map(|t| self.convert(t)).collect()
+                    }
+                    clean::GenericArgs::Parenthesized { ref inputs, ref output } => {
+                        inputs.iter().chain(output).map(|t| self.convert(t)).collect()
+                    }
+                };
+                SignatureType::Named(segment.name.to_ascii_lowercase(), args)
+            }
+            clean::Primitive(clean::PrimitiveType::Unit) => {
+                SignatureType::Named("()".to_string(), Vec::new())
+            }
+            clean::Primitive(p) => SignatureType::Named(p.as_str().to_string(), Vec::new()),
+            clean::Tuple(ref types) if types.is_empty() => {
+                SignatureType::Named("()".to_string(), Vec::new())
+            }
+            clean::Tuple(ref types) => {
+                let args = types.iter().map(|t| self.convert(t)).collect();
+                SignatureType::Named("tuple".to_string(), args)
+            }
+            clean::Slice(ref t) => SignatureType::Named("slice".to_string(), vec![self.convert(t)]),
+            clean::Array(ref t, _) => {
+                SignatureType::Named("array".to_string(), vec![self.convert(t)])
+            }
+            clean::Unique(ref t) => SignatureType::Named("box".to_string(), vec![self.convert(t)]),
+            clean::Never => SignatureType::Named("!".to_string(), Vec::new()),
+            clean::BorrowedRef { ref type_, .. } => self.convert(type_),
+            clean::RawPointer(_, ref t) => self.convert(t),
+            // `impl Trait` works like an anonymous type parameter.
+            clean::ImplTrait(ref bounds) => {
+                self.params.push(String::new());
+                self.bounds.push(bound_names(bounds));
+                SignatureType::Param(self.params.len() - 1)
+            }
+            clean::BareFunction(..) | clean::QPath { .. } | clean::Infer => SignatureType::Unknown,
+        }
+    }
+}
+
+/// The lowercased names of the traits in `bounds`, leaving out `?Sized` and lifetimes.
+fn bound_names(bounds: &[clean::GenericBound]) -> Vec<String> {
+    bounds.iter().filter_map(|bound| match *bound {
+        clean::GenericBound::TraitBound(ref poly, hir::TraitBoundModifier::None) => {
+            get_index_type_name(&poly.trait_, false).map(|s| s.to_ascii_lowercase())
+        }
+        _ => None,
+    }).collect()
+}
+
 /// Returns a list of all paths used in the type.
 /// This is used to help deduplicate imported impls
 /// for reexported types. If any of the contained
@@ -841,6 +841,259 @@
                 return itemTypes[ty.ty] + ty.path + ty.name;
             }
 
+            function skipWhitespace(parser) {
+                while (parser.pos < parser.str.length &&
+                       /\s/.test(parser.str.charAt(parser.pos))) {
+                    parser.pos += 1;
+                }
+            }
+
+            // Parses types separated by commas up to the `end` character, which is consumed.
+            function parseSigTypeList(parser, end) {
+                var types = [];
+                skipWhitespace(parser);
+                if (parser.str.charAt(parser.pos) === end) {
+                    parser.pos += 1;
+                    return types;
+                }
+                while (true) {
+                    var ty = parseSigType(parser);
+                    if (ty === null) {
+                        return null;
+                    }
+                    types.push(ty);
+                    skipWhitespace(parser);
+                    var c = parser.str.charAt(parser.pos);
+                    parser.pos += 1;
+                    if (c === end) {
+                        return types;
+                    } else if (c !== ",") {
+                        return null;
+                    }
+                }
+            }
+
+            // Parses a type of a signature query, like `vec<t>`, `&[u8]` or `(a, b)`, into the
+            // same shape as the signatures in the index: references and pointers are left out,
+            // and only the last segment of a path is kept. `_` matches any type, and single
+            // letters are type variables, which have to stand for the same type everywhere.
+            function parseSigType(parser) {
+                skipWhitespace(parser);
+                var c = parser.str.charAt(parser.pos);
+                if (c === "&" || c === "*") {
+                    parser.pos += 1;
+                    skipWhitespace(parser);
+                    var qualifier = parser.str.substr(parser.pos).match(/^(mut|const)\b/);
+                    if (qualifier) {
+                        parser.pos += qualifier[0].length;
+                    }
+                    return parseSigType(parser);
+                } else if (c === "[") {
+                    parser.pos += 1;
+                    var elem = parseSigType(parser);
+                    if (elem === null) {
+                        return null;
+                    }
+                    skipWhitespace(parser);
+                    var name = "slice";
+                    if (parser.str.charAt(parser.pos) === ";") {
+                        parser.pos = parser.str.indexOf("]", parser.pos);
+                        name = "array";
+                    }
+                    if (parser.pos < 0 || parser.str.charAt(parser.pos) !== "]") {
+                        return null;
+                    }
+                    parser.pos += 1;
+                    return {name: name, generics: [elem]};
+                } else if (c === "(") {
+                    parser.pos += 1;
+                    var elems = parseSigTypeList(parser, ")");
+                    if (elems === null) {
+                        return null;
+                    } else if (elems.length === 0) {
+                        return {name: "()", generics: []};
+                    } else if (elems.length === 1) {
+                        return elems[0];
+                    }
+                    return {name: "tuple", generics: elems};
+                } else if (c === "!") {
+                    parser.pos += 1;
+                    return {name: "!", generics: []};
+                }
+
+                var path = parser.str.substr(parser.pos)
+                                     .match(/^(?:(?:impl|dyn)\s+)?(?:\w+\s*::\s*)*(\w+)/);
+                if (!path) {
+                    return null;
+                }
+                parser.pos += path[0].length;
+                var ty = {
+                    name: path[1],
+                    generics: [],
+                    isVar: path[1].length === 1 && path[1] !== "_",
+                };
+                skipWhitespace(parser);
+                if (parser.str.charAt(parser.pos) === "<") {
+                    parser.pos += 1;
+                    ty.generics = parseSigTypeList(parser, ">");
+                    if (ty.generics === null) {
+                        return null;
+                    }
+                }
+                return ty;
+            }
+
+            // Parses a query like `vec<t> -> usize`. `*` or nothing on either side of the arrow
+            // means that side can be anything, and is `null` in the result.
+            function parseSignatureQuery(query) {
+                var arrow = query.indexOf("->");
+                var inputs = query.substr(0, arrow).trim();
+                var output = query.substr(arrow + 2).trim();
+                var sig = {inputs: null, output: null};
+                var parser;
+
+                if (inputs !== "" && inputs !== "*") {
+                    parser = {str: inputs + ")", pos: 0};
+                    sig.inputs = parseSigTypeList(parser, ")");
+                    if (sig.inputs === null || parser.pos !== parser.str.length) {
+                        return null;
+                    }
+                }
+                if (output !== "" && output !== "*") {
+                    parser = {str: output, pos: 0};
+                    sig.output = parseSigType(parser);
+                    skipWhitespace(parser);
+                    if (sig.output === null || parser.pos !== parser.str.length) {
+                        return null;
+                    }
+                }
+                return sig;
+            }
+
+            // Returns a copy of `env` where `key` of the `table` ("vars" or "params") is bound.
+            function bindSigVar(env, table, key, value) {
+                var copy = {vars: {}, params: {}};
+                for (var t in copy) {
+                    for (var k in env[t]) {
+                        if (env[t].hasOwnProperty(k)) {
+                            copy[t][k] = env[t][k];
+                        }
+                    }
+                }
+                copy[table][key] = value;
+                return copy;
+            }
+
+            // Unifies a type of the query with a type of a signature from the index, where type
+            // parameters are numbers and unknown types are `null`. `env` holds what the query's
+            // type variables and the signature's type parameters have been bound to so far.
+            // Returns the cost of the match and the new bindings, or `null` if the types don't
+            // match.
+            function unifySigType(query, ty, bounds, env) {
+                if (query.name === "_") {
+                    return {cost: 0, env: env};
+                } else if (ty === null) {
+                    // The index doesn't know what this type is, so it might match.
+                    return {cost: 2, env: env};
+                }
+
+                if (query.isVar === true) {
+                    var tyKey = JSON.stringify(ty);
+                    if (env.vars[query.name] !== undefined) {
+                        return env.vars[query.name] === tyKey ? {cost: 0, env: env} : null;
+                    }
+                    env = bindSigVar(env, "vars", query.name, tyKey);
+                    if (typeof ty !== "number") {
+                        // A variable standing for a concrete type makes for a weaker match.
+                        return {cost: 1, env: env};
+                    }
+                }
+
+                if (typeof ty === "number") {
+                    var queryKey = JSON.stringify(query);
+                    if (env.params[ty] !== undefined) {
+                        return env.params[ty] === queryKey ? {cost: 0, env: env} : null;
+                    }
+                    env = bindSigVar(env, "params", ty, queryKey);
+                    // Asking for a trait the parameter is bounded by is as good as a variable,
+                    // any other type is a possible instantiation of the parameter.
+                    if (query.isVar === true || bounds[ty].indexOf(query.name) !== -1) {
+                        return {cost: 0, env: env};
+                    }
+                    return {cost: 1, env: env};
+                }
+
+                if (ty[NAME] !== query.name) {
+                    return null;
+                }
+                var args = ty.length > GENERICS_DATA ? ty[GENERICS_DATA] : [];
+                if (query.generics.length > args.length) {
+                    return null;
+                }
+                var cost = 0;
+                for (var i = 0; i < query.generics.length; ++i) {
+                    var res = unifySigType(query.generics[i], args[i], bounds, env);
+                    if (res === null) {
+                        return null;
+                    }
+                    cost += res.cost;
+                    env = res.env;
+                }
+                return {cost: cost, env: env};
+            }
+
+            // Finds the cheapest way to match each of the query's inputs, starting at `pos`, with
+            // a different input of the signature, in any order.
+            function matchSigInputs(queries, pos, inputs, used, bounds, env) {
+                if (pos === queries.length) {
+                    return {cost: 0, env: env};
+                }
+                var best = null;
+                for (var i = 0; i < inputs.length; ++i) {
+                    if (used[i] === true) {
+                        continue;
+                    }
+                    var res = unifySigType(queries[pos], inputs[i], bounds, env);
+                    if (res === null) {
+                        continue;
+                    }
+                    used[i] = true;
+                    var rest = matchSigInputs(queries, pos + 1, inputs, used, bounds, res.env);
+                    used[i] = false;
+                    if (rest !== null && (best === null || res.cost + rest.cost < best.cost)) {
+                        best = {cost: res.cost + rest.cost, env: rest.env};
+                    }
+                }
+                return best;
+            }
+
+            // Matches a parsed signature query against the signature of an item, an array of
+            // [(Array) inputs, (Array | Number | null) output, (Array) trait bounds of each type
+            // parameter]. Returns the cost of the match, lower being better, or -1.
+            function checkSignature(query, sig, checkInputs, checkOutput) {
+                var env = {vars: {}, params: {}};
+                var cost = 0;
+                var res;
+
+                if (checkOutput === true && query.output !== null) {
+                    res = unifySigType(query.output, sig[1], sig[2], env);
+                    if (res === null) {
+                        return -1;
+                    }
+                    cost += res.cost;
+                    env = res.env;
+                }
+                if (checkInputs === true && query.inputs !== null) {
+                    res = matchSigInputs(query.inputs, 0, sig[0], [], sig[2], env);
+                    if (res === null) {
+                        return -1;
+                    }
+                    // Functions taking arguments that weren't asked for match less well.
+                    cost += res.cost + sig[0].length - query.inputs.length;
+                }
+                return cost;
+            }
+
             // quoted values mean literal search
             var nSearchWords = searchWords.length;
             if ((val.charAt(0) === "\"" || val.charAt(0) === "'") &&
@@ -891,67 +1144,46 @@
                 query.search = val;
             // searching by type
             } else if (val.search("->") > -1) {
-                var trimmer = function(s) { return s.trim(); };
-                var parts = val.split("->").map(trimmer);
-                var input = parts[0];
-                // sort inputs so that order does not matter
-                var inputs = input.split(",").map(trimmer).sort();
-                for (var i = 0; i < inputs.length; ++i) {
-                    inputs[i] = extractGenerics(inputs[i]);
-                }
-                var output = extractGenerics(parts[1]);
+                var sigQuery = parseSignatureQuery(val);
 
-                for (var i = 0; i < nSearchWords; ++i) {
-                    var type = searchIndex[i].type;
+                for (var i = 0; sigQuery !== null && i < nSearchWords; ++i) {
                     var ty = searchIndex[i];
-                    if (!type) {
+                    if (!ty.sig || typePassesFilter(typeFilter, ty.ty) === false) {
                         continue;
                     }
                     var fullId = generateId(ty);
 
-                    // allow searching for void (no output) functions as well
-                    var typeOutput = type.length > OUTPUT_DATA ? type[OUTPUT_DATA].name : "";
-                    var returned = checkReturned(ty, output, true);
-                    if (output.name === "*" || returned === true) {
-                        var in_args = false;
-                        var module = false;
-
-                        if (input === "*") {
-                            module = true;
-                        } else {
-                            var allFound = true;
-                            for (var it = 0; allFound === true && it < inputs.length; it++) {
-                                allFound = checkType(type, inputs[it], true);
-                            }
-                            in_args = allFound;
-                        }
-                        if (in_args === true) {
-                            results_in_args[fullId] = {
-                                id: i,
-                                index: -1,
-                                dontValidate: true,
-                            };
-                        }
-                        if (returned === true) {
-                            results_returned[fullId] = {
-                                id: i,
-                                index: -1,
-                                dontValidate: true,
-                            };
-                        }
-                        if (module === true) {
-                            results[fullId] = {
-                                id: i,
-                                index: -1,
-                                dontValidate: true,
-                            };
-                        }
+                    var lev = checkSignature(sigQuery, ty.sig, true, true);
+                    if (lev !== -1) {
+                        results[fullId] = {
+                            id: i,
+                            index: -1,
+                            lev: lev,
+                            dontValidate: true,
+                        };
+                    }
+                    if (sigQuery.inputs !== null &&
+                            (lev = checkSignature(sigQuery, ty.sig, true, false)) !== -1) {
+                        results_in_args[fullId] = {
+                            id: i,
+                            index: -1,
+                            lev: lev,
+                            dontValidate: true,
+                        };
+                    }
+                    if (sigQuery.output !== null &&
+                            (lev = checkSignature(sigQuery, ty.sig, false, true)) !== -1) {
+                        results_returned[fullId] = {
+                            id: i,
+                            index: -1,
+                            lev: lev,
+                            dontValidate: true,
+                        };
                     }
                 }
-                query.inputs = inputs.map(function(input) {
-                    return input.name;
-                });
-                query.output = output.name;
+                var parts = val.split("->");
+                query.inputs = [parts[0].trim()];
+                query.output = parts[1].trim();
             } else {
                 query.inputs = [val];
                 query.output = val;
@@ -1402,7 +1634,8 @@
         }
 
         function execSearch(query, searchWords) {
-            var queries = query.raw.split(",");
+            // Commas in a type signature separate arguments rather than queries.
+            var queries = query.raw.indexOf("->") > -1 ? [query.raw] : query.raw.split(",");
             var results = {
                 'in_args': [],
                 'returned': [],
@@ -1527,6 +1760,7 @@
                 //              (String) description,
                 //              (Number | null) the parent path index to `paths`]
                 //              (Object | null) the type of the function (if any)
+                //              (Array | undefined) the full signature of the function (if any)
                 var items = rawSearchIndex[crate].items;
                 // an array of [(Number) item type,
                 //              (String) name]
@@ -1551,7 +1785,7 @@
                     var rawRow = items[i];
                     var row = {crate: crate, ty: rawRow[0], name: rawRow[1],
                                path: rawRow[2] || lastPath, desc: rawRow[3],
-                               parent: paths[rawRow[4]], type: rawRow[5]};
+                               parent: paths[rawRow[4]], type: rawRow[5], sig: rawRow[6]};
                     searchIndex.push(row);
                     if (typeof row.name === "string") {
                         var word = row.name.toLowerCase();
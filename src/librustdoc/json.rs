@@ -0,0 +1,664 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The JSON backend, selected with `--output-format json -Z unstable-options`.
+//!
+//! Instead of a tree of HTML pages this writes a single `<crate>.json` file into the output
+//! directory, describing the cleaned crate after all passes have run. The top-level object is:
+//!
+//! ```text
+//! {
+//!     "format_version": 1,
+//!     "crate": { "name": "foo", "version": "0.1.0" | null },
+//!     "root": "0:0",
+//!     "index": { "<id>": <item>, ... },
+//!     "paths": { "<id>": { "crate_id": 0, "path": ["foo", "Bar"], "kind": "struct" }, ... },
+//!     "external_crates": { "<crate id>": { "name": "std" }, ... }
+//! }
+//! ```
+//!
+//! An id is `"<crate id>:<def index>"`. They are only meaningful within one file; `index` holds
+//! every documented item, while `paths` also covers items of other crates that are referenced by
+//! types, impls or intra-doc links. Every `<item>` has the shape:
+//!
+//! ```text
+//! {
+//!     "id": "0:5",
+//!     "crate_id": 0,
+//!     "name": "Bar" | null,
+//!     "kind": "struct",
+//!     "visibility": "public" | "crate" | "default" | { "restricted": { "parent": id, "path" } },
+//!     "docs": "..." | null,
+//!     "links": [ { "text": "Baz", "id": id | null, "fragment": "..." | null }, ... ],
+//!     "span": { "filename", "begin": [line, col], "end": [line, col] } | null,
+//!     "stability": { "level": "stable" | "unstable", "feature", "since", "issue", "reason" }
+//!                  | null,
+//!     "deprecation": { "since", "note" } | null,
+//!     "non_exhaustive": true | false,
+//!     "inner": { ... }
+//! }
+//! ```
+//!
+//! `kind` uses the same names as the HTML search index (`mod`, `struct`, `fn`, `method`,
+//! `tymethod`, `impl`, ...), and `inner` holds what is specific to that kind of item: generics and
+//! where clauses, function signatures, fields and variants, trait items, or the trait, self type
+//! and items of an impl. Types and bounds are serialized structurally as objects with a `kind`
+//! field, so consumers never have to parse Rust syntax.
+//!
+//! Any change to this layout that is not purely additive must bump `FORMAT_VERSION`.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::prelude::*;
+use std::io::BufWriter;
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::middle::stability;
+use rustc::util::nodemap::FxHashMap;
+use serialize::json::{object, Json, ToJson};
+use syntax::ext::base::MacroKind;
+
+use clean::{self, GetDefId};
+use config::RenderOptions;
+use doctree;
+use html::item_type::ItemType;
+use html::render::{Error, RenderInfo};
+
+/// Version of the schema described above.
+pub const FORMAT_VERSION: u64 = 1;
+
+fn array<T: ToJson>(items: &[T]) -> Json {
+    Json::Array(items.iter().map(|i| i.to_json()).collect())
+}
+
+fn id(did: DefId) -> String {
+    format!("{}:{}", did.krate.as_u32(), did.index.as_raw_u32())
+}
+
+fn id_json(did: Option<DefId>) -> Json {
+    did.map_or(Json::Null, |did| Json::String(id(did)))
+}
+
+/// Writes the JSON description of `krate` into `<output>/<crate name>.json`.
+pub fn run(krate: clean::Crate,
+           options: RenderOptions,
+           renderinfo: RenderInfo) -> Result<(), Error> {
+    let dst = options.output;
+    fs::create_dir_all(&dst).map_err(|e| Error::new(e, &dst))?;
+
+    let output = krate_json(&krate, &renderinfo);
+    let path = dst.join(format!("{}.json", krate.name));
+    let mut w = BufWriter::new(File::create(&path).map_err(|e| Error::new(e, &path))?);
+    write!(w, "{}", output).map_err(|e| Error::new(e, &path))?;
+    w.flush().map_err(|e| Error::new(e, &path))
+}
+
+/// Builds the JSON description of `krate` described in the module documentation.
+pub fn krate_json(krate: &clean::Crate, renderinfo: &RenderInfo) -> Json {
+    let mut collector = Collector {
+        index: Default::default(),
+        paths: Default::default(),
+        impls: Default::default(),
+        implementors: Default::default(),
+        path_stack: Vec::new(),
+    };
+    let root = match krate.module {
+        Some(ref module) => {
+            collector.collect_impls(module);
+            collector.add_item(module);
+            id_json(Some(module.def_id))
+        }
+        None => Json::Null,
+    };
+
+    let mut paths = collector.paths;
+    for (&did, &(ref fqp, kind)) in &renderinfo.external_paths {
+        paths.entry(id(did)).or_insert_with(|| path_json(did, fqp, ItemType::from(kind)));
+    }
+
+    let external_crates = krate.externs.iter().map(|&(cnum, ref ext)| {
+        (cnum.as_u32().to_string(), object(vec![("name", ext.name.to_json())]))
+    }).collect();
+
+    object(vec![
+        ("format_version", Json::U64(FORMAT_VERSION)),
+        ("crate", object(vec![
+            ("name", krate.name.to_json()),
+            ("version", krate.version.to_json()),
+        ])),
+        ("root", root),
+        ("index", Json::Object(collector.index)),
+        ("paths", Json::Object(paths)),
+        ("external_crates", Json::Object(external_crates)),
+    ])
+}
+
+fn path_json(did: DefId, fqp: &[String], ty: ItemType) -> Json {
+    object(vec![
+        ("crate_id", Json::U64(did.krate.as_u32() as u64)),
+        ("path", fqp.to_json()),
+        ("kind", ty.css_class().to_json()),
+    ])
+}
+
+struct Collector {
+    index: BTreeMap<String, Json>,
+    paths: BTreeMap<String, Json>,
+    /// Impls of each type, by the `DefId` of the type.
+    impls: FxHashMap<DefId, Vec<String>>,
+    /// Impls of each trait, by the `DefId` of the trait.
+    implementors: FxHashMap<DefId, Vec<String>>,
+    /// Path of the module currently being visited.
+    path_stack: Vec<String>,
+}
+
+impl Collector {
+    /// Records which impls belong to which types and traits, so that those can list them.
+    fn collect_impls(&mut self, item: &clean::Item) {
+        match item.inner {
+            clean::ModuleItem(ref m) => {
+                for item in &m.items {
+                    self.collect_impls(item);
+                }
+            }
+            clean::ImplItem(ref i) => {
+                if let Some(did) = i.for_.def_id() {
+                    self.impls.entry(did).or_default().push(id(item.def_id));
+                }
+                if let Some(did) = i.trait_.def_id() {
+                    self.implementors.entry(did).or_default().push(id(item.def_id));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds `item` and everything it contains to the index.
+    fn add_item(&mut self, item: &clean::Item) {
+        if item.is_stripped() {
+            return;
+        }
+        let ty = ItemType::from(item);
+        let is_module = item.is_mod();
+        if let (true, Some(name)) = (is_module, item.name.as_ref()) {
+            self.path_stack.push(name.clone());
+            self.paths.insert(id(item.def_id), path_json(item.def_id, &self.path_stack, ty));
+        }
+
+        let inner = self.inner_json(item);
+        let json = object(vec![
+            ("id", id(item.def_id).to_json()),
+            ("crate_id", Json::U64(item.def_id.krate.as_u32() as u64)),
+            ("name", item.name.to_json()),
+            ("kind", ty.css_class().to_json()),
+            ("visibility", visibility_json(&item.visibility)),
+            ("docs", item.attrs.collapsed_doc_value().to_json()),
+            ("links", Json::Array(item.attrs.links.iter().map(|&(ref text, did, ref frag)| {
+                object(vec![
+                    ("text", text.to_json()),
+                    ("id", id_json(did)),
+                    ("fragment", frag.to_json()),
+                ])
+            }).collect())),
+            ("span", span_json(&item.source)),
+            ("stability", item.stability.as_ref().map_or(Json::Null, stability_json)),
+            ("deprecation", deprecation_json(item)),
+            ("non_exhaustive", item.is_non_exhaustive().to_json()),
+            ("inner", inner),
+        ]);
+        self.index.insert(id(item.def_id), json);
+
+        if is_module && item.name.is_some() {
+            self.path_stack.pop();
+        }
+    }
+
+    /// Adds the children of an item to the index and returns their ids.
+    fn add_children<'a, I>(&mut self, items: I) -> Json
+        where I: IntoIterator<Item = &'a clean::Item>
+    {
+        Json::Array(items.into_iter().filter(|i| !i.is_stripped()).map(|i| {
+            self.add_item(i);
+            Json::String(id(i.def_id))
+        }).collect())
+    }
+
+    fn impls_of(&self, did: DefId, map: &FxHashMap<DefId, Vec<String>>) -> Json {
+        map.get(&did).map_or(Json::Array(Vec::new()), |ids| ids.to_json())
+    }
+
+    fn inner_json(&mut self, item: &clean::Item) -> Json {
+        match item.inner {
+            clean::ModuleItem(ref m) => {
+                // Only items directly inside a module get a path; fields, variants and associated
+                // items are reached through their parent.
+                for child in &m.items {
+                    let ty = ItemType::from(child);
+                    match child.name {
+                        Some(ref name) if !child.is_stripped() && !child.is_mod() &&
+                                          ty != ItemType::Impl => {
+                            let mut fqp = self.path_stack.clone();
+                            fqp.push(name.clone());
+                            self.paths.insert(id(child.def_id), path_json(child.def_id, &fqp, ty));
+                        }
+                        _ => {}
+                    }
+                }
+                object(vec![
+                    ("is_crate", m.is_crate.to_json()),
+                    ("items", self.add_children(&m.items)),
+                ])
+            }
+            clean::ExternCrateItem(ref name, ref rename) => object(vec![
+                ("name", name.to_json()),
+                ("rename", rename.to_json()),
+            ]),
+            clean::ImportItem(ref import) => {
+                let (name, source, glob) = match *import {
+                    clean::Import::Simple(ref name, ref source) => {
+                        (Json::String(name.clone()), source, false)
+                    }
+                    clean::Import::Glob(ref source) => (Json::Null, source, true),
+                };
+                object(vec![
+                    ("source", format!("{:#}", source.path).to_json()),
+                    ("name", name),
+                    ("id", id_json(source.did)),
+                    ("glob", glob.to_json()),
+                ])
+            }
+            clean::StructItem(ref s) => object(vec![
+                ("struct_type", struct_type_json(s.struct_type)),
+                ("generics", s.generics.to_json()),
+                ("fields", self.add_children(&s.fields)),
+                ("fields_stripped", s.fields_stripped.to_json()),
+                ("impls", self.impls_of(item.def_id, &self.impls)),
+            ]),
+            clean::UnionItem(ref u) => object(vec![
+                ("struct_type", struct_type_json(u.struct_type)),
+                ("generics", u.generics.to_json()),
+                ("fields", self.add_children(&u.fields)),
+                ("fields_stripped", u.fields_stripped.to_json()),
+                ("impls", self.impls_of(item.def_id, &self.impls)),
+            ]),
+            clean::EnumItem(ref e) => object(vec![
+                ("generics", e.generics.to_json()),
+                ("variants", self.add_children(&e.variants)),
+                ("variants_stripped", e.variants_stripped.to_json()),
+                ("impls", self.impls_of(item.def_id, &self.impls)),
+            ]),
+            clean::VariantItem(ref v) => match v.kind {
+                clean::VariantKind::CLike => object(vec![("variant_kind", "plain".to_json())]),
+                clean::VariantKind::Tuple(ref types) => object(vec![
+                    ("variant_kind", "tuple".to_json()),
+                    ("fields", array(types)),
+                ]),
+                clean::VariantKind::Struct(ref s) => object(vec![
+                    ("variant_kind", "struct".to_json()),
+                    ("fields", self.add_children(&s.fields)),
+                    ("fields_stripped", s.fields_stripped.to_json()),
+                ]),
+            },
+            clean::StructFieldItem(ref ty) => object(vec![("type", ty.to_json())]),
+            clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => {
+                fn_json(&f.decl, &f.generics, &f.header, None)
+            }
+            clean::MethodItem(ref m) => fn_json(&m.decl, &m.generics, &m.header, Some(true)),
+            clean::TyMethodItem(ref m) => fn_json(&m.decl, &m.generics, &m.header, Some(false)),
+            clean::TypedefItem(ref t, _) => object(vec![
+                ("type", t.type_.to_json()),
+                ("generics", t.generics.to_json()),
+            ]),
+            clean::ExistentialItem(ref e, _) => object(vec![
+                ("bounds", array(&e.bounds)),
+                ("generics", e.generics.to_json()),
+            ]),
+            clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => object(vec![
+                ("type", s.type_.to_json()),
+                ("mutable", (s.mutability == clean::Mutable).to_json()),
+                ("expr", s.expr.to_json()),
+            ]),
+            clean::ConstantItem(ref c) => object(vec![
+                ("type", c.type_.to_json()),
+                ("expr", c.expr.to_json()),
+            ]),
+            clean::AssociatedConstItem(ref ty, ref default) => object(vec![
+                ("type", ty.to_json()),
+                ("default", default.to_json()),
+            ]),
+            clean::AssociatedTypeItem(ref bounds, ref default) => object(vec![
+                ("bounds", array(bounds)),
+                ("default", default.to_json()),
+            ]),
+            clean::TraitItem(ref t) => object(vec![
+                ("is_auto", t.is_auto.to_json()),
+                ("is_unsafe", (t.unsafety == hir::Unsafety::Unsafe).to_json()),
+                ("generics", t.generics.to_json()),
+                ("bounds", array(&t.bounds)),
+                ("items", self.add_children(&t.items)),
+                ("implementors", self.impls_of(item.def_id, &self.implementors)),
+            ]),
+            clean::ImplItem(ref i) => {
+                let mut provided = i.provided_trait_methods.iter().cloned().collect::<Vec<_>>();
+                provided.sort();
+                object(vec![
+                    ("is_unsafe", (i.unsafety == hir::Unsafety::Unsafe).to_json()),
+                    ("generics", i.generics.to_json()),
+                    ("provided_trait_methods", provided.to_json()),
+                    ("trait", i.trait_.to_json()),
+                    ("for", i.for_.to_json()),
+                    ("items", self.add_children(&i.items)),
+                    ("negative", (i.polarity == Some(clean::ImplPolarity::Negative)).to_json()),
+                    ("synthetic", i.synthetic.to_json()),
+                    ("blanket_impl", i.blanket_impl.to_json()),
+                ])
+            }
+            clean::MacroItem(ref m) => object(vec![("source", m.source.to_json())]),
+            clean::ProcMacroItem(ref m) => object(vec![
+                ("macro_kind", match m.kind {
+                    MacroKind::Bang => "bang",
+                    MacroKind::Attr => "attr",
+                    MacroKind::Derive => "derive",
+                    MacroKind::ProcMacroStub => "stub",
+                }.to_json()),
+                ("helpers", m.helpers.to_json()),
+            ]),
+            clean::PrimitiveItem(p) => object(vec![("name", p.as_str().to_json())]),
+            clean::KeywordItem(ref k) => object(vec![("name", k.to_json())]),
+            clean::ForeignTypeItem => object(vec![]),
+            clean::StrippedItem(..) => Json::Null,
+        }
+    }
+}
+
+fn struct_type_json(ty: doctree::StructType) -> Json {
+    match ty {
+        doctree::Plain => "plain",
+        doctree::Tuple => "tuple",
+        doctree::Unit => "unit",
+    }.to_json()
+}
+
+/// `has_body` is only given for methods, which may or may not provide a default body.
+fn fn_json(decl: &clean::FnDecl,
+           generics: &clean::Generics,
+           header: &hir::FnHeader,
+           has_body: Option<bool>) -> Json {
+    let mut fields = vec![
+        ("decl", decl.to_json()),
+        ("generics", generics.to_json()),
+        ("header", object(vec![
+            ("const", (header.constness == hir::Constness::Const).to_json()),
+            ("unsafe", (header.unsafety == hir::Unsafety::Unsafe).to_json()),
+            ("async", (header.asyncness == hir::IsAsync::Async).to_json()),
+            ("abi", header.abi.name().to_json()),
+        ])),
+    ];
+    if let Some(has_body) = has_body {
+        fields.push(("has_body", has_body.to_json()));
+    }
+    object(fields)
+}
+
+fn visibility_json(vis: &Option<clean::Visibility>) -> Json {
+    match *vis {
+        Some(clean::Public) => "public".to_json(),
+        Some(clean::Visibility::Crate) => "crate".to_json(),
+        Some(clean::Inherited) | None => "default".to_json(),
+        Some(clean::Visibility::Restricted(did, ref path)) => object(vec![
+            ("restricted", object(vec![
+                ("parent", id_json(Some(did))),
+                ("path", format!("{:#}", path).to_json()),
+            ])),
+        ]),
+    }
+}
+
+fn span_json(span: &clean::Span) -> Json {
+    // Items without a source location, like primitives, get `Span::empty()`.
+    if span.loline == 0 {
+        return Json::Null;
+    }
+    object(vec![
+        ("filename", span.filename.to_string().to_json()),
+        ("begin", vec![span.loline, span.locol].to_json()),
+        ("end", vec![span.hiline, span.hicol].to_json()),
+    ])
+}
+
+fn non_empty(s: &str) -> Json {
+    if s.is_empty() { Json::Null } else { s.to_json() }
+}
+
+fn stability_json(stab: &clean::Stability) -> Json {
+    object(vec![
+        ("level", match stab.level {
+            stability::Stable => "stable",
+            stability::Unstable => "unstable",
+        }.to_json()),
+        ("feature", non_empty(&stab.feature)),
+        ("since", non_empty(&stab.since)),
+        ("issue", stab.issue.to_json()),
+        ("reason", non_empty(&stab.unstable_reason)),
+    ])
+}
+
+/// Items can be deprecated either with `#[deprecated]` or, inside the standard library, with
+/// `#[rustc_deprecated]`, which ends up in the stability attribute.
+fn deprecation_json(item: &clean::Item) -> Json {
+    if let Some(ref depr) = item.deprecation {
+        return object(vec![
+            ("since", non_empty(&depr.since)),
+            ("note", non_empty(&depr.note)),
+        ]);
+    }
+    match item.stability {
+        Some(ref stab) if !stab.deprecated_since.is_empty() => object(vec![
+            ("since", non_empty(&stab.deprecated_since)),
+            ("note", non_empty(&stab.deprecated_reason)),
+        ]),
+        _ => Json::Null,
+    }
+}
+
+impl ToJson for clean::Type {
+    fn to_json(&self) -> Json {
+        match *self {
+            clean::ResolvedPath { ref path, ref typarams, did, is_generic } => {
+                let name = path.segments.iter()
+                                        .map(|s| &s.name[..])
+                                        .collect::<Vec<_>>()
+                                        .join("::");
+                object(vec![
+                    ("kind", "resolved_path".to_json()),
+                    ("name", name.to_json()),
+                    ("id", id_json(Some(did))),
+                    ("args", path.segments.last().map_or(Json::Null, |s| s.args.to_json())),
+                    ("bounds", typarams.as_ref().map(|b| array(b)).unwrap_or(Json::Null)),
+                    ("is_generic", is_generic.to_json()),
+                ])
+            }
+            clean::Generic(ref name) => object(vec![
+                ("kind", "generic".to_json()),
+                ("name", name.to_json()),
+            ]),
+            clean::Primitive(p) => object(vec![
+                ("kind", "primitive".to_json()),
+                ("name", p.as_str().to_json()),
+            ]),
+            clean::BareFunction(ref f) => object(vec![
+                ("kind", "function_pointer".to_json()),
+                ("decl", f.decl.to_json()),
+                ("generic_params", array(&f.generic_params)),
+                ("unsafe", (f.unsafety == hir::Unsafety::Unsafe).to_json()),
+                ("abi", f.abi.name().to_json()),
+            ]),
+            clean::Tuple(ref types) => object(vec![
+                ("kind", "tuple".to_json()),
+                ("types", array(types)),
+            ]),
+            clean::Slice(ref ty) => object(vec![
+                ("kind", "slice".to_json()),
+                ("type", ty.to_json()),
+            ]),
+            clean::Array(ref ty, ref len) => object(vec![
+                ("kind", "array".to_json()),
+                ("type", ty.to_json()),
+                ("len", len.to_json()),
+            ]),
+            clean::Never => object(vec![("kind", "never".to_json())]),
+            clean::Infer => object(vec![("kind", "infer".to_json())]),
+            clean::Unique(ref ty) => object(vec![
+                ("kind", "unique".to_json()),
+                ("type", ty.to_json()),
+            ]),
+            clean::RawPointer(mutability, ref ty) => object(vec![
+                ("kind", "raw_pointer".to_json()),
+                ("mutable", (mutability == clean::Mutable).to_json()),
+                ("type", ty.to_json()),
+            ]),
+            clean::BorrowedRef { ref lifetime, mutability, ref type_ } => object(vec![
+                ("kind", "borrowed_ref".to_json()),
+                ("lifetime", lifetime.to_json()),
+                ("mutable", (mutability == clean::Mutable).to_json()),
+                ("type", type_.to_json()),
+            ]),
+            clean::QPath { ref name, ref self_type, ref trait_ } => object(vec![
+                ("kind", "qualified_path".to_json()),
+                ("name", name.to_json()),
+                ("self_type", self_type.to_json()),
+                ("trait", trait_.to_json()),
+            ]),
+            clean::ImplTrait(ref bounds) => object(vec![
+                ("kind", "impl_trait".to_json()),
+                ("bounds", array(bounds)),
+            ]),
+        }
+    }
+}
+
+impl ToJson for clean::Lifetime {
+    fn to_json(&self) -> Json {
+        self.get_ref().to_json()
+    }
+}
+
+impl ToJson for clean::GenericArgs {
+    fn to_json(&self) -> Json {
+        match *self {
+            clean::GenericArgs::AngleBracketed { ref lifetimes, ref types, ref bindings } => {
+                object(vec![
+                    ("angle_bracketed", object(vec![
+                        ("lifetimes", array(lifetimes)),
+                        ("types", array(types)),
+                        ("bindings", Json::Array(bindings.iter().map(|b| object(vec![
+                            ("name", b.name.to_json()),
+                            ("type", b.ty.to_json()),
+                        ])).collect())),
+                    ])),
+                ])
+            }
+            clean::GenericArgs::Parenthesized { ref inputs, ref output } => object(vec![
+                ("parenthesized", object(vec![
+                    ("inputs", array(inputs)),
+                    ("output", output.to_json()),
+                ])),
+            ]),
+        }
+    }
+}
+
+impl ToJson for clean::GenericBound {
+    fn to_json(&self) -> Json {
+        match *self {
+            clean::GenericBound::TraitBound(ref poly, modifier) => object(vec![
+                ("kind", "trait_bound".to_json()),
+                ("trait", poly.trait_.to_json()),
+                ("generic_params", array(&poly.generic_params)),
+                ("modifier", match modifier {
+                    hir::TraitBoundModifier::None => "none",
+                    hir::TraitBoundModifier::Maybe => "maybe",
+                }.to_json()),
+            ]),
+            clean::GenericBound::Outlives(ref lifetime) => object(vec![
+                ("kind", "outlives".to_json()),
+                ("lifetime", lifetime.to_json()),
+            ]),
+        }
+    }
+}
+
+impl ToJson for clean::GenericParamDef {
+    fn to_json(&self) -> Json {
+        match self.kind {
+            clean::GenericParamDefKind::Lifetime => object(vec![
+                ("name", self.name.to_json()),
+                ("kind", "lifetime".to_json()),
+            ]),
+            clean::GenericParamDefKind::Type { ref bounds, ref default, ref synthetic, .. } => {
+                object(vec![
+                    ("name", self.name.to_json()),
+                    ("kind", "type".to_json()),
+                    ("bounds", array(bounds)),
+                    ("default", default.to_json()),
+                    ("synthetic", synthetic.is_some().to_json()),
+                ])
+            }
+        }
+    }
+}
+
+impl ToJson for clean::WherePredicate {
+    fn to_json(&self) -> Json {
+        match *self {
+            clean::WherePredicate::BoundPredicate { ref ty, ref bounds } => object(vec![
+                ("kind", "bound_predicate".to_json()),
+                ("type", ty.to_json()),
+                ("bounds", array(bounds)),
+            ]),
+            clean::WherePredicate::RegionPredicate { ref lifetime, ref bounds } => object(vec![
+                ("kind", "region_predicate".to_json()),
+                ("lifetime", lifetime.to_json()),
+                ("bounds", array(bounds)),
+            ]),
+            clean::WherePredicate::EqPredicate { ref lhs, ref rhs } => object(vec![
+                ("kind", "eq_predicate".to_json()),
+                ("lhs", lhs.to_json()),
+                ("rhs", rhs.to_json()),
+            ]),
+        }
+    }
+}
+
+impl ToJson for clean::Generics {
+    fn to_json(&self) -> Json {
+        object(vec![
+            ("params", array(&self.params)),
+            ("where_predicates", array(&self.where_predicates)),
+        ])
+    }
+}
+
+impl ToJson for clean::FnDecl {
+    fn to_json(&self) -> Json {
+        object(vec![
+            ("inputs", Json::Array(self.inputs.values.iter().map(|arg| object(vec![
+                ("name", arg.name.to_json()),
+                ("type", arg.type_.to_json()),
+            ])).collect())),
+            ("output", match self.output {
+                clean::Return(ref ty) => ty.to_json(),
+                clean::DefaultReturn => Json::Null,
+            }),
+            ("variadic", self.variadic.to_json()),
+        ])
+    }
+}
@@ -55,6 +55,7 @@ extern crate serialize as rustc_serialize; // used by deriving
 
 use std::default::Default;
 use std::env;
+use std::ffi::OsStr;
 use std::panic;
 use std::process;
 use std::sync::mpsc::channel;
@@ -65,11 +66,14 @@ use rustc::session::config::{ErrorOutputType, RustcOptGroup};
 #[macro_use]
 mod externalfiles;
 
+mod api_diff;
 mod clean;
 mod config;
 mod core;
+mod coverage;
 mod doctree;
 mod fold;
+mod json;
 pub mod html {
     crate mod highlight;
     crate mod escape;
@@ -142,7 +146,7 @@ fn opts() -> Vec<RustcOptGroup> {
                      "[rust]")
         }),
         stable("w", |o| {
-            o.optopt("w", "output-format", "the output type to write", "[html]")
+            o.optopt("w", "output-format", "the output type to write", "[html|json]")
         }),
         stable("o", |o| o.optopt("o", "output", "where to place the output", "PATH")),
         stable("crate-name", |o| {
@@ -338,6 +342,30 @@ fn opts() -> Vec<RustcOptGroup> {
                        "enable-index-page",
                        "To enable generation of the index page")
         }),
+        unstable("combine-doctests", |o| {
+             o.optflag("",
+                       "combine-doctests",
+                       "compile compatible doctests together as one crate")
+        }),
+        unstable("persist-doctests", |o| {
+             o.optopt("",
+                       "persist-doctests",
+                       "Directory to keep doctest sources and executables in, \
+                        instead of deleting them",
+                       "PATH")
+        }),
+        unstable("show-coverage", |o| {
+             o.optflag("",
+                       "show-coverage",
+                       "calculate percentage of public items with documentation")
+        }),
+        unstable("api-diff", |o| {
+             o.optopt("",
+                       "api-diff",
+                       "compare the public API against an older version of the crate, given as \
+                        the output of `--output-format json`",
+                       "PATH")
+        }),
     ]
 }
 
@@ -370,6 +398,14 @@ fn main_args(args: &[String]) -> isize {
                                  options.debugging_options.treat_err_as_bug,
                                  options.debugging_options.ui_testing);
 
+    if let Some(ref old) = options.api_diff {
+        // Two JSON descriptions can be compared without compiling anything.
+        if options.input.extension() == Some(OsStr::new("json")) {
+            return api_diff::run_files(old, &options.input,
+                                       options.render_options.output_format, &diag);
+        }
+    }
+
     match (options.should_test, options.markdown_input()) {
         (true, true) => return markdown::test(options, &diag),
         (true, false) => return test::run(options),
@@ -382,13 +418,29 @@ fn main_args(args: &[String]) -> isize {
     let diag_opts = (options.error_format,
                      options.debugging_options.treat_err_as_bug,
                      options.debugging_options.ui_testing);
+    let show_coverage = options.show_coverage;
+    let old_api = options.api_diff.clone();
     rust_input(options, move |out| {
         let Output { krate, passes, renderinfo, renderopts } = out;
+        if show_coverage {
+            // Only the coverage passes ran, so there is nothing to render.
+            coverage::run(&krate, renderopts.output_format);
+            return 0;
+        }
         info!("going to format");
         let (error_format, treat_err_as_bug, ui_testing) = diag_opts;
         let diag = core::new_handler(error_format, None, treat_err_as_bug, ui_testing);
-        html::render::run(krate, renderopts, passes.into_iter().collect(), renderinfo, &diag)
-            .expect("failed to generate documentation");
+        if let Some(old) = old_api {
+            return api_diff::run(&krate, &renderinfo, &old, renderopts.output_format, &diag);
+        }
+        let res = match renderopts.output_format {
+            config::OutputFormat::Html => {
+                html::render::run(krate, renderopts, passes.into_iter().collect(), renderinfo,
+                                  &diag)
+            }
+            config::OutputFormat::Json => json::run(krate, renderopts, renderinfo),
+        };
+        res.expect("failed to generate documentation");
         0
     })
 }
@@ -148,6 +148,8 @@ pub fn test(mut options: Options, diag: &errors::Handler) -> isize {
     let mut opts = TestOptions::default();
     opts.no_crate_inject = true;
     opts.display_warnings = options.display_warnings;
+    opts.combine = options.combine_doctests;
+    opts.persist_doctests = options.persist_doctests;
     let mut collector = Collector::new(options.input.display().to_string(), options.cfgs,
                                        options.libs, options.codegen_options, options.externs,
                                        true, opts, options.maybe_sysroot, None,
@@ -160,7 +162,7 @@ pub fn test(mut options: Options, diag: &errors::Handler) -> isize {
         diag.span_warn(DUMMY_SP, &err.to_string());
     }
     options.test_args.insert(0, "rustdoctest".to_string());
-    testing::test_main(&options.test_args, collector.tests,
+    testing::test_main(&options.test_args, collector.into_tests(),
                        testing::Options::new().display_output(options.display_warnings));
     0
 }
@@ -0,0 +1,146 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use errors::emitter::Emitter;
+use errors::{DiagnosticBuilder, Handler};
+use rustc::lint;
+use rustc_data_structures::sync::{Lock, Lrc};
+use syntax::parse::lexer::{StringReader as Lexer, TokenAndSpan};
+use syntax::parse::{token, ParseSess};
+use syntax::source_map::{FilePathMapping, SourceMap};
+use syntax_pos::FileName;
+
+use std::panic::{self, AssertUnwindSafe};
+
+use clean;
+use core::DocContext;
+use fold::DocFolder;
+use html::markdown::{self, RustCodeBlock};
+use passes::{lint_node_for_item, source_span_for_markdown_range, span_of_attrs, Pass};
+
+pub const CHECK_CODE_BLOCK_SYNTAX: Pass =
+    Pass::early("check-code-block-syntax", check_code_block_syntax,
+                "lexes Rust code blocks and checks that their delimiters balance");
+
+pub fn check_code_block_syntax(krate: clean::Crate, cx: &DocContext) -> clean::Crate {
+    SyntaxChecker { cx }.fold_crate(krate)
+}
+
+struct SyntaxChecker<'a, 'tcx: 'a, 'rcx: 'a, 'cstore: 'rcx> {
+    cx: &'a DocContext<'a, 'tcx, 'rcx, 'cstore>,
+}
+
+impl<'a, 'tcx, 'rcx, 'cstore> SyntaxChecker<'a, 'tcx, 'rcx, 'cstore> {
+    fn check_rust_syntax(&self, item: &clean::Item, dox: &str, code_block: RustCodeBlock) {
+        let error = match first_syntax_error(&code_block.code) {
+            Some(error) => error,
+            None => return,
+        };
+
+        let sp = code_block.range.as_ref()
+            .and_then(|range| source_span_for_markdown_range(self.cx, dox, range, &item.attrs))
+            .unwrap_or_else(|| span_of_attrs(&item.attrs));
+
+        let mut diag = self.cx.tcx.struct_span_lint_node(
+            lint::builtin::INVALID_CODEBLOCK_SYNTAX,
+            lint_node_for_item(self.cx, item),
+            sp,
+            "could not parse code block as Rust code",
+        );
+        diag.note(&format!("error from rustc: {}", error));
+        diag.help("mark blocks that do not contain Rust code as text: ```text");
+        diag.emit();
+    }
+}
+
+impl<'a, 'tcx, 'rcx, 'cstore> DocFolder for SyntaxChecker<'a, 'tcx, 'rcx, 'cstore> {
+    fn fold_item(&mut self, item: clean::Item) -> Option<clean::Item> {
+        // Documentation inlined from other crates was already checked when they were documented.
+        if item.def_id.is_local() {
+            if let Some(dox) = item.attrs.collapsed_doc_value() {
+                for code_block in markdown::rust_code_blocks(&dox) {
+                    if !code_block.lang.ignore && !code_block.lang.compile_fail {
+                        self.check_rust_syntax(&item, &dox, code_block);
+                    }
+                }
+            }
+        }
+
+        self.fold_item_recur(item)
+    }
+}
+
+/// Collects the messages of the errors emitted while lexing a code block.
+struct BufferEmitter {
+    messages: Lrc<Lock<Vec<String>>>,
+}
+
+impl Emitter for BufferEmitter {
+    fn emit(&mut self, db: &DiagnosticBuilder) {
+        self.messages.lock().push(db.message());
+    }
+}
+
+/// Returns the first error found while lexing `code`, or while matching up its delimiters.
+///
+/// Code blocks usually hold statements, which are put in a `fn main` when tested, and often are
+/// only fragments of a program. They are not parsed any further so that those don't get flagged.
+fn first_syntax_error(code: &str) -> Option<String> {
+    let messages = Lrc::new(Lock::new(vec![]));
+    let emitter = BufferEmitter { messages: Lrc::clone(&messages) };
+
+    let cm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+    let handler = Handler::with_emitter(false, false, box emitter);
+    let sess = ParseSess::with_span_handler(handler, cm);
+    let source_file = sess.source_map().new_source_file(
+        FileName::Custom(String::from("doctest")),
+        code.to_owned(),
+    );
+
+    // Some lexer errors are fatal and unwind after being emitted, so they are caught here; the
+    // message has been buffered by then.
+    let unbalanced = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut lexer = match Lexer::new_or_buffered_errs(&sess, source_file, None) {
+            Ok(lexer) => lexer,
+            Err(diags) => {
+                messages.lock().extend(diags.iter().map(|diag| diag.message()));
+                return None;
+            }
+        };
+
+        let mut open_delims = vec![];
+        loop {
+            match lexer.try_next_token() {
+                Ok(TokenAndSpan { tok: token::Eof, .. }) => break,
+                Ok(TokenAndSpan { tok: token::OpenDelim(delim), .. }) => open_delims.push(delim),
+                Ok(TokenAndSpan { tok: token::CloseDelim(delim), .. }) => {
+                    if open_delims.pop() != Some(delim) {
+                        return Some("unexpected close delimiter");
+                    }
+                }
+                Ok(_) => {}
+                Err(()) => {
+                    let diags = lexer.buffer_fatal_errors();
+                    messages.lock().extend(diags.iter().map(|diag| diag.message()));
+                    return None;
+                }
+            }
+        }
+
+        if open_delims.is_empty() {
+            None
+        } else {
+            Some("this file contains an un-closed delimiter")
+        }
+    })).unwrap_or(None);
+
+    let first_error = messages.lock().first().cloned();
+    first_error.or_else(|| unbalanced.map(String::from))
+}
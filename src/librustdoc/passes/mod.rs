@@ -16,6 +16,9 @@ use rustc::middle::privacy::AccessLevels;
 use rustc::util::nodemap::DefIdSet;
 use std::mem;
 use std::fmt;
+use std::ops::Range;
+use syntax::ast::NodeId;
+use syntax_pos::{Span, DUMMY_SP};
 
 use clean::{self, GetDefId, Item};
 use core::DocContext;
@@ -46,6 +49,9 @@ pub use self::collect_intra_doc_links::COLLECT_INTRA_DOC_LINKS;
 mod collect_trait_impls;
 pub use self::collect_trait_impls::COLLECT_TRAIT_IMPLS;
 
+mod check_code_block_syntax;
+pub use self::check_code_block_syntax::CHECK_CODE_BLOCK_SYNTAX;
+
 /// Represents a single pass.
 #[derive(Copy, Clone)]
 pub enum Pass {
@@ -135,6 +141,7 @@ pub const PASSES: &'static [Pass] = &[
     STRIP_PRIV_IMPORTS,
     PROPAGATE_DOC_CFG,
     COLLECT_INTRA_DOC_LINKS,
+    CHECK_CODE_BLOCK_SYNTAX,
     COLLECT_TRAIT_IMPLS,
 ];
 
@@ -144,6 +151,7 @@ pub const DEFAULT_PASSES: &'static [&'static str] = &[
     "strip-hidden",
     "strip-private",
     "collect-intra-doc-links",
+    "check-code-block-syntax",
     "collapse-docs",
     "unindent-comments",
     "propagate-doc-cfg",
@@ -154,17 +162,32 @@ pub const DEFAULT_PRIVATE_PASSES: &'static [&'static str] = &[
     "collect-trait-impls",
     "strip-priv-imports",
     "collect-intra-doc-links",
+    "check-code-block-syntax",
     "collapse-docs",
     "unindent-comments",
     "propagate-doc-cfg",
 ];
 
+/// The list of default passes run when `--show-coverage` is passed to rustdoc.
+pub const DEFAULT_COVERAGE_PASSES: &'static [&'static str] = &[
+    "strip-hidden",
+    "strip-private",
+];
+
+/// The list of default passes run when `--show-coverage` and `--document-private-items` are passed
+/// to rustdoc.
+pub const PRIVATE_COVERAGE_PASSES: &'static [&'static str] = &[
+    "strip-priv-imports",
+];
+
 /// A shorthand way to refer to which set of passes to use, based on the presence of
-/// `--no-defaults` or `--document-private-items`.
+/// `--no-defaults`, `--document-private-items` or `--show-coverage`.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum DefaultPassOption {
     Default,
     Private,
+    Coverage,
+    PrivateCoverage,
     None,
 }
 
@@ -173,6 +196,8 @@ pub fn defaults(default_set: DefaultPassOption) -> &'static [&'static str] {
     match default_set {
         DefaultPassOption::Default => DEFAULT_PASSES,
         DefaultPassOption::Private => DEFAULT_PRIVATE_PASSES,
+        DefaultPassOption::Coverage => DEFAULT_COVERAGE_PASSES,
+        DefaultPassOption::PrivateCoverage => PRIVATE_COVERAGE_PASSES,
         DefaultPassOption::None => &[],
     }
 }
@@ -348,3 +373,70 @@ impl fold::DocFolder for ImportStripper {
         }
     }
 }
+
+/// Returns the node to report lints about `item` at, so that `#[allow]` and `#[deny]` attributes
+/// on the item and its parents apply. Items without one of their own use the crate root.
+crate fn lint_node_for_item(cx: &DocContext, item: &Item) -> NodeId {
+    cx.as_local_node_id(item.def_id).unwrap_or(NodeId::from_u32(0))
+}
+
+/// Returns a span covering all the doc comments of an item.
+crate fn span_of_attrs(attrs: &clean::Attributes) -> Span {
+    if attrs.doc_strings.is_empty() {
+        return DUMMY_SP;
+    }
+    let start = attrs.doc_strings[0].span();
+    let end = attrs.doc_strings.last().expect("No doc strings provided").span();
+    start.to(end)
+}
+
+/// Attempts to find the span in the source code of `md_range`, a range of `markdown`, which is
+/// the collapsed documentation of `attrs`.
+///
+/// This only works when every line of the documentation comes verbatim from a line of `///` or
+/// `//!` comments, in which case each markdown line is the end of its source line. Otherwise
+/// `None` is returned and callers should fall back to `span_of_attrs`.
+crate fn source_span_for_markdown_range(
+    cx: &DocContext,
+    markdown: &str,
+    md_range: &Range<usize>,
+    attrs: &clean::Attributes,
+) -> Option<Span> {
+    let is_all_sugared_doc = attrs.doc_strings.iter().all(|frag| match frag {
+        clean::DocFragment::SugaredDoc(..) => true,
+        _ => false,
+    });
+    if !is_all_sugared_doc {
+        return None;
+    }
+
+    let sp = span_of_attrs(attrs);
+    let snippet = cx.sess().source_map().span_to_snippet(sp).ok()?;
+    let md_lines = markdown.split('\n').collect::<Vec<_>>();
+    let src_lines = snippet.split('\n').collect::<Vec<_>>();
+    if md_lines.len() != src_lines.len() {
+        return None;
+    }
+
+    let src_offset = |pos: usize| -> Option<usize> {
+        let mut md_start = 0;
+        let mut src_start = 0;
+        for (md_line, src_line) in md_lines.iter().zip(&src_lines) {
+            let md_text = md_line.trim_end_matches('\r');
+            let src_text = src_line.trim_end_matches('\r');
+            if pos <= md_start + md_line.len() {
+                if !src_text.ends_with(md_text) {
+                    return None;
+                }
+                return Some(src_start + src_text.len() - md_text.len() + (pos - md_start));
+            }
+            md_start += md_line.len() + 1;
+            src_start += src_line.len() + 1;
+        }
+        None
+    };
+
+    let start = src_offset(md_range.start)?;
+    let end = src_offset(md_range.end)?;
+    Some(sp.from_inner_byte_pos(start, end))
+}
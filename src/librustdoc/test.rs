@@ -8,11 +8,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::cmp;
 use std::env;
 use std::ffi::OsString;
 use std::io::prelude::*;
+use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::mem;
+use std::path::{Component, Path, PathBuf};
 use std::panic::{self, AssertUnwindSafe};
 use std::process::Command;
 use std::str;
@@ -27,7 +30,7 @@ use rustc::session::{self, CompileIncomplete, config};
 use rustc::session::config::{OutputType, OutputTypes, Externs, CodegenOptions};
 use rustc::session::search_paths::{SearchPaths, PathKind};
 use rustc_metadata::dynamic_lib::DynamicLibrary;
-use tempfile::Builder as TempFileBuilder;
+use tempfile::{Builder as TempFileBuilder, TempDir};
 use rustc_driver::{self, driver, target_features, Compilation};
 use rustc_driver::driver::phase_2_configure_and_expand;
 use rustc_metadata::cstore::CStore;
@@ -54,6 +57,12 @@ pub struct TestOptions {
     pub display_warnings: bool,
     /// Additional crate-level attributes to add to doctests.
     pub attrs: Vec<String>,
+    /// Whether to compile the doctests that allow it into a single crate, instead of one crate
+    /// per doctest.
+    pub combine: bool,
+    /// If present, a directory to keep the source and executable of every doctest in, instead of
+    /// a temporary directory.
+    pub persist_doctests: Option<PathBuf>,
 }
 
 pub fn run(mut options: Options) -> isize {
@@ -115,6 +124,8 @@ pub fn run(mut options: Options) -> isize {
         });
         let mut opts = scrape_test_config(hir_forest.krate());
         opts.display_warnings |= options.display_warnings;
+        opts.combine = options.combine_doctests;
+        opts.persist_doctests = options.persist_doctests;
         let mut collector = Collector::new(
             crate_name,
             options.cfgs,
@@ -147,7 +158,7 @@ pub fn run(mut options: Options) -> isize {
         options.test_args.insert(0, "rustdoctest".to_string());
 
         testing::test_main(&options.test_args,
-                        collector.tests.into_iter().collect(),
+                        collector.into_tests(),
                         testing::Options::new().display_output(options.display_warnings));
         0
     })
@@ -161,6 +172,8 @@ fn scrape_test_config(krate: &::rustc::hir::Crate) -> TestOptions {
         no_crate_inject: false,
         display_warnings: false,
         attrs: Vec::new(),
+        combine: false,
+        persist_doctests: None,
     };
 
     let test_attrs: Vec<_> = krate.attrs.iter()
@@ -195,6 +208,126 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
     // the test harness wants its own `main` & top level functions, so
     // never wrap the test in `fn main() { ... }`
     let (test, line_offset) = make_test(test, Some(cratename), as_test_harness, opts);
+
+    // Shuffle around a few input and output handles here. We're going to pass
+    // an explicit handle into rustc to collect output messages, but we also
+    // want to catch the error message that rustc prints when it fails.
+    //
+    // We take our thread-local stderr (likely set by the test runner) and replace
+    // it with a sink that is also passed to rustc itself. When this function
+    // returns the output of the sink is copied onto the output of our own thread.
+    //
+    // The basic idea is to not use a default Handler for rustc, and then also
+    // not print things by default to the actual stderr.
+    struct Bomb(Arc<Mutex<Vec<u8>>>, Box<dyn Write+Send>);
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            let _ = self.1.write_all(&self.0.lock().unwrap());
+        }
+    }
+    let data = Arc::new(Mutex::new(Vec::new()));
+
+    let old = io::set_panic(Some(box Sink(data.clone())));
+    let _bomb = Bomb(data.clone(), old.unwrap_or(box io::stdout()));
+
+    let persist = opts.persist_doctests.as_ref().map(|dir| {
+        persist_dir(dir, cratename, &filename.to_string(), line)
+    });
+    let (libdir, outdir, compile_result) = compile_test(
+        test, filename, line as isize - line_offset as isize, cfgs, libs, cg, externs,
+        as_test_harness, no_run, maybe_sysroot, linker, edition, persist, data.clone(),
+    );
+
+    match (compile_result, compile_fail) {
+        (Ok(()), true) => {
+            panic!("test compiled while it wasn't supposed to")
+        }
+        (Ok(()), false) => {}
+        (Err(()), true) => {
+            if error_codes.len() > 0 {
+                let out = String::from_utf8(data.lock().unwrap().to_vec()).unwrap();
+                error_codes.retain(|err| !out.contains(err));
+            }
+        }
+        (Err(()), false) => {
+            panic!("couldn't compile the test")
+        }
+    }
+
+    if error_codes.len() > 0 {
+        panic!("Some expected error codes were not found: {:?}", error_codes);
+    }
+
+    if no_run { return }
+
+    run_binary(&outdir.lock().unwrap().path().join("rust_out"), libdir, None, should_panic,
+               None);
+}
+
+/// A `Write` that appends to a shared buffer, used to collect rustc's output.
+struct Sink(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Sink {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        Write::write(&mut *self.0.lock().unwrap(), data)
+    }
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+/// Where a doctest's files go: either a temporary directory, or the one chosen with
+/// `--persist-doctests`.
+enum DirState {
+    Temp(TempDir),
+    Perm(PathBuf),
+}
+
+impl DirState {
+    fn path(&self) -> &Path {
+        match *self {
+            DirState::Temp(ref t) => t.path(),
+            DirState::Perm(ref p) => p.as_path(),
+        }
+    }
+}
+
+/// The directory that `--persist-doctests` keeps a doctest in: one directory per crate, holding
+/// the path of the doctest's file, with one directory per line a doctest starts at, like
+/// `foo/src/lib.rs/42`. Keeping the path intact keeps the directories of different files apart.
+fn persist_dir(base: &Path, cratename: &str, filename: &str, line: usize) -> PathBuf {
+    let mut dir = base.join(cratename);
+    for component in Path::new(filename).components() {
+        match component {
+            Component::Normal(name) => {
+                // Names like `<anon>` can't be directory names everywhere.
+                let name = name.to_string_lossy();
+                dir.push(name.replace(|c: char| "<>:\"|?*".contains(c), "_"));
+            }
+            // Files outside of the current directory are still kept within `base`.
+            Component::ParentDir => dir.push("__"),
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+    dir.join(line.to_string())
+}
+
+/// Compiles a doctest program into an executable named `rust_out`, sending rustc's diagnostics
+/// to `output`. The executable goes into a new temporary directory, or into `persist`, together
+/// with the source as `main.rs`. Returns the target library directory, the output directory and
+/// whether compilation succeeded.
+fn compile_test(test: String, filename: &FileName, line_offset: isize,
+                cfgs: Vec<String>, libs: SearchPaths,
+                cg: CodegenOptions, externs: Externs,
+                as_test_harness: bool, no_run: bool,
+                maybe_sysroot: Option<PathBuf>, linker: Option<PathBuf>, edition: Edition,
+                persist: Option<PathBuf>, output: Arc<Mutex<Vec<u8>>>)
+                -> (PathBuf, Mutex<DirState>, Result<(), ()>) {
+    if let Some(ref dir) = persist {
+        fs::create_dir_all(dir).expect("couldn't create directory for doctest executables");
+        fs::write(dir.join("main.rs"), &test).expect("couldn't write doctest source");
+        // Don't leave the binary of an earlier run around if this one fails to compile.
+        let _ = fs::remove_file(dir.join("rust_out"));
+    }
+
     // FIXME(#44940): if doctests ever support path remapping, then this filename
     // needs to be the result of SourceMap::span_to_unmapped_path
     let input = config::Input::Str {
@@ -223,39 +356,11 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
         ..config::Options::default()
     };
 
-    // Shuffle around a few input and output handles here. We're going to pass
-    // an explicit handle into rustc to collect output messages, but we also
-    // want to catch the error message that rustc prints when it fails.
-    //
-    // We take our thread-local stderr (likely set by the test runner) and replace
-    // it with a sink that is also passed to rustc itself. When this function
-    // returns the output of the sink is copied onto the output of our own thread.
-    //
-    // The basic idea is to not use a default Handler for rustc, and then also
-    // not print things by default to the actual stderr.
-    struct Sink(Arc<Mutex<Vec<u8>>>);
-    impl Write for Sink {
-        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
-            Write::write(&mut *self.0.lock().unwrap(), data)
-        }
-        fn flush(&mut self) -> io::Result<()> { Ok(()) }
-    }
-    struct Bomb(Arc<Mutex<Vec<u8>>>, Box<dyn Write+Send>);
-    impl Drop for Bomb {
-        fn drop(&mut self) {
-            let _ = self.1.write_all(&self.0.lock().unwrap());
-        }
-    }
-    let data = Arc::new(Mutex::new(Vec::new()));
-
-    let old = io::set_panic(Some(box Sink(data.clone())));
-    let _bomb = Bomb(data.clone(), old.unwrap_or(box io::stdout()));
-
-    let (libdir, outdir, compile_result) = driver::spawn_thread_pool(sessopts, |sessopts| {
+    driver::spawn_thread_pool(sessopts, |sessopts| {
         let source_map = Lrc::new(SourceMap::new_doctest(
-            sessopts.file_path_mapping(), filename.clone(), line as isize - line_offset as isize
+            sessopts.file_path_mapping(), filename.clone(), line_offset
         ));
-        let emitter = errors::emitter::EmitterWriter::new(box Sink(data.clone()),
+        let emitter = errors::emitter::EmitterWriter::new(box Sink(output),
                                                         Some(source_map.clone()),
                                                         false,
                                                         false);
@@ -270,9 +375,13 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
         let cstore = CStore::new(codegen_backend.metadata_loader());
         rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
 
-        let outdir = Mutex::new(
-            TempFileBuilder::new().prefix("rustdoctest").tempdir().expect("rustdoc needs a tempdir")
-        );
+        let outdir = Mutex::new(match persist {
+            Some(dir) => DirState::Perm(dir),
+            None => DirState::Temp(
+                TempFileBuilder::new().prefix("rustdoctest").tempdir()
+                                      .expect("rustdoc needs a tempdir")
+            ),
+        });
         let libdir = sess.target_filesearch(PathKind::All).get_lib_path();
         let mut control = driver::CompileController::basic();
 
@@ -306,37 +415,22 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
         };
 
         (libdir, outdir, compile_result)
-    });
-
-    match (compile_result, compile_fail) {
-        (Ok(()), true) => {
-            panic!("test compiled while it wasn't supposed to")
-        }
-        (Ok(()), false) => {}
-        (Err(()), true) => {
-            if error_codes.len() > 0 {
-                let out = String::from_utf8(data.lock().unwrap().to_vec()).unwrap();
-                error_codes.retain(|err| !out.contains(err));
-            }
-        }
-        (Err(()), false) => {
-            panic!("couldn't compile the test")
-        }
-    }
-
-    if error_codes.len() > 0 {
-        panic!("Some expected error codes were not found: {:?}", error_codes);
-    }
-
-    if no_run { return }
+    })
+}
 
+/// Runs a compiled doctest, passing it `arg` if given, and panics if it didn't exit the way
+/// `should_panic` says it should. `map_stderr` can rewrite what the test printed to stderr before
+/// it is reported.
+fn run_binary(binary: &Path, libdir: PathBuf, arg: Option<&str>, should_panic: bool,
+              map_stderr: Option<&dyn Fn(&str) -> String>) {
     // Run the code!
     //
     // We're careful to prepend the *target* dylib search path to the child's
     // environment to ensure that the target loads the right libraries at
     // runtime. It would be a sad day if the *host* libraries were loaded as a
     // mistake.
-    let mut cmd = Command::new(&outdir.lock().unwrap().path().join("rust_out"));
+    let mut cmd = Command::new(binary);
+    cmd.args(arg);
     let var = DynamicLibrary::envvar();
     let newpath = {
         let path = env::var_os(var).unwrap_or(OsString::new());
@@ -355,9 +449,11 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
             if should_panic && out.status.success() {
                 panic!("test executable succeeded when it should have failed");
             } else if !should_panic && !out.status.success() {
+                let stderr = str::from_utf8(&out.stderr).unwrap_or("");
+                let stderr = map_stderr.map_or_else(|| stderr.to_owned(), |map| map(stderr));
                 panic!("test executable failed:\n{}\n{}\n",
                        str::from_utf8(&out.stdout).unwrap_or(""),
-                       str::from_utf8(&out.stderr).unwrap_or(""));
+                       stderr);
             }
         }
     }
@@ -395,14 +491,45 @@ pub fn make_test(s: &str,
 
     // Uses libsyntax to parse the doctest and find if there's a main fn and the extern
     // crate already is included.
-    let (already_has_main, already_has_extern_crate) = crate::syntax::with_globals(|| {
+    let (already_has_main, already_has_extern_crate) =
+        check_for_main_and_extern_crate(crates + &everything_else, cratename);
+
+    // Don't inject `extern crate std` because it's already injected by the
+    // compiler.
+    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
+        if let Some(cratename) = cratename {
+            // Make sure its actually used if not included.
+            if s.contains(cratename) {
+                prog.push_str(&format!("extern crate {};\n", cratename));
+                line_offset += 1;
+            }
+        }
+    }
+
+    if dont_insert_main || already_has_main {
+        prog.push_str(everything_else);
+    } else {
+        prog.push_str("fn main() {\n");
+        line_offset += 1;
+        prog.push_str(everything_else);
+        prog.push_str("\n}");
+    }
+
+    info!("final test program: {}", prog);
+
+    (prog, line_offset)
+}
+
+/// Parses a doctest to find whether it has its own `main` function, and whether it already
+/// contains an `extern crate` for `cratename`.
+fn check_for_main_and_extern_crate(source: String, cratename: Option<&str>) -> (bool, bool) {
+    crate::syntax::with_globals(|| {
         use crate::syntax::{ast, parse::{self, ParseSess}, source_map::FilePathMapping};
         use crate::syntax_pos::FileName;
         use errors::emitter::EmitterWriter;
         use errors::Handler;
 
         let filename = FileName::Anon;
-        let source = crates + &everything_else;
 
         // any errors in parsing should also appear when the doctest is compiled for real, so just
         // send all the errors that libsyntax emits directly into a Sink instead of stderr
@@ -464,32 +591,7 @@ pub fn make_test(s: &str,
         }
 
         (found_main, found_extern_crate)
-    });
-
-    // Don't inject `extern crate std` because it's already injected by the
-    // compiler.
-    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
-        if let Some(cratename) = cratename {
-            // Make sure its actually used if not included.
-            if s.contains(cratename) {
-                prog.push_str(&format!("extern crate {};\n", cratename));
-                line_offset += 1;
-            }
-        }
-    }
-
-    if dont_insert_main || already_has_main {
-        prog.push_str(everything_else);
-    } else {
-        prog.push_str("fn main() {\n");
-        line_offset += 1;
-        prog.push_str(everything_else);
-        prog.push_str("\n}");
-    }
-
-    info!("final test program: {}", prog);
-
-    (prog, line_offset)
+    })
 }
 
 // FIXME(aburka): use a real parser to deal with multiline attributes
@@ -569,6 +671,9 @@ pub struct Collector {
     filename: Option<PathBuf>,
     linker: Option<PathBuf>,
     edition: Edition,
+
+    /// Doctests set aside to be compiled together by `into_tests`, with `opts.combine`.
+    combinable: Vec<CombinableTest>,
 }
 
 impl Collector {
@@ -592,6 +697,7 @@ impl Collector {
             filename,
             linker,
             edition,
+            combinable: Vec::new(),
         }
     }
 
@@ -620,6 +726,260 @@ impl Collector {
             FileName::Custom("input".to_owned())
         }
     }
+
+    /// Returns all collected doctests. With `opts.combine`, the doctests that were set aside are
+    /// compiled together the first time one of them runs; each of them still shows up as its own
+    /// test, and if the combined crate fails to compile they are all compiled on their own so
+    /// that errors are reported as usual.
+    pub fn into_tests(mut self) -> Vec<testing::TestDescAndFn> {
+        if self.combinable.is_empty() {
+            return self.tests;
+        }
+
+        let combinable = mem::replace(&mut self.combinable, Vec::new());
+        let (source, lines) = make_combined_test(&combinable, &self.cratename, &self.opts);
+        info!("combined {} doctests into one crate: {}", combinable.len(), source);
+        let combined = Arc::new(CombinedTests {
+            source,
+            cfgs: self.cfgs.clone(),
+            libs: self.libs.clone(),
+            cg: self.cg.clone(),
+            externs: self.externs.clone(),
+            maybe_sysroot: self.maybe_sysroot.clone(),
+            linker: self.linker.clone(),
+            edition: self.edition,
+            persist: self.opts.persist_doctests.as_ref().map(|dir| {
+                dir.join(&self.cratename).join("combined")
+            }),
+            binary: Mutex::new(None),
+        });
+
+        for ((index, t), lines) in combinable.into_iter().enumerate().zip(lines) {
+            let CombinableTest { name, test, filename, line, config } = t;
+            let combined = combined.clone();
+            let cfgs = self.cfgs.clone();
+            let libs = self.libs.clone();
+            let cg = self.cg.clone();
+            let externs = self.externs.clone();
+            let cratename = self.cratename.to_string();
+            let opts = self.opts.clone();
+            let maybe_sysroot = self.maybe_sysroot.clone();
+            let linker = self.linker.clone();
+            let edition = self.edition;
+            // Failures are reported at the same lines as without `--combine-doctests`.
+            let (_, line_offset) = make_test(&test, Some(&cratename), false, &opts);
+            self.tests.push(testing::TestDescAndFn {
+                desc: testing::TestDesc {
+                    name: testing::DynTestName(name.clone()),
+                    ignore: false,
+                    should_panic: testing::ShouldPanic::No,
+                    allow_fail: config.allow_fail,
+                    timeout: None,
+                    retries: None,
+                    setup: None,
+                    teardown: None,
+                },
+                testfn: testing::DynTestFn(box move || {
+                    if let Some((binary, libdir)) = CombinedTests::binary(&combined) {
+                        if !config.no_run {
+                            let arg = index.to_string();
+                            let map_stderr = |stderr: &str| {
+                                map_combined_lines(stderr, lines, &filename, line_offset)
+                            };
+                            run_binary(&binary, libdir, Some(&arg), config.should_panic,
+                                       Some(&map_stderr));
+                        }
+                        return;
+                    }
+                    in_rustc_thread(name, move || {
+                        run_test(&test,
+                                 &cratename,
+                                 &filename,
+                                 line,
+                                 cfgs,
+                                 libs,
+                                 cg,
+                                 externs,
+                                 config.should_panic,
+                                 config.no_run,
+                                 config.test_harness,
+                                 config.compile_fail,
+                                 config.error_codes,
+                                 &opts,
+                                 maybe_sysroot,
+                                 linker,
+                                 edition)
+                    })
+                }),
+            });
+        }
+        self.tests
+    }
+}
+
+/// Runs `f` on a new thread set up to run the compiler, with the output capture of the current
+/// test, and propagates any panic.
+fn in_rustc_thread<R, F>(name: String, f: F) -> R
+    where R: Send + 'static,
+          F: FnOnce() -> R + Send + 'static
+{
+    let panic = io::set_panic(None);
+    let print = io::set_print(None);
+    match {
+        rustc_driver::in_named_rustc_thread(name, move || with_globals(move || {
+            io::set_panic(panic);
+            io::set_print(print);
+            f()
+        }))
+    } {
+        Ok(r) => r,
+        Err(err) => panic::resume_unwind(err),
+    }
+}
+
+/// A doctest that can be compiled as part of a larger crate together with other doctests.
+struct CombinableTest {
+    name: String,
+    test: String,
+    filename: FileName,
+    line: usize,
+    config: LangString,
+}
+
+/// Whether a doctest can be compiled together with others: it needs to be a plain example that
+/// is run (or only built) as a `main` function, and can't depend on crate-level attributes.
+fn can_combine(test: &str, config: &LangString, cratename: &str) -> bool {
+    if config.ignore || config.compile_fail || config.test_harness ||
+       !config.error_codes.is_empty() {
+        return false;
+    }
+    // The header lines hold any `#![feature]`s, other crate attributes, and `extern crate`s,
+    // which can only go at the top of a crate of their own.
+    let (crate_attrs, everything_else, crates) = partition_source(test);
+    if !crate_attrs.trim().is_empty() {
+        return false;
+    }
+    let (has_main, _) = check_for_main_and_extern_crate(crates + &everything_else,
+                                                        Some(cratename));
+    !has_main
+}
+
+/// Makes the source of the crate that combined doctests are compiled into: every doctest becomes
+/// a function, and `main` calls the one whose index it is given as its argument. Also returns the
+/// first and last line of the code of each doctest in that source.
+fn make_combined_test(tests: &[CombinableTest], cratename: &str, opts: &TestOptions)
+                      -> (String, Vec<(usize, usize)>) {
+    let mut prog = String::new();
+    let mut lines = Vec::with_capacity(tests.len());
+
+    // The same crate attributes as `make_test` adds.
+    if opts.attrs.is_empty() && !opts.display_warnings {
+        prog.push_str("#![allow(unused)]\n");
+    }
+    for attr in &opts.attrs {
+        prog.push_str(&format!("#![{}]\n", attr));
+    }
+    if !opts.no_crate_inject && cratename != "std" &&
+       tests.iter().any(|t| t.test.contains(cratename)) {
+        prog.push_str(&format!("extern crate {};\n", cratename));
+    }
+
+    let mut line_count = prog.lines().count();
+    for (i, t) in tests.iter().enumerate() {
+        // Trimmed like in `make_test`, so that the code starts at the same line of its function
+        // as it would in the `main` of a doctest compiled on its own.
+        let code = t.test.trim();
+        prog.push_str(&format!("// {}\nfn __doctest_{}() {{\n{}\n}}\n", t.name, i, code));
+        let code_lines = cmp::max(code.lines().count(), 1);
+        lines.push((line_count + 3, line_count + 2 + code_lines));
+        line_count += code_lines + 3;
+    }
+
+    prog.push_str("fn main() {\n");
+    prog.push_str("    match ::std::env::args().nth(1).as_ref().map(|s| &**s) {\n");
+    for i in 0..tests.len() {
+        prog.push_str(&format!("        Some(\"{0}\") => __doctest_{0}(),\n", i));
+    }
+    prog.push_str("        _ => ::std::process::exit(101),\n");
+    prog.push_str("    }\n}\n");
+    (prog, lines)
+}
+
+/// Points the locations in `output` that are within the code of a combined doctest, which spans
+/// `lines` of the combined crate, at the lines the doctest would have if it was compiled on its
+/// own, where its code follows `line_offset` lines added by `make_test`.
+fn map_combined_lines(output: &str, lines: (usize, usize), filename: &FileName,
+                      line_offset: usize) -> String {
+    const COMBINED: &str = "<combined doctests>:";
+    let mut mapped = String::with_capacity(output.len());
+    let mut rest = output;
+    while let Some(pos) = rest.find(COMBINED) {
+        mapped.push_str(&rest[..pos]);
+        let after = &rest[pos + COMBINED.len()..];
+        let digits = after.find(|c: char| !c.is_digit(10)).unwrap_or(after.len());
+        match after[..digits].parse::<usize>() {
+            Ok(l) if l >= lines.0 && l <= lines.1 => {
+                mapped.push_str(&format!("{}:{}", filename, line_offset + 1 + l - lines.0));
+            }
+            _ => mapped.push_str(&rest[pos..pos + COMBINED.len() + digits]),
+        }
+        rest = &after[digits..];
+    }
+    mapped.push_str(rest);
+    mapped
+}
+
+/// The crate that combined doctests are compiled into, shared by all of them.
+struct CombinedTests {
+    source: String,
+    cfgs: Vec<String>,
+    libs: SearchPaths,
+    cg: CodegenOptions,
+    externs: Externs,
+    maybe_sysroot: Option<PathBuf>,
+    linker: Option<PathBuf>,
+    edition: Edition,
+    persist: Option<PathBuf>,
+    /// The directory holding the compiled binary and the target library directory, or `None` if
+    /// compilation failed. Not set before the first of the tests runs.
+    binary: Mutex<Option<Option<(DirState, PathBuf)>>>,
+}
+
+impl CombinedTests {
+    /// Compiles the combined crate if that hasn't happened yet, and returns the path to the
+    /// binary and the library path to run it with, or `None` if it didn't compile.
+    fn binary(this: &Arc<CombinedTests>) -> Option<(PathBuf, PathBuf)> {
+        // Tests are run on several threads; the first one to get here does the compilation and
+        // the others wait for it.
+        let mut binary = this.binary.lock().unwrap_or_else(|e| e.into_inner());
+        if binary.is_none() {
+            let krate = this.clone();
+            let compiled = in_rustc_thread("combined doctests".to_owned(), move || {
+                // Errors will show up again when the tests fall back to being compiled on their
+                // own, against the right file and line.
+                let output = Arc::new(Mutex::new(Vec::new()));
+                io::set_panic(Some(box Sink(output.clone())));
+                let filename = FileName::Custom("combined doctests".to_owned());
+                let (libdir, outdir, res) = compile_test(
+                    krate.source.clone(), &filename, 0, krate.cfgs.clone(), krate.libs.clone(),
+                    krate.cg.clone(), krate.externs.clone(), false, false,
+                    krate.maybe_sysroot.clone(), krate.linker.clone(), krate.edition,
+                    krate.persist.clone(), output,
+                );
+                res.ok().map(|()| (outdir.into_inner().unwrap(), libdir))
+            });
+            if compiled.is_none() {
+                info!("combined doctests failed to compile, compiling them one by one");
+            }
+            *binary = Some(compiled);
+        }
+        match *binary {
+            Some(Some((ref outdir, ref libdir))) => {
+                Some((outdir.path().join("rust_out"), libdir.clone()))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Tester for Collector {
@@ -635,6 +995,12 @@ impl Tester for Collector {
         let maybe_sysroot = self.maybe_sysroot.clone();
         let linker = self.linker.clone();
         let edition = config.edition.unwrap_or(self.edition);
+        if self.opts.combine && edition == self.edition &&
+           can_combine(&test, &config, &self.cratename) {
+            debug!("Setting aside test {} to be combined: {}", name, test);
+            self.combinable.push(CombinableTest { name, test, filename, line, config });
+            return;
+        }
         debug!("Creating test {}: {}", name, test);
         self.tests.push(testing::TestDescAndFn {
             desc: testing::TestDesc {
@@ -643,36 +1009,31 @@ impl Tester for Collector {
                 // compiler failures are test failures
                 should_panic: testing::ShouldPanic::No,
                 allow_fail: config.allow_fail,
+                timeout: None,
+                retries: None,
+                setup: None,
+                teardown: None,
             },
             testfn: testing::DynTestFn(box move || {
-                let panic = io::set_panic(None);
-                let print = io::set_print(None);
-                match {
-                    rustc_driver::in_named_rustc_thread(name, move || with_globals(move || {
-                        io::set_panic(panic);
-                        io::set_print(print);
-                        run_test(&test,
-                                 &cratename,
-                                 &filename,
-                                 line,
-                                 cfgs,
-                                 libs,
-                                 cg,
-                                 externs,
-                                 config.should_panic,
-                                 config.no_run,
-                                 config.test_harness,
-                                 config.compile_fail,
-                                 config.error_codes,
-                                 &opts,
-                                 maybe_sysroot,
-                                 linker,
-                                 edition)
-                    }))
-                } {
-                    Ok(()) => (),
-                    Err(err) => panic::resume_unwind(err),
-                }
+                in_rustc_thread(name, move || {
+                    run_test(&test,
+                             &cratename,
+                             &filename,
+                             line,
+                             cfgs,
+                             libs,
+                             cg,
+                             externs,
+                             config.should_panic,
+                             config.no_run,
+                             config.test_harness,
+                             config.compile_fail,
+                             config.error_codes,
+                             &opts,
+                             maybe_sysroot,
+                             linker,
+                             edition)
+                })
             }),
         });
     }
@@ -828,7 +1189,11 @@ impl<'a, 'hir> intravisit::Visitor<'hir> for HirCollector<'a, 'hir> {
 
 #[cfg(test)]
 mod tests {
-    use super::{TestOptions, make_test};
+    use super::{TestOptions, make_test, can_combine, make_combined_test, map_combined_lines,
+                persist_dir, CombinableTest};
+    use html::markdown::{ErrorCodes, LangString};
+    use syntax_pos::FileName;
+    use std::path::{Path, PathBuf};
 
     #[test]
     fn make_test_basic() {
@@ -888,6 +1253,8 @@ assert_eq!(2+2, 4);
             no_crate_inject: true,
             display_warnings: false,
             attrs: vec![],
+            combine: false,
+            persist_doctests: None,
         };
         let input =
 "use asdf::qwop;
@@ -1106,4 +1473,80 @@ assert_eq!(asdf::foo, 4);
         let output = make_test(input, Some("asdf"), false, &opts);
         assert_eq!(output, (expected, 3));
     }
+
+    #[test]
+    fn combine_only_plain_examples() {
+        let lang = |s| LangString::parse(s, ErrorCodes::No);
+        assert!(can_combine("assert_eq!(2+2, 4);", &lang("rust"), "asdf"));
+        assert!(can_combine("use asdf::qwop;\nqwop();", &lang("should_panic"), "asdf"));
+        assert!(can_combine("loop {}", &lang("no_run"), "asdf"));
+
+        assert!(!can_combine("let x: u8 = \"\";", &lang("compile_fail"), "asdf"));
+        assert!(!can_combine("assert!(true);", &lang("ignore"), "asdf"));
+        assert!(!can_combine("#[test] fn t() {}", &lang("test_harness"), "asdf"));
+        assert!(!can_combine("fn main() {\n    assert!(true);\n}", &lang("rust"), "asdf"));
+        assert!(!can_combine("#![feature(nll)]\nassert!(true);", &lang("rust"), "asdf"));
+        assert!(!can_combine("extern crate asdf;\nasdf::qwop();", &lang("rust"), "asdf"));
+    }
+
+    #[test]
+    fn make_combined_test_basic() {
+        let test = |name: &str, code: &str| CombinableTest {
+            name: name.to_string(),
+            test: code.to_string(),
+            filename: FileName::Custom("lib.rs".to_string()),
+            line: 1,
+            config: LangString::parse("", ErrorCodes::No),
+        };
+        let tests = vec![
+            test("lib.rs - a (line 3)", "assert_eq!(2+2, 4);"),
+            test("lib.rs - b (line 9)", "use asdf::qwop;\nqwop();\n"),
+        ];
+        let expected =
+"#![allow(unused)]
+extern crate asdf;
+// lib.rs - a (line 3)
+fn __doctest_0() {
+assert_eq!(2+2, 4);
+}
+// lib.rs - b (line 9)
+fn __doctest_1() {
+use asdf::qwop;
+qwop();
+}
+fn main() {
+    match ::std::env::args().nth(1).as_ref().map(|s| &**s) {
+        Some(\"0\") => __doctest_0(),
+        Some(\"1\") => __doctest_1(),
+        _ => ::std::process::exit(101),
+    }
+}
+".to_string();
+        let (output, lines) = make_combined_test(&tests, "asdf", &TestOptions::default());
+        assert_eq!(output, expected);
+        assert_eq!(lines, vec![(5, 5), (9, 10)]);
+    }
+
+    #[test]
+    fn map_combined_lines_to_doctest() {
+        let filename = FileName::Real(PathBuf::from("src/lib.rs"));
+        let output = "thread 'main' panicked at 'oh no', <combined doctests>:10:5\n\
+                      thread 'main' panicked at 'oh no', <combined doctests>:12:1\n";
+        assert_eq!(map_combined_lines(output, (9, 10), &filename, 2),
+                   "thread 'main' panicked at 'oh no', src/lib.rs:4:5\n\
+                    thread 'main' panicked at 'oh no', <combined doctests>:12:1\n");
+    }
+
+    #[test]
+    fn persist_dir_names() {
+        let base = Path::new("out");
+        assert_eq!(persist_dir(base, "foo", "src/lib.rs", 42),
+                   base.join("foo").join("src").join("lib.rs").join("42"));
+        assert_eq!(persist_dir(base, "foo", "../my-crate/README.md", 7),
+                   base.join("foo").join("__").join("my-crate").join("README.md").join("7"));
+        assert_eq!(persist_dir(base, "foo", "<anon>", 1),
+                   base.join("foo").join("_anon_").join("1"));
+        assert!(persist_dir(base, "foo", "src/a_b.rs", 1) !=
+                persist_dir(base, "foo", "src/a/b.rs", 1));
+    }
 }
@@ -1033,6 +1033,12 @@ pub fn as_pretty_json<T>(t: &T) -> AsPrettyJson<T> {
     AsPrettyJson { inner: t, indent: None }
 }
 
+/// Create a `Json::Object` out of a list of fields, for building a
+/// document by hand
+pub fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(name, value)| (name.to_owned(), value)).collect())
+}
+
 impl Json {
     /// Borrow this json object as a pretty object to generate a pretty
     /// representation for it via `Display`.
@@ -504,6 +504,15 @@ declare_features! (
 
     // `reason = ` in lint attributes and `expect` lint attribute
     (active, lint_reasons, "1.31.0", Some(54503), None),
+
+    // #[test_timeout(seconds)] on tests
+    (active, test_timeout, "1.32.0", None, None),
+
+    // #[test_flaky] and #[test_flaky(retries)] on tests
+    (active, test_flaky, "1.32.0", None, None),
+
+    // #[test_setup(f)] and #[test_teardown(f)] on tests, and as crate attributes
+    (active, test_fixtures, "1.32.0", None, None),
 );
 
 declare_features! (
@@ -1135,6 +1144,27 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
                                  "allow_fail attribute is currently unstable",
                                  cfg_fn!(allow_fail))),
 
+    ("test_timeout", Normal, Gated(Stability::Unstable,
+                                   "test_timeout",
+                                   "the `#[test_timeout]` attribute is an experimental feature",
+                                   cfg_fn!(test_timeout))),
+
+    ("test_flaky", Normal, Gated(Stability::Unstable,
+                                 "test_flaky",
+                                 "the `#[test_flaky]` attribute is an experimental feature",
+                                 cfg_fn!(test_flaky))),
+
+    // Also used as crate attributes, for the fixtures of the whole test suite.
+    ("test_setup", Normal, Gated(Stability::Unstable,
+                                 "test_fixtures",
+                                 "the `#[test_setup]` attribute is an experimental feature",
+                                 cfg_fn!(test_fixtures))),
+
+    ("test_teardown", Normal, Gated(Stability::Unstable,
+                                    "test_fixtures",
+                                    "the `#[test_teardown]` attribute is an experimental feature",
+                                    cfg_fn!(test_fixtures))),
+
     ("rustc_std_internal_symbol", Whitelisted, Gated(Stability::Unstable,
                                      "rustc_attrs",
                                      "this is an internal attribute that will \
@@ -155,6 +155,7 @@ pub mod util {
 }
 
 pub mod json;
+pub mod sarif;
 
 pub mod syntax {
     pub use ext;
@@ -0,0 +1,293 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A SARIF emitter for errors, used by `--error-format=sarif`.
+//!
+//! SARIF, the Static Analysis Results Interchange Format (version 2.1.0), is what code scanning
+//! services ingest. A SARIF log describes a whole run of a tool, so unlike the JSON emitter, which
+//! writes every diagnostic as soon as it is emitted, this one collects them and writes a single
+//! run when it is dropped along with its `Handler`, at the end of the compilation. Problems with
+//! the command line, found before there is a session to own the emitter, are printed in
+//! human-readable form before the log.
+//!
+//! Error codes and lint names become the rules of the run, with the `--explain` text of error
+//! codes as their help. Diagnostics pointing at source code become results located at their
+//! primary spans, with the labels of their other spans as related locations and their
+//! suggestions as fixes. Diagnostics without a span, like "aborting due to previous error", are
+//! reported as notifications of the invocation instead.
+
+use source_map::SourceMap;
+use syntax_pos::{FileName, Span};
+use errors::registry::Registry;
+use errors::{DiagnosticBuilder, CodeSuggestion, SourceMapper, DiagnosticId, Level};
+use errors::emitter::Emitter;
+
+use rustc_data_structures::sync::{self, Lrc};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::path::Path;
+
+use rustc_serialize::json::{object, Json, ToJson};
+
+const SARIF_SCHEMA: &str =
+    "https://schemastore.azurewebsites.net/schemas/json/sarif-2.1.0-rtm.4.json";
+
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    registry: Option<Registry>,
+    sm: Lrc<dyn SourceMapper + sync::Send + sync::Sync>,
+    /// The rules of the run, in the order in which they were first referred to.
+    rules: Vec<Json>,
+    /// The index in `rules` of each error code and lint name.
+    rule_indices: HashMap<String, usize>,
+    results: Vec<Json>,
+    notifications: Vec<Json>,
+    /// Whether no error has been emitted so far.
+    successful: bool,
+}
+
+impl SarifEmitter {
+    pub fn stderr(registry: Option<Registry>, source_map: Lrc<SourceMap>) -> SarifEmitter {
+        SarifEmitter::new(Box::new(io::stderr()), registry, source_map)
+    }
+
+    pub fn new(dst: Box<dyn Write + Send>,
+               registry: Option<Registry>,
+               source_map: Lrc<SourceMap>) -> SarifEmitter {
+        SarifEmitter {
+            dst,
+            registry,
+            sm: source_map,
+            rules: vec![],
+            rule_indices: HashMap::new(),
+            results: vec![],
+            notifications: vec![],
+            successful: true,
+        }
+    }
+
+    /// Returns the id of the rule for `code` and a reference to it, adding the rule to the run if
+    /// needed.
+    fn rule_reference(&mut self, code: &DiagnosticId) -> (String, Json) {
+        let id = match *code {
+            DiagnosticId::Error(ref id) | DiagnosticId::Lint(ref id) => id,
+        };
+        let index = match self.rule_indices.get(id) {
+            Some(&index) => index,
+            None => {
+                let mut rule = vec![("id", id.to_json())];
+                if let DiagnosticId::Error(..) = *code {
+                    let explanation = self.registry.as_ref()
+                                                   .and_then(|r| r.find_description(id));
+                    if let Some(explanation) = explanation {
+                        rule.push(("help", object(vec![("text", explanation.to_json())])));
+                        rule.push(("helpUri",
+                                   format!("https://doc.rust-lang.org/error-index.html#{}", id)
+                                       .to_json()));
+                    }
+                }
+                self.rules.push(object(rule));
+                self.rule_indices.insert(id.clone(), self.rules.len() - 1);
+                self.rules.len() - 1
+            }
+        };
+        (id.clone(), object(vec![("id", id.to_json()), ("index", index.to_json())]))
+    }
+
+    /// Maps `span` to the URI of its file and a SARIF region, if it points into a source file.
+    ///
+    /// Spans in macro expansions are mapped to the place where the macro was called.
+    fn region(&self, span: Span) -> Option<(String, Json)> {
+        let span = self.sm.call_span_if_macro(span);
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let uri = match start.file.name {
+            FileName::Real(ref path) => path_to_uri(path),
+            _ => return None,
+        };
+
+        let region = object(vec![
+            ("startLine", start.line.to_json()),
+            ("startColumn", (start.col.0 + 1).to_json()),
+            ("endLine", end.line.to_json()),
+            ("endColumn", (end.col.0 + 1).to_json()),
+            ("byteOffset", (span.lo().0 - start.file.start_pos.0).to_json()),
+            ("byteLength", (span.hi().0 - span.lo().0).to_json()),
+        ]);
+        Some((uri, region))
+    }
+
+    fn location(&self, span: Span, message: Option<String>) -> Option<Json> {
+        self.region(span).map(|(uri, region)| {
+            let physical_location = object(vec![
+                ("artifactLocation", object(vec![("uri", uri.to_json())])),
+                ("region", region),
+            ]);
+            let mut location = vec![("physicalLocation", physical_location)];
+            if let Some(message) = message {
+                location.push(("message", object(vec![("text", message.to_json())])));
+            }
+            object(location)
+        })
+    }
+
+    /// Turns each alternative of a suggestion into a fix, replacing the spans of its parts.
+    fn fixes(&self, suggestion: &CodeSuggestion) -> Vec<Json> {
+        suggestion.substitutions.iter().filter_map(|substitution| {
+            // Group the replacements by file, as SARIF wants one change per artifact.
+            let mut changes: BTreeMap<String, Vec<Json>> = BTreeMap::new();
+            for part in &substitution.parts {
+                // Replacing the call of a macro instead of its expansion would be wrong.
+                if self.sm.call_span_if_macro(part.span) != part.span {
+                    return None;
+                }
+                let (uri, region) = self.region(part.span)?;
+                changes.entry(uri).or_default().push(object(vec![
+                    ("deletedRegion", region),
+                    ("insertedContent", object(vec![("text", part.snippet.to_json())])),
+                ]));
+            }
+
+            let artifact_changes = changes.into_iter().map(|(uri, replacements)| {
+                object(vec![
+                    ("artifactLocation", object(vec![("uri", uri.to_json())])),
+                    ("replacements", Json::Array(replacements)),
+                ])
+            }).collect();
+            Some(object(vec![
+                ("description", object(vec![("text", suggestion.msg.to_json())])),
+                ("artifactChanges", Json::Array(artifact_changes)),
+                ("properties", object(vec![
+                    ("applicability", format!("{:?}", suggestion.applicability).to_json()),
+                ])),
+            ]))
+        }).collect()
+    }
+
+    fn log(&mut self) -> Json {
+        let driver = object(vec![
+            ("name", "rustc".to_json()),
+            ("informationUri", "https://www.rust-lang.org/".to_json()),
+            ("rules", Json::Array(self.rules.drain(..).collect())),
+        ]);
+        let invocation = object(vec![
+            ("executionSuccessful", self.successful.to_json()),
+            ("toolExecutionNotifications", Json::Array(self.notifications.drain(..).collect())),
+        ]);
+        let run = object(vec![
+            ("tool", object(vec![("driver", driver)])),
+            ("invocations", Json::Array(vec![invocation])),
+            ("columnKind", "unicodeCodePoints".to_json()),
+            ("results", Json::Array(self.results.drain(..).collect())),
+        ]);
+        object(vec![
+            ("$schema", SARIF_SCHEMA.to_json()),
+            ("version", "2.1.0".to_json()),
+            ("runs", Json::Array(vec![run])),
+        ])
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit(&mut self, db: &DiagnosticBuilder) {
+        let level = match db.level {
+            Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error => {
+                self.successful = false;
+                "error"
+            }
+            Level::Warning => "warning",
+            Level::Note | Level::Help => "note",
+            // Failure notes only point to `--explain`, which the rules already do.
+            Level::Cancelled | Level::FailureNote => return,
+        };
+
+        // The sub-diagnostics are kept in the message, the way they would be rendered.
+        let mut message = db.message();
+        for child in &db.children {
+            message.push_str(&format!("\n{}: {}", child.level, child.message()));
+        }
+
+        let mut fields = vec![
+            ("level", level.to_json()),
+            ("message", object(vec![("text", message.to_json())])),
+        ];
+        let rule = db.code.as_ref().map(|code| self.rule_reference(code));
+
+        let span_labels = db.span.span_labels();
+        let locations: Vec<_> = span_labels.iter()
+            .filter(|label| label.is_primary)
+            .filter_map(|label| self.location(label.span, None))
+            .collect();
+        if locations.is_empty() {
+            if let Some((_, reference)) = rule {
+                fields.push(("associatedRule", reference));
+            }
+            self.notifications.push(object(fields));
+            return;
+        }
+
+        let related_locations: Vec<_> = span_labels.iter()
+            .filter(|label| !label.is_primary)
+            .filter_map(|label| self.location(label.span, label.label.clone()))
+            .chain(db.children.iter().flat_map(|child| {
+                let message = format!("{}: {}", child.level, child.message());
+                child.span.primary_spans().iter()
+                     .filter_map(|&span| self.location(span, Some(message.clone())))
+                     .collect::<Vec<_>>()
+            }))
+            .collect();
+        let fixes: Vec<_> = db.suggestions.iter()
+            .flat_map(|suggestion| self.fixes(suggestion))
+            .collect();
+
+        if let Some((id, reference)) = rule {
+            fields.push(("ruleId", id.to_json()));
+            fields.push(("rule", reference));
+        }
+        fields.push(("locations", Json::Array(locations)));
+        if !related_locations.is_empty() {
+            fields.push(("relatedLocations", Json::Array(related_locations)));
+        }
+        if !fixes.is_empty() {
+            fields.push(("fixes", Json::Array(fixes)));
+        }
+        self.results.push(object(fields));
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        let log = self.log();
+        // This runs while the session is torn down, so there is nothing left to report a failure
+        // to write the log to.
+        let _ = writeln!(&mut self.dst, "{}", log.pretty());
+    }
+}
+
+/// Turns a path into the URI SARIF wants: relative paths stay relative, absolute ones become
+/// `file` URIs. Characters that are not allowed in URIs are percent-encoded.
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::with_capacity(path.len());
+    if path.starts_with('/') {
+        uri.push_str("file://");
+    } else if path.as_bytes().get(1) == Some(&b':') {
+        // A Windows path with a drive letter.
+        uri.push_str("file:///");
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' |
+            b'-' | b'.' | b'_' | b'~' | b'/' | b':' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
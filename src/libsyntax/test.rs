@@ -47,6 +47,13 @@ struct Test {
     path: Vec<Ident>,
 }
 
+/// Functions named by `#![test_setup(..)]` and `#![test_teardown(..)]`.
+#[derive(Default)]
+struct SuiteFixtures {
+    setup: Option<ast::Path>,
+    teardown: Option<ast::Path>,
+}
+
 struct TestCtxt<'a> {
     span_diagnostic: &'a errors::Handler,
     path: Vec<Ident>,
@@ -57,6 +64,7 @@ struct TestCtxt<'a> {
     ctxt: SyntaxContext,
     features: &'a Features,
     test_runner: Option<ast::Path>,
+    suite_fixtures: SuiteFixtures,
 
     // top-level re-export submodule, filled out after folding is finished
     toplevel_reexport: Option<Ident>,
@@ -81,10 +89,15 @@ pub fn modify_for_testing(sess: &ParseSess,
     // Do this here so that the test_runner crate attribute gets marked as used
     // even in non-test builds
     let test_runner = get_test_runner(span_diagnostic, &krate);
+    let suite_fixtures = SuiteFixtures {
+        setup: fixture_path(span_diagnostic, &krate.attrs, "test_setup"),
+        teardown: fixture_path(span_diagnostic, &krate.attrs, "test_teardown"),
+    };
 
     if should_test {
         generate_test_harness(sess, resolver, reexport_test_harness_main,
-                              krate, span_diagnostic, features, test_runner)
+                              krate, span_diagnostic, features, test_runner,
+                              suite_fixtures)
     } else {
         krate
     }
@@ -265,7 +278,8 @@ fn generate_test_harness(sess: &ParseSess,
                          krate: ast::Crate,
                          sd: &errors::Handler,
                          features: &Features,
-                         test_runner: Option<ast::Path>) -> ast::Crate {
+                         test_runner: Option<ast::Path>,
+                         suite_fixtures: SuiteFixtures) -> ast::Crate {
     // Remove the entry points
     let mut cleaner = EntryPointCleaner { depth: 0 };
     let krate = cleaner.fold_crate(krate);
@@ -286,7 +300,8 @@ fn generate_test_harness(sess: &ParseSess,
         toplevel_reexport: None,
         ctxt: SyntaxContext::empty().apply_mark(mark),
         features,
-        test_runner
+        test_runner,
+        suite_fixtures,
     };
 
     mark.set_expn_info(ExpnInfo {
@@ -346,9 +361,35 @@ fn mk_main(cx: &mut TestCtxt) -> P<ast::Item> {
 
     test_runner.span = sp;
 
+    let mut test_main_args = vec![mk_tests_slice(cx)];
+
+    // test::test_main_static_with_options(..., test::Options::new().suite_setup(...))
+    let fixtures = &cx.suite_fixtures;
+    if fixtures.setup.is_some() || fixtures.teardown.is_some() {
+        if cx.test_runner.is_some() {
+            let span = fixtures.setup.as_ref().or(fixtures.teardown.as_ref()).unwrap().span;
+            cx.span_diagnostic.span_err(span,
+                "`#![test_setup]` and `#![test_teardown]` can not be used with a custom \
+                 test runner");
+        }
+        test_runner = ecx.path(sp, vec![test_id, ecx.ident_of("test_main_static_with_options")]);
+
+        let options_new = ecx.path(sp, vec![
+            test_id, ecx.ident_of("Options"), ecx.ident_of("new")
+        ]);
+        let mut options = ecx.expr_call(sp, ecx.expr_path(options_new), vec![]);
+        for (method, path) in vec![("suite_setup", &fixtures.setup),
+                                   ("suite_teardown", &fixtures.teardown)] {
+            if let Some(ref path) = *path {
+                options = ecx.expr_method_call(sp, options, ecx.ident_of(method),
+                                               vec![ecx.expr_path(path.clone())]);
+            }
+        }
+        test_main_args.push(options);
+    }
+
     let test_main_path_expr = ecx.expr_path(test_runner);
-    let call_test_main = ecx.expr_call(sp, test_main_path_expr,
-                                       vec![mk_tests_slice(cx)]);
+    let call_test_main = ecx.expr_call(sp, test_main_path_expr, test_main_args);
     let call_test_main = ecx.stmt_expr(call_test_main);
 
     // #![main]
@@ -436,6 +477,25 @@ fn is_test_case(i: &ast::Item) -> bool {
     attr::contains_name(&i.attrs, "rustc_test_marker")
 }
 
+/// Reads a fixture attribute like `#[test_setup(path)]` or `#![test_teardown(path)]`, naming a
+/// `fn()` to run around a test, or around all tests for the crate-level ones.
+pub fn fixture_path(sd: &errors::Handler, attrs: &[ast::Attribute], name: &str)
+                    -> Option<ast::Path> {
+    let attr = attr::find_by_name(attrs, name)?;
+    let path = attr.meta_item_list().and_then(|list| {
+        if list.len() != 1 {
+            return None;
+        }
+        list[0].word().map(|word| word.ident.clone())
+    });
+    if path.is_none() {
+        let bang = if attr.style == ast::AttrStyle::Inner { "!" } else { "" };
+        sd.span_err(attr.span(),
+            &format!("attribute must be of the form: `#{}[{}(path::to::function)]`", bang, name));
+    }
+    path
+}
+
 fn get_test_runner(sd: &errors::Handler, krate: &ast::Crate) -> Option<ast::Path> {
     let test_attr = attr::find_by_name(&krate.attrs, "test_runner")?;
     if let Some(meta_list) = test_attr.meta_item_list() {
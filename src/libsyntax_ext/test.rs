@@ -17,6 +17,7 @@ use syntax::ext::hygiene::{self, Mark, SyntaxContext};
 use syntax::attr;
 use syntax::ast;
 use syntax::print::pprust;
+use syntax::test::fixture_path;
 use syntax::symbol::Symbol;
 use syntax_pos::{DUMMY_SP, Span};
 use syntax::source_map::{ExpnInfo, MacroAttribute};
@@ -101,6 +102,8 @@ pub fn expand_test_or_bench(
     // creates $name: $expr
     let field = |name, expr| cx.field_imm(sp, cx.ident_of(name), expr);
 
+    let sd = &cx.parse_sess.span_diagnostic;
+
     let test_fn = if is_bench {
         // A simple ident for a lambda
         let b = cx.ident_of("b");
@@ -162,6 +165,28 @@ pub fn expand_test_or_bench(
                     field("ignore", cx.expr_bool(sp, should_ignore(&item))),
                     // allow_fail: true | false
                     field("allow_fail", cx.expr_bool(sp, should_fail(&item))),
+                    // timeout: Some(...) | None
+                    field("timeout", match test_timeout(cx, &item) {
+                        Some(secs) => cx.expr_some(sp, cx.expr_lit(sp,
+                            ast::LitKind::Int(secs as u128,
+                                              ast::LitIntType::Unsigned(ast::UintTy::U64)))),
+                        None => cx.expr_none(sp),
+                    }),
+                    // retries: Some(...) | None
+                    field("retries", match test_retries(cx, &item) {
+                        Some(n) => cx.expr_some(sp, cx.expr_usize(sp, n)),
+                        None => cx.expr_none(sp),
+                    }),
+                    // setup: Some(...) | None
+                    field("setup", match fixture_path(sd, &item.attrs, "test_setup") {
+                        Some(path) => cx.expr_some(sp, cx.expr_path(path)),
+                        None => cx.expr_none(sp),
+                    }),
+                    // teardown: Some(...) | None
+                    field("teardown", match fixture_path(sd, &item.attrs, "test_teardown") {
+                        Some(path) => cx.expr_some(sp, cx.expr_path(path)),
+                        None => cx.expr_none(sp),
+                    }),
                     // should_panic: ...
                     field("should_panic", match should_panic(cx, &item) {
                         // test::ShouldPanic::No
@@ -220,6 +245,59 @@ fn should_fail(i: &ast::Item) -> bool {
     attr::contains_name(&i.attrs, "allow_fail")
 }
 
+fn test_timeout(cx: &ExtCtxt, i: &ast::Item) -> Option<u64> {
+    let attr = attr::find_by_name(&i.attrs, "test_timeout")?;
+    let secs = attr.meta_item_list().and_then(|list| {
+        if list.len() != 1 {
+            return None;
+        }
+        match list[0].literal().map(|lit| &lit.node) {
+            Some(&ast::LitKind::Int(secs, _)) if secs > 0 && secs <= u64::max_value() as u128 => {
+                Some(secs as u64)
+            }
+            _ => None,
+        }
+    });
+    if secs.is_none() {
+        cx.parse_sess.span_diagnostic.span_err(
+            attr.span(),
+            "attribute must be of the form: `#[test_timeout(seconds)]` \
+             with a positive number of seconds"
+        );
+    }
+    secs
+}
+
+/// How often a `#[test_flaky]` test may be retried when no count is given.
+const DEFAULT_FLAKY_RETRIES: usize = 3;
+
+fn test_retries(cx: &ExtCtxt, i: &ast::Item) -> Option<usize> {
+    let attr = attr::find_by_name(&i.attrs, "test_flaky")?;
+    let list = match attr.meta_item_list() {
+        Some(list) => list,
+        None if attr.is_word() => return Some(DEFAULT_FLAKY_RETRIES),
+        None => Vec::new(),
+    };
+    let retries = if list.len() == 1 {
+        match list[0].literal().map(|lit| &lit.node) {
+            Some(&ast::LitKind::Int(n, _)) if n > 0 && n <= u32::max_value() as u128 => {
+                Some(n as usize)
+            }
+            _ => None,
+        }
+    } else {
+        None
+    };
+    if retries.is_none() {
+        cx.parse_sess.span_diagnostic.span_err(
+            attr.span(),
+            "attribute must be of the form: `#[test_flaky]` or `#[test_flaky(retries)]` \
+             with a positive number of retries"
+        );
+    }
+    retries
+}
+
 fn should_panic(cx: &ExtCtxt, i: &ast::Item) -> ShouldPanic {
     match attr::find_by_name(&i.attrs, "should_panic") {
         Some(attr) => {
@@ -0,0 +1,323 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Saving benchmark results under a name (`--save-baseline`) and comparing a
+//! later run against them (`--baseline`).
+//!
+//! Baselines are kept in `$RUST_TEST_BASELINE_DIR`, or next to the test
+//! executable in a `baselines` directory, as one plain text file per
+//! executable and baseline name.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::prelude::*;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+
+use stats;
+
+const HEADER: &str = "# libtest benchmark baseline v1";
+
+/// Differences with a p-value below this are considered significant.
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// What is remembered about a single benchmark.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BaselineEntry {
+    pub samples: usize,
+    pub median: f64,
+    pub mean: f64,
+    pub var: f64,
+}
+
+impl BaselineEntry {
+    pub fn new(summ: &stats::Summary, samples: usize) -> BaselineEntry {
+        BaselineEntry {
+            samples,
+            median: summ.median,
+            mean: summ.mean,
+            var: summ.var,
+        }
+    }
+}
+
+/// How a benchmark changed relative to the baseline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BaselineComparison {
+    /// Change of the mean time per iteration, in percent. Positive means the
+    /// benchmark got slower. This is the mean rather than the median, as the
+    /// mean is what the significance test compares.
+    pub change_pct: f64,
+    /// Probability of seeing a difference this large by chance alone.
+    pub p_value: f64,
+    /// Whether the benchmark got slower by more than the allowed threshold,
+    /// and the slowdown is unlikely to be noise. Such a benchmark fails.
+    pub regression: bool,
+}
+
+impl BaselineComparison {
+    pub fn is_significant(&self) -> bool {
+        self.p_value < SIGNIFICANCE_LEVEL
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Baseline(BTreeMap<String, BaselineEntry>);
+
+impl Baseline {
+    pub fn new() -> Baseline {
+        Baseline(BTreeMap::new())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn insert(&mut self, bench: &str, entry: BaselineEntry) {
+        self.0.insert(bench.to_owned(), entry);
+    }
+
+    /// Compares a fresh result for `bench` against the saved one, if any. It
+    /// is a regression if it got slower by more than `threshold_pct` percent.
+    pub fn compare(
+        &self,
+        bench: &str,
+        new: &BaselineEntry,
+        threshold_pct: f64,
+    ) -> Option<BaselineComparison> {
+        let old = self.0.get(bench)?;
+        let change_pct = if old.mean == 0.0 {
+            0.0
+        } else {
+            (new.mean - old.mean) / old.mean * 100.0
+        };
+        let p_value = stats::welch_t_test(
+            (old.mean, old.var, old.samples),
+            (new.mean, new.var, new.samples),
+        );
+        let mut cmp = BaselineComparison { change_pct, p_value, regression: false };
+        cmp.regression = cmp.is_significant() && cmp.change_pct > threshold_pct;
+        Some(cmp)
+    }
+
+    pub fn load(name: &str) -> io::Result<Baseline> {
+        let path = baseline_path(name)?;
+        let file = File::open(&path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("could not read baseline `{}` from {}: {}", name, path.display(), e),
+            )
+        })?;
+        Baseline::read(&path, file)
+    }
+
+    /// Saves the results under `name`. Benchmarks that were not run this
+    /// time, for example because of a filter, keep their saved results.
+    pub fn save(&self, name: &str) -> io::Result<()> {
+        let path = baseline_path(name)?;
+        let mut merged = match File::open(&path) {
+            Ok(file) => Baseline::read(&path, file)?,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Baseline::new(),
+            Err(e) => return Err(e),
+        };
+        merged.merge(self);
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = File::create(&path)?;
+        merged.write_to(&mut file)
+    }
+
+    /// Replaces the results of the benchmarks in `newer`.
+    fn merge(&mut self, newer: &Baseline) {
+        self.0.extend(newer.0.iter().map(|(bench, entry)| (bench.clone(), *entry)));
+    }
+
+    fn read(path: &Path, file: File) -> io::Result<Baseline> {
+        Baseline::parse(BufReader::new(file)).map_err(|msg| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("malformed baseline {}: {}", path.display(), msg),
+            )
+        })
+    }
+
+    fn write_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "{}", HEADER)?;
+        for (bench, entry) in &self.0 {
+            writeln!(
+                out,
+                "{}\t{}\t{}\t{}\t{}",
+                escape_name(bench), entry.samples, entry.median, entry.mean, entry.var
+            )?;
+        }
+        Ok(())
+    }
+
+    fn parse<R: BufRead>(input: R) -> Result<Baseline, String> {
+        let mut lines = input.lines();
+        match lines.next() {
+            Some(Ok(ref header)) if header == HEADER => {}
+            _ => return Err("missing header".to_owned()),
+        }
+
+        let mut baseline = Baseline::new();
+        for (i, line) in lines.enumerate() {
+            let line = line.map_err(|e| e.to_string())?;
+            let fields = line.split('\t').collect::<Vec<_>>();
+            if fields.len() != 5 {
+                return Err(format!("line {}: expected 5 fields", i + 2));
+            }
+            let num = |s: &str| s.parse::<f64>().map_err(|e| format!("line {}: {}", i + 2, e));
+            let entry = BaselineEntry {
+                samples: fields[1].parse().map_err(|e| format!("line {}: {}", i + 2, e))?,
+                median: num(fields[2])?,
+                mean: num(fields[3])?,
+                var: num(fields[4])?,
+            };
+            let bench = unescape_name(fields[0]).ok_or_else(|| {
+                format!("line {}: invalid escape in benchmark name", i + 2)
+            })?;
+            baseline.insert(&bench, entry);
+        }
+        Ok(baseline)
+    }
+}
+
+/// Escapes the tabs and line breaks in a benchmark name, which would
+/// otherwise be taken for field and line separators.
+fn escape_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape_name(escaped: &str) -> Option<String> {
+    let mut name = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            name.push(c);
+            continue;
+        }
+        name.push(match chars.next()? {
+            '\\' => '\\',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            _ => return None,
+        });
+    }
+    Some(name)
+}
+
+fn baseline_path(name: &str) -> io::Result<PathBuf> {
+    let exe = env::current_exe()?;
+    let dir = match env::var_os("RUST_TEST_BASELINE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => exe.parent().map(|p| p.join("baselines")).unwrap_or_default(),
+    };
+    let stem = exe
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok(dir.join(format!("{}.{}.txt", stem, name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Baseline, BaselineEntry};
+
+    fn entry(median: f64) -> BaselineEntry {
+        BaselineEntry {
+            samples: 50,
+            median,
+            mean: median,
+            var: 1.0,
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut baseline = Baseline::new();
+        baseline.insert("a::bench", entry(1000.0));
+        baseline.insert("b", entry(12.5));
+
+        let mut buf = Vec::new();
+        baseline.write_to(&mut buf).unwrap();
+        assert_eq!(Baseline::parse(&buf[..]).unwrap(), baseline);
+    }
+
+    #[test]
+    fn regressions_need_significance_and_threshold() {
+        let mut baseline = Baseline::new();
+        baseline.insert("bench", entry(1000.0));
+
+        let slower = baseline.compare("bench", &entry(1100.0), 5.0).unwrap();
+        assert!((slower.change_pct - 10.0).abs() < 1e-9);
+        assert!(slower.regression);
+        assert!(!baseline.compare("bench", &entry(1100.0), 20.0).unwrap().regression);
+
+        let same = baseline.compare("bench", &entry(1000.0), 0.0).unwrap();
+        assert!(!same.regression);
+
+        assert!(baseline.compare("unknown", &entry(1.0), 5.0).is_none());
+    }
+
+    #[test]
+    fn change_is_measured_on_the_mean() {
+        let mut baseline = Baseline::new();
+        baseline.insert("bench", entry(1000.0));
+
+        let new = BaselineEntry { mean: 1200.0, ..entry(1000.0) };
+        let cmp = baseline.compare("bench", &new, 5.0).unwrap();
+        assert!((cmp.change_pct - 20.0).abs() < 1e-9);
+        assert!(cmp.regression);
+    }
+
+    #[test]
+    fn names_with_separators_roundtrip() {
+        let mut baseline = Baseline::new();
+        baseline.insert("tab\there", entry(1.0));
+        baseline.insert("line\nbreak\\t", entry(2.0));
+
+        let mut buf = Vec::new();
+        baseline.write_to(&mut buf).unwrap();
+        assert_eq!(String::from_utf8_lossy(&buf).lines().count(), 3);
+        assert_eq!(Baseline::parse(&buf[..]).unwrap(), baseline);
+    }
+
+    #[test]
+    fn merge_keeps_benchmarks_that_were_not_run() {
+        let mut saved = Baseline::new();
+        saved.insert("a", entry(1.0));
+        saved.insert("b", entry(2.0));
+        let mut run = Baseline::new();
+        run.insert("b", entry(3.0));
+        run.insert("c", entry(4.0));
+
+        saved.merge(&run);
+        let mut expected = Baseline::new();
+        expected.insert("a", entry(1.0));
+        expected.insert("b", entry(3.0));
+        expected.insert("c", entry(4.0));
+        assert_eq!(saved, expected);
+    }
+}
@@ -31,8 +31,16 @@ impl<T: Write> JsonFormatter<T> {
         ty: &str,
         name: &str,
         evt: &str,
+        exec_time: Option<&TestExecTime>,
         extra: Option<String>,
     ) -> io::Result<()> {
+        let extra = match (exec_time, extra) {
+            (Some(time), Some(extra)) => {
+                Some(format!(r#"{}, "exec_time": {}"#, extra, fmt_secs(time.0)))
+            }
+            (Some(time), None) => Some(format!(r#""exec_time": {}"#, fmt_secs(time.0))),
+            (None, extra) => extra,
+        };
         if let Some(extras) = extra {
             self.write_message(&*format!(
                 r#"{{ "type": "{}", "name": "{}", "event": "{}", {} }}"#,
@@ -48,10 +56,15 @@ impl<T: Write> JsonFormatter<T> {
 }
 
 impl<T: Write> OutputFormatter for JsonFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()> {
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>)
+        -> io::Result<()> {
+        let shuffle = match shuffle_seed {
+            Some(seed) => format!(r#", "shuffle_seed": {}"#, seed),
+            None => String::new(),
+        };
         self.write_message(&*format!(
-            r#"{{ "type": "suite", "event": "started", "test_count": {} }}"#,
-            test_count
+            r#"{{ "type": "suite", "event": "started", "test_count": {}{} }}"#,
+            test_count, shuffle
         ))
     }
 
@@ -66,10 +79,11 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
         &mut self,
         desc: &TestDesc,
         result: &TestResult,
+        exec_time: Option<&TestExecTime>,
         stdout: &[u8],
     ) -> io::Result<()> {
         match *result {
-            TrOk => self.write_event("test", desc.name.as_slice(), "ok", None),
+            TrOk => self.write_event("test", desc.name.as_slice(), "ok", exec_time, None),
 
             TrFailed => {
                 let extra_data = if stdout.len() > 0 {
@@ -81,22 +95,43 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
                     None
                 };
 
-                self.write_event("test", desc.name.as_slice(), "failed", extra_data)
+                self.write_event("test", desc.name.as_slice(), "failed", exec_time, extra_data)
             }
 
             TrFailedMsg(ref m) => self.write_event(
                 "test",
                 desc.name.as_slice(),
                 "failed",
+                exec_time,
                 Some(format!(r#""message": "{}""#, EscapedString(m))),
             ),
 
-            TrIgnored => self.write_event("test", desc.name.as_slice(), "ignored", None),
+            TrIgnored => self.write_event("test", desc.name.as_slice(), "ignored", None, None),
 
             TrAllowedFail => {
-                self.write_event("test", desc.name.as_slice(), "allowed_failure", None)
+                self.write_event("test", desc.name.as_slice(), "allowed_failure", exec_time, None)
+            }
+
+            TrTimedOut => {
+                self.write_event("test", desc.name.as_slice(), "timed_out", exec_time, None)
             }
 
+            TrFixtureFailed(ref m) => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "fixture_failed",
+                exec_time,
+                Some(format!(r#""message": "{}""#, EscapedString(m))),
+            ),
+
+            TrFlaky(attempts) => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "flaky",
+                exec_time,
+                Some(format!(r#""attempts": {}"#, attempts)),
+            ),
+
             TrBench(ref bs) => {
                 let median = bs.ns_iter_summ.median as usize;
                 let deviation = (bs.ns_iter_summ.max - bs.ns_iter_summ.min) as usize;
@@ -107,12 +142,27 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
                     format!(r#", "mib_per_second": {}"#, bs.mb_s)
                 };
 
+                let elems = if bs.elems_s == 0 {
+                    String::new()
+                } else {
+                    format!(r#", "elements_per_second": {}"#, bs.elems_s)
+                };
+
+                let baseline = match bs.baseline {
+                    Some(ref cmp) => format!(
+                        ", \"baseline_change_pct\": {}, \"baseline_p_value\": {}, \
+                         \"regression\": {}",
+                        cmp.change_pct, cmp.p_value, cmp.regression
+                    ),
+                    None => String::new(),
+                };
+
                 let line = format!(
                     "{{ \"type\": \"bench\", \
                      \"name\": \"{}\", \
                      \"median\": {}, \
-                     \"deviation\": {}{} }}",
-                    desc.name, median, deviation, mbps
+                     \"deviation\": {}{}{}{} }}",
+                    desc.name, median, deviation, mbps, elems, baseline
                 );
 
                 self.write_message(&*line)
@@ -128,25 +178,33 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
     }
 
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let success = state.failed == 0 && state.suite_teardown_failure.is_none();
+        let teardown = match state.suite_teardown_failure {
+            Some(ref msg) => format!(r#", "teardown_failed": "{}""#, EscapedString(msg)),
+            None => String::new(),
+        };
         self.write_message(&*format!(
             "{{ \"type\": \"suite\", \
              \"event\": \"{}\", \
              \"passed\": {}, \
+             \"flaky\": {}, \
              \"failed\": {}, \
              \"allowed_fail\": {}, \
              \"ignored\": {}, \
              \"measured\": {}, \
-             \"filtered_out\": {} }}",
-            if state.failed == 0 { "ok" } else { "failed" },
+             \"filtered_out\": {}{} }}",
+            if success { "ok" } else { "failed" },
             state.passed,
+            state.flaky,
             state.failed + state.allowed_fail,
             state.allowed_fail,
             state.ignored,
             state.measured,
-            state.filtered_out
+            state.filtered_out,
+            teardown
         ))?;
 
-        Ok(state.failed == 0)
+        Ok(success)
     }
 }
 
@@ -0,0 +1,300 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::*;
+
+/// Emits a JUnit XML report once the whole run has finished.
+///
+/// JUnit wants the totals as attributes of the `<testsuite>` element, so
+/// unlike the other formatters nothing is written until `write_run_finish`;
+/// results are buffered in the meantime.
+pub(crate) struct JunitFormatter<T> {
+    out: OutputLocation<T>,
+    shuffle_seed: Option<u64>,
+    results: Vec<(TestDesc, TestResult, Duration, Vec<u8>)>,
+}
+
+impl<T: Write> JunitFormatter<T> {
+    pub fn new(out: OutputLocation<T>) -> Self {
+        Self {
+            out,
+            shuffle_seed: None,
+            results: Vec::new(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
+    fn write_message(&mut self, s: &str) -> io::Result<()> {
+        self.out.write_all(s.as_ref())?;
+        self.out.write_all(b"\n")
+    }
+
+    fn write_testcase(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        duration: Duration,
+        stdout: &[u8],
+    ) -> io::Result<()> {
+        let (class_name, test_name) = split_class_name(desc.name.as_slice());
+        let open = format!(
+            r#"<testcase classname="{}" name="{}" time="{}""#,
+            EscapedXml(class_name),
+            EscapedXml(test_name),
+            fmt_secs(duration)
+        );
+
+        match *result {
+            TrOk | TrAllowedFail => self.write_message(&*format!("{}/>", open)),
+
+            TrFailed | TrFailedMsg(_) | TrTimedOut => {
+                let (ty, message) = match *result {
+                    TrFailedMsg(ref msg) => ("assert", &**msg),
+                    TrTimedOut => ("timeout", "test did not finish within its time limit"),
+                    _ => ("assert", "test panicked"),
+                };
+                self.write_message(&*format!("{}>", open))?;
+                self.write_message(&*format!(
+                    r#"<failure type="{}" message="{}"/>"#,
+                    ty,
+                    EscapedXml(message)
+                ))?;
+                if !stdout.is_empty() {
+                    self.write_message(&*format!(
+                        "<system-out>{}</system-out>",
+                        EscapedXml(String::from_utf8_lossy(stdout))
+                    ))?;
+                }
+                self.write_message("</testcase>")
+            }
+
+            TrFixtureFailed(ref msg) => {
+                self.write_message(&*format!("{}>", open))?;
+                self.write_message(&*format!(
+                    r#"<error type="fixture" message="{}"/>"#,
+                    EscapedXml(msg)
+                ))?;
+                if !stdout.is_empty() {
+                    self.write_message(&*format!(
+                        "<system-out>{}</system-out>",
+                        EscapedXml(String::from_utf8_lossy(stdout))
+                    ))?;
+                }
+                self.write_message("</testcase>")
+            }
+
+            TrFlaky(attempts) => {
+                self.write_message(&*format!("{}>", open))?;
+                self.write_message("<properties>")?;
+                self.write_message(r#"<property name="flaky" value="true"/>"#)?;
+                self.write_message(&*format!(
+                    r#"<property name="attempts" value="{}"/>"#,
+                    attempts
+                ))?;
+                self.write_message("</properties>")?;
+                self.write_message("</testcase>")
+            }
+
+            TrIgnored => {
+                self.write_message(&*format!("{}>", open))?;
+                self.write_message("<skipped/>")?;
+                self.write_message("</testcase>")
+            }
+
+            TrBench(ref bs) => {
+                let median = bs.ns_iter_summ.median as usize;
+                let deviation = (bs.ns_iter_summ.max - bs.ns_iter_summ.min) as usize;
+
+                self.write_message(&*format!("{}>", open))?;
+                self.write_message("<properties>")?;
+                self.write_message(&*format!(
+                    r#"<property name="median" value="{}"/>"#,
+                    median
+                ))?;
+                self.write_message(&*format!(
+                    r#"<property name="deviation" value="{}"/>"#,
+                    deviation
+                ))?;
+                if bs.mb_s != 0 {
+                    self.write_message(&*format!(
+                        r#"<property name="mib_per_second" value="{}"/>"#,
+                        bs.mb_s
+                    ))?;
+                }
+                if bs.elems_s != 0 {
+                    self.write_message(&*format!(
+                        r#"<property name="elements_per_second" value="{}"/>"#,
+                        bs.elems_s
+                    ))?;
+                }
+                if let Some(ref cmp) = bs.baseline {
+                    self.write_message(&*format!(
+                        r#"<property name="baseline_change_pct" value="{}"/>"#,
+                        cmp.change_pct
+                    ))?;
+                    self.write_message(&*format!(
+                        r#"<property name="baseline_p_value" value="{}"/>"#,
+                        cmp.p_value
+                    ))?;
+                }
+                self.write_message("</properties>")?;
+                if bs.baseline.as_ref().map_or(false, |cmp| cmp.regression) {
+                    self.write_message(
+                        r#"<failure type="regression" message="slower than the baseline"/>"#,
+                    )?;
+                }
+                self.write_message("</testcase>")
+            }
+        }
+    }
+}
+
+impl<T: Write> OutputFormatter for JunitFormatter<T> {
+    fn write_run_start(&mut self, _test_count: usize, shuffle_seed: Option<u64>)
+        -> io::Result<()> {
+        self.shuffle_seed = shuffle_seed;
+        Ok(())
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&TestExecTime>,
+        stdout: &[u8],
+    ) -> io::Result<()> {
+        // Benchmarks and ignored tests have no execution time of their own.
+        let duration = exec_time.map_or(Duration::new(0, 0), |time| time.0);
+        self.results
+            .push((desc.clone(), result.clone(), duration, stdout.to_vec()));
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // Timeouts are only warnings; the test still reports a result later.
+        Ok(())
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let total_time = self.results
+            .iter()
+            .fold(Duration::new(0, 0), |acc, &(_, _, d, _)| acc + d);
+        // JUnit counts broken fixtures as errors rather than failures.
+        let errors = self.results
+            .iter()
+            .filter(|&&(_, ref result, _, _)| match *result {
+                TrFixtureFailed(_) => true,
+                _ => false,
+            })
+            .count();
+
+        self.write_message(r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        self.write_message("<testsuites>")?;
+        self.write_message(&*format!(
+            "<testsuite name=\"test\" package=\"test\" id=\"0\" \
+             errors=\"{}\" \
+             failures=\"{}\" \
+             tests=\"{}\" \
+             skipped=\"{}\" \
+             time=\"{}\">",
+            errors,
+            state.failed - errors,
+            state.total,
+            state.ignored,
+            fmt_secs(total_time)
+        ))?;
+
+        if let Some(seed) = self.shuffle_seed {
+            self.write_message("<properties>")?;
+            self.write_message(&*format!(
+                r#"<property name="shuffle_seed" value="{}"/>"#,
+                seed
+            ))?;
+            self.write_message("</properties>")?;
+        }
+
+        let results = ::std::mem::replace(&mut self.results, Vec::new());
+        for (desc, result, duration, stdout) in results {
+            self.write_testcase(&desc, &result, duration, &stdout)?;
+        }
+
+        if let Some(ref msg) = state.suite_teardown_failure {
+            self.write_message(&*format!(
+                "<system-err>suite teardown failed: {}</system-err>",
+                EscapedXml(msg)
+            ))?;
+        }
+
+        self.write_message("</testsuite>")?;
+        self.write_message("</testsuites>")?;
+
+        Ok(state.failed == 0 && state.suite_teardown_failure.is_none())
+    }
+}
+
+/// Splits `a::b::c` into the JUnit class name `a::b` and test name `c`.
+/// Tests at the crate root are reported under the class name `crate`.
+fn split_class_name(name: &str) -> (&str, &str) {
+    match name.rfind("::") {
+        Some(i) => (&name[..i], &name[i + 2..]),
+        None => ("crate", name),
+    }
+}
+
+/// A formatting utility used to print strings as XML attribute values or
+/// character data. Characters that XML 1.0 does not allow at all are
+/// replaced with U+FFFD.
+struct EscapedXml<S: AsRef<str>>(S);
+
+impl<S: AsRef<str>> ::std::fmt::Display for EscapedXml<S> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        let s = self.0.as_ref();
+        let mut start = 0;
+
+        for (i, c) in s.char_indices() {
+            let escaped = match c {
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '&' => "&amp;",
+                '"' => "&quot;",
+                '\'' => "&apos;",
+                '\t' | '\n' | '\r' => {
+                    continue;
+                }
+                '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => "\u{fffd}",
+                _ => {
+                    continue;
+                }
+            };
+
+            if start < i {
+                f.write_str(&s[start..i])?;
+            }
+
+            f.write_str(escaped)?;
+
+            start = i + c.len_utf8();
+        }
+
+        if start != s.len() {
+            f.write_str(&s[start..])?;
+        }
+
+        Ok(())
+    }
+}
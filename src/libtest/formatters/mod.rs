@@ -12,21 +12,44 @@ use super::*;
 
 mod pretty;
 mod json;
+mod junit;
 mod terse;
 
 pub(crate) use self::pretty::PrettyFormatter;
 pub(crate) use self::json::JsonFormatter;
+pub(crate) use self::junit::JunitFormatter;
 pub(crate) use self::terse::TerseFormatter;
 
 pub(crate) trait OutputFormatter {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()>;
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>)
+        -> io::Result<()>;
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()>;
     fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()>;
     fn write_result(
         &mut self,
         desc: &TestDesc,
         result: &TestResult,
+        exec_time: Option<&TestExecTime>,
         stdout: &[u8],
     ) -> io::Result<()>;
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool>;
 }
+
+/// Lists the tests which took longer than `--warn-slow` allows, slowest first.
+pub(crate) fn write_slow_tests<W: Write>(out: &mut W, state: &ConsoleTestState) -> io::Result<()> {
+    if let Some(limit) = state.warn_slow {
+        out.write_all(format!("\nslow tests (over {}):\n", TestExecTime(limit)).as_bytes())?;
+    }
+    let mut slow = state.slow.iter().collect::<Vec<_>>();
+    slow.sort_by(|a, b| (b.1).0.cmp(&(a.1).0));
+    for &(ref desc, ref time) in slow {
+        out.write_all(format!("    {} ({})\n", desc.name, time).as_bytes())?;
+    }
+    out.flush()
+}
+
+/// Formats a duration as fractional seconds, as used in the machine readable
+/// formats.
+pub(crate) fn fmt_secs(dur: Duration) -> String {
+    format!("{:.3}", ns_from_dur(dur) as f64 / 1_000_000_000.0)
+}
@@ -18,6 +18,9 @@ pub(crate) struct PrettyFormatter<T> {
     max_name_len: usize,
 
     is_multithreaded: bool,
+
+    /// Whether to print how long each test took
+    report_time: bool,
 }
 
 impl<T: Write> PrettyFormatter<T> {
@@ -26,12 +29,14 @@ impl<T: Write> PrettyFormatter<T> {
         use_color: bool,
         max_name_len: usize,
         is_multithreaded: bool,
+        report_time: bool,
     ) -> Self {
         PrettyFormatter {
             out,
             use_color,
             max_name_len,
             is_multithreaded,
+            report_time,
         }
     }
 
@@ -56,6 +61,18 @@ impl<T: Write> PrettyFormatter<T> {
         self.write_short_result("FAILED (allowed)", term::color::YELLOW)
     }
 
+    pub fn write_timed_out(&mut self) -> io::Result<()> {
+        self.write_short_result("FAILED (timed out)", term::color::RED)
+    }
+
+    pub fn write_fixture_failed(&mut self) -> io::Result<()> {
+        self.write_short_result("ERROR", term::color::RED)
+    }
+
+    pub fn write_flaky(&mut self) -> io::Result<()> {
+        self.write_short_result("FLAKY", term::color::YELLOW)
+    }
+
     pub fn write_bench(&mut self) -> io::Result<()> {
         self.write_pretty("bench", term::color::CYAN)
     }
@@ -65,8 +82,7 @@ impl<T: Write> PrettyFormatter<T> {
         result: &str,
         color: term::color::Color,
     ) -> io::Result<()> {
-        self.write_pretty(result, color)?;
-        self.write_plain("\n")
+        self.write_pretty(result, color)
     }
 
     pub fn write_pretty(&mut self, word: &str, color: term::color::Color) -> io::Result<()> {
@@ -155,9 +171,14 @@ impl<T: Write> PrettyFormatter<T> {
 }
 
 impl<T: Write> OutputFormatter for PrettyFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()> {
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>)
+        -> io::Result<()> {
         let noun = if test_count != 1 { "tests" } else { "test" };
-        self.write_plain(&format!("\nrunning {} {}\n", test_count, noun))
+        let shuffle = match shuffle_seed {
+            Some(seed) => format!(" (shuffle seed: {})", seed),
+            None => String::new(),
+        };
+        self.write_plain(&format!("\nrunning {} {}{}\n", test_count, noun, shuffle))
     }
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
@@ -172,21 +193,39 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
         Ok(())
     }
 
-    fn write_result(&mut self, desc: &TestDesc, result: &TestResult, _: &[u8]) -> io::Result<()> {
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&TestExecTime>,
+        _: &[u8],
+    ) -> io::Result<()> {
         if self.is_multithreaded {
             self.write_test_name(desc)?;
         }
 
         match *result {
-            TrOk => self.write_ok(),
-            TrFailed | TrFailedMsg(_) => self.write_failed(),
-            TrIgnored => self.write_ignored(),
-            TrAllowedFail => self.write_allowed_fail(),
+            TrOk => self.write_ok()?,
+            TrFailed | TrFailedMsg(_) => self.write_failed()?,
+            TrIgnored => self.write_ignored()?,
+            TrAllowedFail => self.write_allowed_fail()?,
+            TrTimedOut => self.write_timed_out()?,
+            TrFlaky(_) => self.write_flaky()?,
+            TrFixtureFailed(_) => self.write_fixture_failed()?,
             TrBench(ref bs) => {
-                self.write_bench()?;
-                self.write_plain(&format!(": {}\n", fmt_bench_samples(bs)))
+                if bs.baseline.as_ref().map_or(false, |cmp| cmp.regression) {
+                    self.write_failed()?;
+                } else {
+                    self.write_bench()?;
+                }
+                return self.write_plain(&format!(": {}\n", fmt_bench_samples(bs)));
             }
         }
+
+        if let (true, Some(time)) = (self.report_time, exec_time) {
+            self.write_plain(&format!(" <{}>", time))?;
+        }
+        self.write_plain("\n")
     }
 
     fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
@@ -204,10 +243,16 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
         if state.options.display_output {
             self.write_successes(state)?;
         }
-        let success = state.failed == 0;
-        if !success {
+        let success = state.failed == 0 && state.suite_teardown_failure.is_none();
+        if state.failed != 0 {
             self.write_failures(state)?;
         }
+        if let Some(ref msg) = state.suite_teardown_failure {
+            self.write_plain(&format!("\nsuite teardown failed: {}\n", msg))?;
+        }
+        if !state.slow.is_empty() {
+            write_slow_tests(&mut self.out, state)?;
+        }
 
         self.write_plain("\ntest result: ")?;
 
@@ -218,10 +263,16 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
             self.write_pretty("FAILED", term::color::RED)?;
         }
 
+        let passed = if state.flaky > 0 {
+            format!("{} passed ({} flaky)", state.passed, state.flaky)
+        } else {
+            format!("{} passed", state.passed)
+        };
+
         let s = if state.allowed_fail > 0 {
             format!(
-                ". {} passed; {} failed ({} allowed); {} ignored; {} measured; {} filtered out\n\n",
-                state.passed,
+                ". {}; {} failed ({} allowed); {} ignored; {} measured; {} filtered out\n\n",
+                passed,
                 state.failed + state.allowed_fail,
                 state.allowed_fail,
                 state.ignored,
@@ -230,8 +281,8 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
             )
         } else {
             format!(
-                ". {} passed; {} failed; {} ignored; {} measured; {} filtered out\n\n",
-                state.passed, state.failed, state.ignored, state.measured, state.filtered_out
+                ". {}; {} failed; {} ignored; {} measured; {} filtered out\n\n",
+                passed, state.failed, state.ignored, state.measured, state.filtered_out
             )
         };
 
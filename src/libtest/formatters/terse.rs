@@ -54,6 +54,18 @@ impl<T: Write> TerseFormatter<T> {
         self.write_short_result("a", term::color::YELLOW)
     }
 
+    pub fn write_timed_out(&mut self) -> io::Result<()> {
+        self.write_short_result("T", term::color::RED)
+    }
+
+    pub fn write_fixture_failed(&mut self) -> io::Result<()> {
+        self.write_short_result("E", term::color::RED)
+    }
+
+    pub fn write_flaky(&mut self) -> io::Result<()> {
+        self.write_short_result("f", term::color::YELLOW)
+    }
+
     pub fn write_bench(&mut self) -> io::Result<()> {
         self.write_pretty("bench", term::color::CYAN)
     }
@@ -162,10 +174,15 @@ impl<T: Write> TerseFormatter<T> {
 }
 
 impl<T: Write> OutputFormatter for TerseFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()> {
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>)
+        -> io::Result<()> {
         self.total_test_count = test_count;
         let noun = if test_count != 1 { "tests" } else { "test" };
-        self.write_plain(&format!("\nrunning {} {}\n", test_count, noun))
+        let shuffle = match shuffle_seed {
+            Some(seed) => format!(" (shuffle seed: {})", seed),
+            None => String::new(),
+        };
+        self.write_plain(&format!("\nrunning {} {}{}\n", test_count, noun, shuffle))
     }
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
@@ -180,17 +197,30 @@ impl<T: Write> OutputFormatter for TerseFormatter<T> {
         Ok(())
     }
 
-    fn write_result(&mut self, desc: &TestDesc, result: &TestResult, _: &[u8]) -> io::Result<()> {
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        _: Option<&TestExecTime>,
+        _: &[u8],
+    ) -> io::Result<()> {
         match *result {
             TrOk => self.write_ok(),
             TrFailed | TrFailedMsg(_) => self.write_failed(),
             TrIgnored => self.write_ignored(),
             TrAllowedFail => self.write_allowed_fail(),
+            TrTimedOut => self.write_timed_out(),
+            TrFlaky(_) => self.write_flaky(),
+            TrFixtureFailed(_) => self.write_fixture_failed(),
             TrBench(ref bs) => {
                 if self.is_multithreaded {
                     self.write_test_name(desc)?;
                 }
-                self.write_bench()?;
+                if bs.baseline.as_ref().map_or(false, |cmp| cmp.regression) {
+                    self.write_pretty("FAILED", term::color::RED)?;
+                } else {
+                    self.write_bench()?;
+                }
                 self.write_plain(&format!(": {}\n", fmt_bench_samples(bs)))
             }
         }
@@ -207,10 +237,16 @@ impl<T: Write> OutputFormatter for TerseFormatter<T> {
         if state.options.display_output {
             self.write_outputs(state)?;
         }
-        let success = state.failed == 0;
-        if !success {
+        let success = state.failed == 0 && state.suite_teardown_failure.is_none();
+        if state.failed != 0 {
             self.write_failures(state)?;
         }
+        if let Some(ref msg) = state.suite_teardown_failure {
+            self.write_plain(&format!("\nsuite teardown failed: {}\n", msg))?;
+        }
+        if !state.slow.is_empty() {
+            write_slow_tests(&mut self.out, state)?;
+        }
 
         self.write_plain("\ntest result: ")?;
 
@@ -221,10 +257,16 @@ impl<T: Write> OutputFormatter for TerseFormatter<T> {
             self.write_pretty("FAILED", term::color::RED)?;
         }
 
+        let passed = if state.flaky > 0 {
+            format!("{} passed ({} flaky)", state.passed, state.flaky)
+        } else {
+            format!("{} passed", state.passed)
+        };
+
         let s = if state.allowed_fail > 0 {
             format!(
-                ". {} passed; {} failed ({} allowed); {} ignored; {} measured; {} filtered out\n\n",
-                state.passed,
+                ". {}; {} failed ({} allowed); {} ignored; {} measured; {} filtered out\n\n",
+                passed,
                 state.failed + state.allowed_fail,
                 state.allowed_fail,
                 state.ignored,
@@ -233,8 +275,8 @@ impl<T: Write> OutputFormatter for TerseFormatter<T> {
             )
         } else {
             format!(
-                ". {} passed; {} failed; {} ignored; {} measured; {} filtered out\n\n",
-                state.passed, state.failed, state.ignored, state.measured, state.filtered_out
+                ". {}; {} failed; {} ignored; {} measured; {} filtered out\n\n",
+                passed, state.failed, state.ignored, state.measured, state.filtered_out
             )
         };
 
@@ -82,22 +82,38 @@ use std::thread;
 use std::time::{Duration, Instant};
 use std::borrow::Cow;
 use std::process;
+use std::process::{Command, ExitStatus, Stdio};
 
 const TEST_WARN_TIMEOUT_S: u64 = 60;
 const QUIET_MODE_MAX_COLUMN: usize = 100; // insert a '\n' after 100 tests in quiet mode
+const DEFAULT_BASELINE_THRESHOLD_PCT: f64 = 5.0;
+
+// Set when the harness re-executes itself to run a single test in its own
+// process; the value is the name of the test to run.
+const ISOLATED_TEST_ENV: &str = "__RUST_TEST_ISOLATED";
+// Exit codes of an isolated test process. They are deliberately unusual so
+// that a test which calls `process::exit` itself is reported as a failure.
+const TR_OK: i32 = 50;
+const TR_FAILED: i32 = 51;
+const TR_FIXTURE_FAILED: i32 = 52;
 
 // to be used by rustc to compile tests in libtest
 pub mod test {
     pub use {assert_test_result, filter_tests, parse_opts, run_test, test_main, test_main_static,
-             Bencher, DynTestFn, DynTestName, Metric, MetricMap, Options, RunIgnored, ShouldPanic,
-             StaticBenchFn, StaticTestFn, StaticTestName, TestDesc, TestDescAndFn, TestName,
-             TestOpts, TestResult, TrFailed, TrFailedMsg, TrIgnored, TrOk};
+             Bencher, DynTestFn, DynTestName, Isolation, Metric, MetricMap, Options, RunIgnored,
+             ShouldPanic,
+             StaticBenchFn, StaticTestFn, StaticTestName, TestDesc, TestDescAndFn, TestExecTime,
+             TestName, TestOpts, TestResult, TrFailed, TrFailedMsg, TrFlaky, TrIgnored, TrOk,
+             TrTimedOut};
 }
 
 pub mod stats;
+mod baseline;
 mod formatters;
 
-use formatters::{JsonFormatter, OutputFormatter, PrettyFormatter, TerseFormatter};
+use baseline::{Baseline, BaselineComparison, BaselineEntry};
+use formatters::{JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter,
+                 TerseFormatter};
 
 // The name of a test. By convention this follows the rules for rust
 // paths; i.e. it should be a series of identifiers separated by double
@@ -210,7 +226,10 @@ impl fmt::Debug for TestFn {
 pub struct Bencher {
     mode: BenchMode,
     summary: Option<stats::Summary>,
+    config: BenchConfig,
+    overrides: BenchOverrides,
     pub bytes: u64,
+    pub elements: u64,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -219,6 +238,69 @@ pub enum BenchMode {
     Single,
 }
 
+/// How a benchmark is sampled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BenchConfig {
+    /// How long to run the benchmark before taking any measurements.
+    pub warm_up_time: Duration,
+    /// How long a round of sampling has to take before its result is
+    /// accepted.
+    pub measurement_time: Duration,
+    /// How many samples each round of sampling takes.
+    pub sample_count: usize,
+    pub outliers: OutlierPolicy,
+}
+
+impl Default for BenchConfig {
+    fn default() -> BenchConfig {
+        BenchConfig {
+            warm_up_time: Duration::new(0, 0),
+            measurement_time: Duration::from_millis(100),
+            sample_count: 50,
+            outliers: OutlierPolicy::Winsorize(5.0),
+        }
+    }
+}
+
+/// What to do with samples far away from the others.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutlierPolicy {
+    /// Use all samples as they are.
+    Keep,
+    /// Clamp the given percentage of samples at either end; see
+    /// `stats::winsorize`.
+    Winsorize(f64),
+}
+
+impl OutlierPolicy {
+    fn apply(&self, samples: &mut [f64]) {
+        if let OutlierPolicy::Winsorize(pct) = *self {
+            stats::winsorize(samples, pct);
+        }
+    }
+}
+
+/// Settings given on the command line, which take precedence over whatever
+/// a benchmark configures itself.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BenchOverrides {
+    pub warm_up_time: Option<Duration>,
+    pub measurement_time: Option<Duration>,
+    pub sample_count: Option<usize>,
+    pub outliers: Option<OutlierPolicy>,
+}
+
+impl BenchOverrides {
+    fn apply(&self, config: BenchConfig) -> BenchConfig {
+        BenchConfig {
+            warm_up_time: self.warm_up_time.unwrap_or(config.warm_up_time),
+            measurement_time: self.measurement_time.unwrap_or(config.measurement_time),
+            sample_count: self.sample_count.unwrap_or(config.sample_count),
+            outliers: self.outliers.unwrap_or(config.outliers),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ShouldPanic {
     No,
@@ -234,6 +316,15 @@ pub struct TestDesc {
     pub ignore: bool,
     pub should_panic: ShouldPanic,
     pub allow_fail: bool,
+    // Hard time limit in seconds, overriding `--test-timeout` for this test.
+    pub timeout: Option<u64>,
+    // How often to re-run this test if it fails, overriding `--retries`.
+    // `DynTestFn` tests can only be run again with `--isolate=process`.
+    pub retries: Option<usize>,
+    // Run on the test's thread right before and after the test itself. The
+    // teardown runs even if the test panics, but not if the setup does.
+    pub setup: Option<fn()>,
+    pub teardown: Option<fn()>,
 }
 
 #[derive(Debug)]
@@ -258,12 +349,16 @@ impl Metric {
 #[derive(Copy, Clone, Debug)]
 pub struct Options {
     display_output: bool,
+    suite_setup: Option<fn()>,
+    suite_teardown: Option<fn()>,
 }
 
 impl Options {
     pub fn new() -> Options {
         Options {
             display_output: false,
+            suite_setup: None,
+            suite_teardown: None,
         }
     }
 
@@ -271,11 +366,37 @@ impl Options {
         self.display_output = display_output;
         self
     }
+
+    /// Run `setup` once before any test of the run. If it panics, no test is
+    /// run and all of them are reported as failed fixtures.
+    ///
+    /// With `--isolate=process`, it runs in the process of each test instead,
+    /// before the test, as that process does not share the state of the
+    /// runner. The runner itself only runs it before benchmarks.
+    pub fn suite_setup(mut self, setup: fn()) -> Options {
+        self.suite_setup = Some(setup);
+        self
+    }
+
+    /// Run `teardown` once after all tests of the run have finished.
+    ///
+    /// With `--isolate=process`, it runs in the process of each test instead,
+    /// after the test, and in the runner only after benchmarks.
+    pub fn suite_teardown(mut self, teardown: fn()) -> Options {
+        self.suite_teardown = Some(teardown);
+        self
+    }
 }
 
 // The default console test runner. It accepts the command line
 // arguments and a vector of test_descs.
 pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Options) {
+    if let Ok(name) = env::var(ISOLATED_TEST_ENV) {
+        // Test harnesses started by the test must run normally.
+        env::remove_var(ISOLATED_TEST_ENV);
+        run_isolated_test(&name, tests, options);
+    }
+
     let mut opts = match parse_opts(args) {
         Some(Ok(o)) => o,
         Some(Err(msg)) => {
@@ -286,6 +407,19 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Options) {
     };
 
     opts.options = options;
+    if opts.retries > 0 && opts.isolation == Isolation::Thread && !opts.list {
+        let dynamic = tests.iter().any(|t| match t.testfn {
+            DynTestFn(_) => true,
+            _ => false,
+        });
+        if dynamic {
+            eprintln!(
+                "error: --retries can not re-run dynamically created tests in the same \
+                 process; use it with --isolate=process"
+            );
+            process::exit(101);
+        }
+    }
     if opts.list {
         if let Err(e) = list_tests_console(&opts, tests) {
             eprintln!("error: io error when listing tests: {:?}", e);
@@ -311,6 +445,12 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Options) {
 // semantics into parallel test runners, which in turn requires a Vec<>
 // rather than a &[].
 pub fn test_main_static(tests: &[&TestDescAndFn]) {
+    test_main_static_with_options(tests, Options::new())
+}
+
+// Used by the test harness when the crate has `#![test_setup]` or
+// `#![test_teardown]` attributes.
+pub fn test_main_static_with_options(tests: &[&TestDescAndFn], options: Options) {
     let args = env::args().collect::<Vec<_>>();
     let owned_tests = tests
         .iter()
@@ -326,7 +466,7 @@ pub fn test_main_static(tests: &[&TestDescAndFn]) {
             _ => panic!("non-static tests passed to test::test_main_static"),
         })
         .collect();
-    test_main(&args, owned_tests, Options::new())
+    test_main(&args, owned_tests, options)
 }
 
 /// Invoked when unit tests terminate. Should panic if the unit
@@ -355,6 +495,7 @@ pub enum OutputFormat {
     Pretty,
     Terse,
     Json,
+    Junit,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -364,6 +505,13 @@ pub enum RunIgnored {
     Only,
 }
 
+/// Whether each test gets a thread or a whole process to itself.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Isolation {
+    Thread,
+    Process,
+}
+
 #[derive(Debug)]
 pub struct TestOpts {
     pub list: bool,
@@ -377,6 +525,21 @@ pub struct TestOpts {
     pub color: ColorConfig,
     pub format: OutputFormat,
     pub test_threads: Option<usize>,
+    pub test_timeout: Option<Duration>,
+    pub isolation: Isolation,
+    pub shuffle: bool,
+    pub shuffle_seed: Option<u64>,
+    pub save_baseline: Option<String>,
+    pub baseline: Option<String>,
+    pub baseline_threshold: f64,
+    pub bench_overrides: BenchOverrides,
+    pub shard_index: usize,
+    pub shard_count: usize,
+    pub retries: usize,
+    pub fail_flaky: bool,
+    pub report_time: bool,
+    pub warn_slow: Option<Duration>,
+    pub fail_slow: Option<Duration>,
     pub skip: Vec<String>,
     pub options: Options,
 }
@@ -396,6 +559,21 @@ impl TestOpts {
             color: AutoColor,
             format: OutputFormat::Pretty,
             test_threads: None,
+            test_timeout: None,
+            isolation: Isolation::Thread,
+            shuffle: false,
+            shuffle_seed: None,
+            save_baseline: None,
+            baseline: None,
+            baseline_threshold: DEFAULT_BASELINE_THRESHOLD_PCT,
+            bench_overrides: BenchOverrides::default(),
+            shard_index: 0,
+            shard_count: 1,
+            retries: 0,
+            fail_flaky: false,
+            report_time: false,
+            warn_slow: None,
+            fail_slow: None,
             skip: vec![],
             options: Options::new(),
         }
@@ -433,6 +611,124 @@ fn optgroups() -> getopts::Options {
              in parallel",
             "n_threads",
         )
+        .optopt(
+            "",
+            "test-timeout",
+            "Fail tests which run for longer than SECS seconds \
+             (can be overridden per test with #[test_timeout(SECS)])",
+            "SECS",
+        )
+        .optopt(
+            "",
+            "isolate",
+            "Configure how tests are isolated from each other:
+            thread  = run each test on its own thread (default);
+            process = run each test in its own process",
+            "thread|process",
+        )
+        .optflag(
+            "",
+            "shuffle",
+            "Run tests in random order",
+        )
+        .optopt(
+            "",
+            "shuffle-seed",
+            "Run tests in random order, shuffled with the given seed \
+             (the seed of a shuffled run is printed when it starts)",
+            "SEED",
+        )
+        .optopt(
+            "",
+            "save-baseline",
+            "Save the results of this benchmark run under NAME",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "baseline",
+            "Compare benchmark results against those saved under NAME",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "baseline-threshold",
+            "Fail benchmarks which got significantly slower than the \
+             baseline by more than PCT percent (default: 5)",
+            "PCT",
+        )
+        .optopt(
+            "",
+            "bench-warm-up",
+            "Run each benchmark for MS milliseconds before measuring it",
+            "MS",
+        )
+        .optopt(
+            "",
+            "bench-measurement-time",
+            "Keep sampling each benchmark for at least MS milliseconds",
+            "MS",
+        )
+        .optopt(
+            "",
+            "bench-samples",
+            "Take N samples per round of benchmark measurements",
+            "N",
+        )
+        .optopt(
+            "",
+            "bench-outliers",
+            "Configure how outlying benchmark samples are handled:
+            keep          = use all samples;
+            winsorize     = clamp the most extreme 5% at either end;
+            winsorize=PCT = clamp the most extreme PCT% at either end",
+            "keep|winsorize[=PCT]",
+        )
+        .optopt(
+            "",
+            "shard-index",
+            "Only run the N-th of the shards the tests are split into, \
+             counting from 0 (requires --shard-count)",
+            "N",
+        )
+        .optopt(
+            "",
+            "shard-count",
+            "Split the tests into M shards of similar size \
+             (requires --shard-index)",
+            "M",
+        )
+        .optopt(
+            "",
+            "retries",
+            "Re-run failing tests up to N times; tests which pass on a \
+             later attempt are reported as flaky (can be overridden per \
+             test with #[test_flaky(N)]). Dynamically created tests, \
+             like doctests, can only be re-run with --isolate=process",
+            "N",
+        )
+        .optflag(
+            "",
+            "fail-flaky",
+            "Treat tests which only passed after being retried as failures",
+        )
+        .optflag(
+            "",
+            "report-time",
+            "Show the execution time of each test (pretty format only)",
+        )
+        .optopt(
+            "",
+            "warn-slow",
+            "List tests which take longer than MS milliseconds at the end of the run",
+            "MS",
+        )
+        .optopt(
+            "",
+            "fail-slow",
+            "Fail tests which pass but take longer than MS milliseconds",
+            "MS",
+        )
         .optmulti(
             "",
             "skip",
@@ -466,8 +762,9 @@ fn optgroups() -> getopts::Options {
             "Configure formatting of output:
             pretty = Print verbose output;
             terse  = Display one character per test;
-            json   = Output a json document",
-            "pretty|terse|json",
+            json   = Output a json document
+            junit  = Output a JUnit XML document",
+            "pretty|terse|json|junit",
         )
         .optopt(
             "Z",
@@ -508,7 +805,19 @@ Test Attributes:
     #[ignore]      - When applied to a function which is already attributed as a
                      test, then the test runner will ignore these tests during
                      normal test runs. Running with --ignored or --include-ignored will run
-                     these tests."#,
+                     these tests.
+    #[test_timeout(SECS)] - This function (also labeled with #[test]) will fail
+                     if it does not finish within SECS seconds, regardless of
+                     the --test-timeout flag.
+    #[test_flaky(N)] - This function (also labeled with #[test]) is re-run up to
+                     N times (3 if omitted) when it fails, regardless of the
+                     --retries flag. Passing on a later attempt is reported as
+                     flaky.
+    #[test_setup(f)] - Run the function f before this test (also labeled with
+                     #[test]). If it panics, the test is reported as a failed
+                     fixture and not run.
+    #[test_teardown(f)] - Run the function f after this test (also labeled with
+                     #[test]), even if the test panicked."#,
         usage = options.usage(&message)
     );
 }
@@ -609,6 +918,199 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         None => None,
     };
 
+    let test_timeout = match matches.opt_str("test-timeout") {
+        Some(secs) => {
+            if !allow_unstable {
+                return Some(Err(
+                    "The \"test-timeout\" flag is only accepted on the nightly compiler".into(),
+                ));
+            }
+            match secs.parse::<u64>() {
+                Ok(0) => return Some(Err("argument for --test-timeout must not be 0".to_string())),
+                Ok(n) => Some(Duration::from_secs(n)),
+                Err(e) => {
+                    return Some(Err(format!(
+                        "argument for --test-timeout must be a number > 0 \
+                         (error: {})",
+                        e
+                    )))
+                }
+            }
+        }
+        None => None,
+    };
+
+    let isolation = match matches.opt_str("isolate").as_ref().map(|s| &**s) {
+        Some("thread") | None => Isolation::Thread,
+        Some("process") => {
+            if !allow_unstable {
+                return Some(Err(
+                    "The \"process\" isolation mode is only accepted on the nightly compiler"
+                        .into(),
+                ));
+            }
+            Isolation::Process
+        }
+
+        Some(v) => {
+            return Some(Err(format!(
+                "argument for --isolate must be thread or process (was \
+                 {})",
+                v
+            )))
+        }
+    };
+
+    let shuffle = matches.opt_present("shuffle");
+    let shuffle_seed = match matches.opt_str("shuffle-seed") {
+        Some(n_str) => match n_str.parse::<u64>() {
+            Ok(n) => Some(n),
+            Err(e) => {
+                return Some(Err(format!(
+                    "argument for --shuffle-seed must be a number \
+                     (error: {})",
+                    e
+                )))
+            }
+        },
+        None => None,
+    };
+    if !allow_unstable && (shuffle || shuffle_seed.is_some()) {
+        return Some(Err(
+            "The \"shuffle\" and \"shuffle-seed\" flags are only accepted on the nightly compiler"
+                .into(),
+        ));
+    }
+
+    let save_baseline = matches.opt_str("save-baseline");
+    let baseline = matches.opt_str("baseline");
+    let baseline_threshold = match matches.opt_str("baseline-threshold") {
+        Some(pct_str) => match pct_str.parse::<f64>() {
+            Ok(pct) if pct >= 0.0 => pct,
+            Ok(_) => {
+                return Some(Err("argument for --baseline-threshold must not be negative".into()))
+            }
+            Err(e) => {
+                return Some(Err(format!(
+                    "argument for --baseline-threshold must be a number \
+                     (error: {})",
+                    e
+                )))
+            }
+        },
+        None => DEFAULT_BASELINE_THRESHOLD_PCT,
+    };
+    if !allow_unstable
+        && (save_baseline.is_some() || baseline.is_some()
+            || matches.opt_present("baseline-threshold"))
+    {
+        return Some(Err(
+            "The \"save-baseline\", \"baseline\" and \"baseline-threshold\" flags are \
+             only accepted on the nightly compiler"
+                .into(),
+        ));
+    }
+
+    let bench_overrides = match parse_bench_overrides(&matches) {
+        Ok(overrides) => overrides,
+        Err(e) => return Some(Err(e)),
+    };
+    if !allow_unstable && bench_overrides != BenchOverrides::default() {
+        return Some(Err(
+            "The \"bench-warm-up\", \"bench-measurement-time\", \"bench-samples\" and \
+             \"bench-outliers\" flags are only accepted on the nightly compiler"
+                .into(),
+        ));
+    }
+
+    let (shard_index, shard_count) =
+        match (matches.opt_str("shard-index"), matches.opt_str("shard-count")) {
+            (None, None) => (0, 1),
+            (Some(index_str), Some(count_str)) => {
+                if !allow_unstable {
+                    return Some(Err(
+                        "The \"shard-index\" and \"shard-count\" flags are only accepted \
+                         on the nightly compiler"
+                            .into(),
+                    ));
+                }
+                let count = match count_str.parse::<usize>() {
+                    Ok(0) => {
+                        return Some(Err("argument for --shard-count must not be 0".to_string()))
+                    }
+                    Ok(n) => n,
+                    Err(e) => {
+                        return Some(Err(format!(
+                            "argument for --shard-count must be a number > 0 \
+                             (error: {})",
+                            e
+                        )))
+                    }
+                };
+                let index = match index_str.parse::<usize>() {
+                    Ok(n) if n < count => n,
+                    Ok(n) => {
+                        return Some(Err(format!(
+                            "argument for --shard-index must be less than --shard-count \
+                             (was {})",
+                            n
+                        )))
+                    }
+                    Err(e) => {
+                        return Some(Err(format!(
+                            "argument for --shard-index must be a number \
+                             (error: {})",
+                            e
+                        )))
+                    }
+                };
+                (index, count)
+            }
+            _ => {
+                return Some(Err(
+                    "the options --shard-index and --shard-count must be used together".into(),
+                ))
+            }
+        };
+
+    let retries = match matches.opt_str("retries") {
+        Some(n_str) => match n_str.parse::<usize>() {
+            Ok(n) => n,
+            Err(e) => {
+                return Some(Err(format!(
+                    "argument for --retries must be a number \
+                     (error: {})",
+                    e
+                )))
+            }
+        },
+        None => 0,
+    };
+    let fail_flaky = matches.opt_present("fail-flaky");
+    if !allow_unstable && (matches.opt_present("retries") || fail_flaky) {
+        return Some(Err(
+            "The \"retries\" and \"fail-flaky\" flags are only accepted on the nightly compiler"
+                .into(),
+        ));
+    }
+
+    let report_time = matches.opt_present("report-time");
+    let warn_slow = match parse_millis(&matches, "warn-slow") {
+        Ok(ms) => ms,
+        Err(e) => return Some(Err(e)),
+    };
+    let fail_slow = match parse_millis(&matches, "fail-slow") {
+        Ok(ms) => ms,
+        Err(e) => return Some(Err(e)),
+    };
+    if !allow_unstable && (report_time || warn_slow.is_some() || fail_slow.is_some()) {
+        return Some(Err(
+            "The \"report-time\", \"warn-slow\" and \"fail-slow\" flags are only accepted \
+             on the nightly compiler"
+                .into(),
+        ));
+    }
+
     let color = match matches.opt_str("color").as_ref().map(|s| &**s) {
         Some("auto") | None => AutoColor,
         Some("always") => AlwaysColor,
@@ -635,10 +1137,18 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
             }
             OutputFormat::Json
         }
+        Some("junit") => {
+            if !allow_unstable {
+                return Some(Err(
+                    "The \"junit\" format is only accepted on the nightly compiler".into(),
+                ));
+            }
+            OutputFormat::Junit
+        }
 
         Some(v) => {
             return Some(Err(format!(
-                "argument for --format must be pretty, terse, or json (was \
+                "argument for --format must be pretty, terse, json, or junit (was \
                  {})",
                 v
             )))
@@ -657,6 +1167,21 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         color,
         format,
         test_threads,
+        test_timeout,
+        isolation,
+        shuffle,
+        shuffle_seed,
+        save_baseline,
+        baseline,
+        baseline_threshold,
+        bench_overrides,
+        shard_index,
+        shard_count,
+        retries,
+        fail_flaky,
+        report_time,
+        warn_slow,
+        fail_slow,
         skip: matches.opt_strs("skip"),
         options: Options::new(),
     };
@@ -664,10 +1189,79 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
     Some(Ok(test_opts))
 }
 
+fn parse_bench_overrides(matches: &getopts::Matches) -> Result<BenchOverrides, String> {
+    let sample_count = match matches.opt_str("bench-samples") {
+        Some(n_str) => match n_str.parse::<usize>() {
+            Ok(n) if n >= 2 => Some(n),
+            Ok(_) => return Err("argument for --bench-samples must be at least 2".to_string()),
+            Err(e) => {
+                return Err(format!(
+                    "argument for --bench-samples must be a number (error: {})",
+                    e
+                ))
+            }
+        },
+        None => None,
+    };
+
+    let outliers = match matches.opt_str("bench-outliers").as_ref().map(|s| &**s) {
+        None => None,
+        Some("keep") => Some(OutlierPolicy::Keep),
+        Some("winsorize") => Some(OutlierPolicy::Winsorize(5.0)),
+        Some(v) => match v.trim_start_matches("winsorize=").parse::<f64>() {
+            Ok(pct) if v.starts_with("winsorize=") && pct >= 0.0 && pct < 50.0 => {
+                Some(OutlierPolicy::Winsorize(pct))
+            }
+            _ => {
+                return Err(format!(
+                    "argument for --bench-outliers must be keep, winsorize or \
+                     winsorize=PCT with 0 <= PCT < 50 (was {})",
+                    v
+                ))
+            }
+        },
+    };
+
+    Ok(BenchOverrides {
+        warm_up_time: parse_millis(matches, "bench-warm-up")?,
+        measurement_time: parse_millis(matches, "bench-measurement-time")?,
+        sample_count,
+        outliers,
+    })
+}
+
+fn parse_millis(matches: &getopts::Matches, flag: &str) -> Result<Option<Duration>, String> {
+    match matches.opt_str(flag) {
+        Some(ms) => match ms.parse::<u64>() {
+            Ok(ms) => Ok(Some(Duration::from_millis(ms))),
+            Err(e) => Err(format!(
+                "argument for --{} must be a number of milliseconds (error: {})",
+                flag, e
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+/// How long a test took to run, measured around the test function itself.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestExecTime(pub Duration);
+
+impl fmt::Display for TestExecTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.3}s", ns_from_dur(self.0) as f64 / 1_000_000_000.0)
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct BenchSamples {
     ns_iter_summ: stats::Summary,
     mb_s: usize,
+    elems_s: usize,
+    // Number of samples `ns_iter_summ` was computed from, 0 if the benchmark
+    // never called `Bencher::iter`.
+    samples: usize,
+    baseline: Option<BaselineComparison>,
 }
 
 #[derive(Clone, PartialEq)]
@@ -677,6 +1271,11 @@ pub enum TestResult {
     TrFailedMsg(String),
     TrIgnored,
     TrAllowedFail,
+    TrTimedOut,
+    // Passed, but only after failing; holds the number of attempts made.
+    TrFlaky(usize),
+    // A setup or teardown hook panicked; the test itself did not fail.
+    TrFixtureFailed(String),
     TrBench(BenchSamples),
 }
 
@@ -707,6 +1306,7 @@ struct ConsoleTestState {
     log_out: Option<File>,
     total: usize,
     passed: usize,
+    flaky: usize,
     failed: usize,
     ignored: usize,
     allowed_fail: usize,
@@ -715,6 +1315,13 @@ struct ConsoleTestState {
     metrics: MetricMap,
     failures: Vec<(TestDesc, Vec<u8>)>,
     not_failures: Vec<(TestDesc, Vec<u8>)>,
+    baseline: Option<Baseline>,
+    baseline_threshold: f64,
+    bench_results: Baseline,
+    fail_flaky: bool,
+    warn_slow: Option<Duration>,
+    slow: Vec<(TestDesc, TestExecTime)>,
+    suite_teardown_failure: Option<String>,
     options: Options,
 }
 
@@ -729,6 +1336,7 @@ impl ConsoleTestState {
             log_out,
             total: 0,
             passed: 0,
+            flaky: 0,
             failed: 0,
             ignored: 0,
             allowed_fail: 0,
@@ -737,6 +1345,13 @@ impl ConsoleTestState {
             metrics: MetricMap::new(),
             failures: Vec::new(),
             not_failures: Vec::new(),
+            baseline: None,
+            baseline_threshold: opts.baseline_threshold,
+            bench_results: Baseline::new(),
+            fail_flaky: opts.fail_flaky,
+            warn_slow: opts.warn_slow,
+            slow: Vec::new(),
+            suite_teardown_failure: None,
             options: opts.options,
         })
     }
@@ -758,12 +1373,34 @@ impl ConsoleTestState {
                 TrFailedMsg(ref msg) => format!("failed: {}", msg),
                 TrIgnored => "ignored".to_owned(),
                 TrAllowedFail => "failed (allowed)".to_owned(),
+                TrTimedOut => "timed out".to_owned(),
+                TrFlaky(attempts) => format!("ok (flaky, {} attempts)", attempts),
+                TrFixtureFailed(ref msg) => format!("fixture failed: {}", msg),
                 TrBench(ref bs) => fmt_bench_samples(bs),
             },
             test.name
         ))
     }
 
+    // Records the result of a benchmark for `--save-baseline` and fills in
+    // how it compares to the `--baseline`, if there is one.
+    fn compare_with_baseline(&mut self, test: &TestDesc, result: TestResult) -> TestResult {
+        match result {
+            TrBench(mut bs) => {
+                if bs.samples > 0 {
+                    let entry = BaselineEntry::new(&bs.ns_iter_summ, bs.samples);
+                    let name = test.name.as_slice();
+                    if let Some(ref baseline) = self.baseline {
+                        bs.baseline = baseline.compare(name, &entry, self.baseline_threshold);
+                    }
+                    self.bench_results.insert(name, entry);
+                }
+                TrBench(bs)
+            }
+            result => result,
+        }
+    }
+
     fn current_test_count(&self) -> usize {
         self.passed + self.failed + self.ignored + self.measured + self.allowed_fail
     }
@@ -812,6 +1449,27 @@ pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
             .write_fmt(format_args!(" = {} MB/s", bs.mb_s))
             .unwrap();
     }
+    if bs.elems_s != 0 {
+        output
+            .write_fmt(format_args!(" = {} elem/s", fmt_thousands_sep(bs.elems_s, ',')))
+            .unwrap();
+    }
+    if let Some(ref cmp) = bs.baseline {
+        output
+            .write_fmt(format_args!(
+                " (mean change: {:+.2}%, p = {:.3}{})",
+                cmp.change_pct,
+                cmp.p_value,
+                if cmp.regression {
+                    ", regression"
+                } else if cmp.is_significant() {
+                    ""
+                } else {
+                    ", within noise"
+                }
+            ))
+            .unwrap();
+    }
     output
 }
 
@@ -882,21 +1540,43 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
         out: &mut dyn OutputFormatter,
     ) -> io::Result<()> {
         match (*event).clone() {
-            TeFiltered(ref filtered_tests) => {
+            TeFiltered(ref filtered_tests, shuffle_seed) => {
                 st.total = filtered_tests.len();
-                out.write_run_start(filtered_tests.len())
+                out.write_run_start(filtered_tests.len(), shuffle_seed)
             }
             TeFilteredOut(filtered_out) => Ok(st.filtered_out = filtered_out),
             TeWait(ref test) => out.write_test_start(test),
             TeTimeout(ref test) => out.write_timeout(test),
-            TeResult(test, result, stdout) => {
+            TeSuiteTeardownFailed(msg) => Ok(st.suite_teardown_failure = Some(msg)),
+            TeResult(test, result, exec_time, stdout) => {
+                let result = st.compare_with_baseline(&test, result);
                 st.write_log_result(&test, &result)?;
-                out.write_result(&test, &result, &*stdout)?;
+                out.write_result(&test, &result, exec_time.as_ref(), &*stdout)?;
+                if let (Some(limit), Some(time)) = (st.warn_slow, exec_time) {
+                    if time.0 > limit {
+                        st.slow.push((test.clone(), time));
+                    }
+                }
                 match result {
                     TrOk => {
                         st.passed += 1;
                         st.not_failures.push((test, stdout));
                     }
+                    TrFlaky(attempts) => {
+                        if st.fail_flaky {
+                            st.failed += 1;
+                            let mut stdout = stdout;
+                            stdout.extend_from_slice(
+                                format!("note: test only passed after {} attempts", attempts)
+                                    .as_bytes(),
+                            );
+                            st.failures.push((test, stdout));
+                        } else {
+                            st.passed += 1;
+                            st.flaky += 1;
+                            st.not_failures.push((test, stdout));
+                        }
+                    }
                     TrIgnored => st.ignored += 1,
                     TrAllowedFail => st.allowed_fail += 1,
                     TrBench(bs) => {
@@ -905,7 +1585,21 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
                             bs.ns_iter_summ.median,
                             bs.ns_iter_summ.max - bs.ns_iter_summ.min,
                         );
-                        st.measured += 1
+                        match bs.baseline {
+                            Some(ref cmp) if cmp.regression => {
+                                st.failed += 1;
+                                let mut stdout = stdout;
+                                stdout.extend_from_slice(
+                                    format!(
+                                        "note: benchmark is {:.2}% slower on average than the \
+                                         baseline (p = {:.3}), more than the {}% allowed",
+                                        cmp.change_pct, cmp.p_value, st.baseline_threshold
+                                    ).as_bytes(),
+                                );
+                                st.failures.push((test, stdout));
+                            }
+                            _ => st.measured += 1,
+                        }
                     }
                     TrFailed => {
                         st.failed += 1;
@@ -917,6 +1611,20 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
                         stdout.extend_from_slice(format!("note: {}", msg).as_bytes());
                         st.failures.push((test, stdout));
                     }
+                    TrTimedOut => {
+                        st.failed += 1;
+                        let mut stdout = stdout;
+                        stdout.extend_from_slice(
+                            b"note: test did not finish within its time limit",
+                        );
+                        st.failures.push((test, stdout));
+                    }
+                    TrFixtureFailed(msg) => {
+                        st.failed += 1;
+                        let mut stdout = stdout;
+                        stdout.extend_from_slice(format!("note: {}", msg).as_bytes());
+                        st.failures.push((test, stdout));
+                    }
                 }
                 Ok(())
             }
@@ -942,6 +1650,7 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
             use_color(opts),
             max_name_len,
             is_multithreaded,
+            opts.report_time,
         )),
         OutputFormat::Terse => Box::new(TerseFormatter::new(
             output,
@@ -950,8 +1659,12 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
             is_multithreaded,
         )),
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
+        OutputFormat::Junit => Box::new(JunitFormatter::new(output)),
     };
     let mut st = ConsoleTestState::new(opts)?;
+    if let Some(ref name) = opts.baseline {
+        st.baseline = Some(Baseline::load(name)?);
+    }
     fn len_if_padded(t: &TestDescAndFn) -> usize {
         match t.testfn.padding() {
             PadNone => 0,
@@ -963,6 +1676,16 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
 
     assert!(st.current_test_count() == st.total);
 
+    if let Some(ref name) = opts.save_baseline {
+        // Saving the results of a run without benchmarks, for example because
+        // of a filter, would replace a good baseline with an empty one.
+        if st.bench_results.is_empty() {
+            eprintln!("note: no benchmarks were run, not saving baseline `{}`", name);
+        } else {
+            st.bench_results.save(name)?;
+        }
+    }
+
     return out.write_run_finish(&st);
 }
 
@@ -973,6 +1696,10 @@ fn should_sort_failures_before_printing_them() {
         ignore: false,
         should_panic: ShouldPanic::No,
         allow_fail: false,
+        timeout: None,
+        retries: None,
+        setup: None,
+        teardown: None,
     };
 
     let test_b = TestDesc {
@@ -980,14 +1707,19 @@ fn should_sort_failures_before_printing_them() {
         ignore: false,
         should_panic: ShouldPanic::No,
         allow_fail: false,
+        timeout: None,
+        retries: None,
+        setup: None,
+        teardown: None,
     };
 
-    let mut out = PrettyFormatter::new(Raw(Vec::new()), false, 10, false);
+    let mut out = PrettyFormatter::new(Raw(Vec::new()), false, 10, false, false);
 
     let st = ConsoleTestState {
         log_out: None,
         total: 0,
         passed: 0,
+        flaky: 0,
         failed: 0,
         ignored: 0,
         allowed_fail: 0,
@@ -995,6 +1727,13 @@ fn should_sort_failures_before_printing_them() {
         measured: 0,
         metrics: MetricMap::new(),
         failures: vec![(test_b, Vec::new()), (test_a, Vec::new())],
+        baseline: None,
+        baseline_threshold: 0.0,
+        bench_results: Baseline::new(),
+        fail_flaky: false,
+        warn_slow: None,
+        slow: Vec::new(),
+        suite_teardown_failure: None,
         options: Options::new(),
         not_failures: Vec::new(),
     };
@@ -1010,16 +1749,71 @@ fn should_sort_failures_before_printing_them() {
     assert!(apos < bpos);
 }
 
-fn use_color(opts: &TestOpts) -> bool {
-    match opts.color {
-        AutoColor => !opts.nocapture && stdout_isatty(),
-        AlwaysColor => true,
-        NeverColor => false,
-    }
-}
-
-#[cfg(any(target_os = "cloudabi", target_os = "redox",
-          all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+#[test]
+fn junit_report_contains_escaped_testcases() {
+    let desc = |name| TestDesc {
+        name: StaticTestName(name),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        allow_fail: false,
+        timeout: None,
+        retries: None,
+        setup: None,
+        teardown: None,
+    };
+
+    let mut out = JunitFormatter::new(Raw(Vec::new()));
+    let time = TestExecTime(Duration::from_millis(1500));
+    out.write_result(&desc("m::passes"), &TrOk, Some(&time), &[]).unwrap();
+    out.write_result(&desc("m::fails"), &TrFailed, None, b"x < y & z").unwrap();
+    out.write_result(&desc("skipped"), &TrIgnored, None, &[]).unwrap();
+
+    let st = ConsoleTestState {
+        log_out: None,
+        total: 3,
+        passed: 1,
+        flaky: 0,
+        failed: 1,
+        ignored: 1,
+        allowed_fail: 0,
+        filtered_out: 0,
+        measured: 0,
+        metrics: MetricMap::new(),
+        failures: Vec::new(),
+        baseline: None,
+        baseline_threshold: 0.0,
+        bench_results: Baseline::new(),
+        fail_flaky: false,
+        warn_slow: None,
+        slow: Vec::new(),
+        suite_teardown_failure: None,
+        options: Options::new(),
+        not_failures: Vec::new(),
+    };
+
+    assert!(!out.write_run_finish(&st).unwrap());
+    let s = match out.output_location() {
+        &Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        &Pretty(_) => unreachable!(),
+    };
+
+    assert!(s.contains(r#"failures="1" tests="3" skipped="1""#));
+    assert!(s.contains(r#"<testcase classname="m" name="passes" time="1.500""#));
+    assert!(s.contains("<system-out>x &lt; y &amp; z</system-out>"));
+    assert!(s.contains(r#"<testcase classname="crate" name="skipped" time="0.000""#));
+    assert!(s.contains("<skipped/>"));
+}
+
+fn use_color(opts: &TestOpts) -> bool {
+    match opts.color {
+        AutoColor => !opts.nocapture && stdout_isatty(),
+        AlwaysColor => true,
+        NeverColor => false,
+    }
+}
+
+#[cfg(any(target_os = "cloudabi", target_os = "redox",
+          all(target_arch = "wasm32", not(target_os = "emscripten"))))]
 fn stdout_isatty() -> bool {
     // FIXME: Implement isatty on Redox
     false
@@ -1048,14 +1842,15 @@ fn stdout_isatty() -> bool {
 
 #[derive(Clone)]
 pub enum TestEvent {
-    TeFiltered(Vec<TestDesc>),
+    TeFiltered(Vec<TestDesc>, Option<u64>),
     TeWait(TestDesc),
-    TeResult(TestDesc, TestResult, Vec<u8>),
+    TeResult(TestDesc, TestResult, Option<TestExecTime>, Vec<u8>),
     TeTimeout(TestDesc),
     TeFilteredOut(usize),
+    TeSuiteTeardownFailed(String),
 }
 
-pub type MonitorMsg = (TestDesc, TestResult, Vec<u8>);
+pub type MonitorMsg = (TestDesc, TestResult, Option<TestExecTime>, Vec<u8>);
 
 struct Sink(Arc<Mutex<Vec<u8>>>);
 impl Write for Sink {
@@ -1081,6 +1876,11 @@ where
         filtered_tests = convert_benchmarks_to_tests(filtered_tests);
     }
 
+    let shuffle_seed = get_shuffle_seed(opts);
+    if let Some(seed) = shuffle_seed {
+        shuffle_tests(seed, &mut filtered_tests);
+    }
+
     let filtered_tests = {
         let mut filtered_tests = filtered_tests;
         for test in filtered_tests.iter_mut() {
@@ -1095,7 +1895,29 @@ where
 
     let filtered_descs = filtered_tests.iter().map(|t| t.desc.clone()).collect();
 
-    callback(TeFiltered(filtered_descs))?;
+    callback(TeFiltered(filtered_descs, shuffle_seed))?;
+
+    // With `--isolate=process`, the process of each test runs the suite
+    // fixtures around it, so they only run here for benchmarks, which are
+    // never isolated.
+    let fixtures_here = opts.isolation == Isolation::Thread
+        || filtered_tests.iter().any(|t| match t.testfn {
+            StaticBenchFn(_) | DynBenchFn(_) => true,
+            _ => false,
+        });
+    let suite_setup = opts.options.suite_setup.filter(|_| fixtures_here);
+    let suite_teardown = opts.options.suite_teardown.filter(|_| fixtures_here);
+
+    if let Some(setup) = suite_setup {
+        if let Err(e) = catch_unwind(setup) {
+            let msg = format!("suite setup failed: {}", panic_message(&*e));
+            for test in filtered_tests {
+                callback(TeWait(test.desc.clone()))?;
+                callback(TeResult(test.desc, TrFixtureFailed(msg.clone()), None, Vec::new()))?;
+            }
+            return Ok(());
+        }
+    }
 
     let (filtered_tests, filtered_benchs): (Vec<_>, _) =
         filtered_tests.into_iter().partition(|e| match e.testfn {
@@ -1105,98 +1927,224 @@ where
 
     let concurrency = opts.test_threads.unwrap_or_else(get_concurrency);
 
+    // Only static tests can be run a second time in this process, so those
+    // are the ones which can be retried when they fail. Dynamic tests are
+    // consumed by their first run, unless every attempt runs in a process of
+    // its own, which builds the test again.
+    let rerunnable: HashMap<TestDesc, Option<fn()>> = filtered_tests
+        .iter()
+        .filter_map(|t| match t.testfn {
+            StaticTestFn(f) => Some((t.desc.clone(), Some(f))),
+            DynTestFn(_) if opts.isolation == Isolation::Process => Some((t.desc.clone(), None)),
+            _ => None,
+        })
+        .collect();
+    let mut retried: HashMap<TestDesc, usize> = HashMap::new();
+    // The output of the failed attempts of retried tests, reported along with
+    // the output of their last attempt.
+    let mut failed_attempts: HashMap<TestDesc, Vec<u8>> = HashMap::new();
+
     let mut remaining = filtered_tests;
     remaining.reverse();
     let mut pending = 0;
 
     let (tx, rx) = channel::<MonitorMsg>();
 
-    let mut running_tests: HashMap<TestDesc, Instant> = HashMap::new();
+    // When a test should be warned about for running too long, and when it
+    // should be given up on and reported as timed out.
+    struct Deadlines {
+        warn: Option<Instant>,
+        kill: Option<Instant>,
+    }
+
+    let mut running_tests: HashMap<TestDesc, Deadlines> = HashMap::new();
 
-    fn get_timed_out_tests(running_tests: &mut HashMap<TestDesc, Instant>) -> Vec<TestDesc> {
+    fn get_timed_out_tests(running_tests: &mut HashMap<TestDesc, Deadlines>) -> Vec<TestDesc> {
         let now = Instant::now();
-        let timed_out = running_tests
+        let mut timed_out = Vec::new();
+        for (desc, deadlines) in running_tests.iter_mut() {
+            if deadlines.warn.map_or(false, |warn| now >= warn) {
+                deadlines.warn = None;
+                timed_out.push(desc.clone());
+            }
+        }
+        timed_out
+    };
+
+    fn get_hung_tests(running_tests: &mut HashMap<TestDesc, Deadlines>) -> Vec<TestDesc> {
+        let now = Instant::now();
+        let hung: Vec<_> = running_tests
             .iter()
-            .filter_map(|(desc, timeout)| {
-                if &now >= timeout {
+            .filter_map(|(desc, deadlines)| {
+                if deadlines.kill.map_or(false, |kill| now >= kill) {
                     Some(desc.clone())
                 } else {
                     None
                 }
             })
             .collect();
-        for test in &timed_out {
+        for test in &hung {
             running_tests.remove(test);
         }
-        timed_out
+        hung
     };
 
-    fn calc_timeout(running_tests: &HashMap<TestDesc, Instant>) -> Option<Duration> {
-        running_tests.values().min().map(|next_timeout| {
-            let now = Instant::now();
-            if *next_timeout >= now {
-                *next_timeout - now
-            } else {
-                Duration::new(0, 0)
-            }
-        })
+    fn calc_timeout(running_tests: &HashMap<TestDesc, Deadlines>) -> Option<Duration> {
+        running_tests
+            .values()
+            .flat_map(|deadlines| deadlines.warn.into_iter().chain(deadlines.kill))
+            .min()
+            .map(|next_timeout| {
+                let now = Instant::now();
+                if next_timeout >= now {
+                    next_timeout - now
+                } else {
+                    Duration::new(0, 0)
+                }
+            })
     };
 
-    if concurrency == 1 {
-        while !remaining.is_empty() {
+    while pending > 0 || !remaining.is_empty() {
+        while pending < concurrency && !remaining.is_empty() {
             let test = remaining.pop().unwrap();
-            callback(TeWait(test.desc.clone()))?;
-            run_test(opts, !opts.run_tests, test, tx.clone());
-            let (test, result, stdout) = rx.recv().unwrap();
-            callback(TeResult(test, result, stdout))?;
-        }
-    } else {
-        while pending > 0 || !remaining.is_empty() {
-            while pending < concurrency && !remaining.is_empty() {
-                let test = remaining.pop().unwrap();
-                let timeout = Instant::now() + Duration::from_secs(TEST_WARN_TIMEOUT_S);
-                running_tests.insert(test.desc.clone(), timeout);
+            let now = Instant::now();
+            // When running serially the name of the test has already been
+            // printed, so there is no need to warn about slow tests.
+            let warn = if concurrency > 1 {
+                Some(now + Duration::from_secs(TEST_WARN_TIMEOUT_S))
+            } else {
+                None
+            };
+            let kill = hard_timeout(opts, &test.desc).map(|timeout| now + timeout);
+            running_tests.insert(test.desc.clone(), Deadlines { warn, kill });
+            // Retries are silent; the test was already announced.
+            if !retried.contains_key(&test.desc) {
                 callback(TeWait(test.desc.clone()))?; //here no pad
-                run_test(opts, !opts.run_tests, test, tx.clone());
-                pending += 1;
             }
+            run_test(opts, !opts.run_tests, test, tx.clone());
+            pending += 1;
+        }
 
-            let mut res;
-            loop {
-                if let Some(timeout) = calc_timeout(&running_tests) {
-                    res = rx.recv_timeout(timeout);
-                    for test in get_timed_out_tests(&mut running_tests) {
-                        callback(TeTimeout(test))?;
+        let res = match calc_timeout(&running_tests) {
+            Some(timeout) => rx.recv_timeout(timeout),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        for test in get_timed_out_tests(&mut running_tests) {
+            callback(TeTimeout(test))?;
+        }
+
+        // Threads can't be killed, so a hung test is abandoned: its slot is
+        // freed and whatever it reports later is ignored.
+        for test in get_hung_tests(&mut running_tests) {
+            callback(TeResult(test, TrTimedOut, None, Vec::new()))?;
+            pending -= 1;
+        }
+
+        match res {
+            Ok((desc, result, exec_time, mut stdout)) => {
+                if running_tests.remove(&desc).is_some() {
+                    pending -= 1;
+                    // Tests which timed out are not retried, as their
+                    // abandoned attempt could still report in.
+                    let attempts = retried.get(&desc).cloned().unwrap_or(0);
+                    let failed = match result {
+                        TrFailed | TrFailedMsg(_) => true,
+                        _ => false,
+                    };
+                    let max_retries = desc.retries.unwrap_or(opts.retries);
+                    if failed && attempts < max_retries {
+                        if let Some(&testfn) = rerunnable.get(&desc) {
+                            let output = failed_attempts.entry(desc.clone()).or_default();
+                            output.extend_from_slice(
+                                format!("---- attempt {} ----\n", attempts + 1).as_bytes(),
+                            );
+                            output.extend_from_slice(&stdout);
+                            if let TrFailedMsg(ref msg) = result {
+                                output.extend_from_slice(format!("note: {}\n", msg).as_bytes());
+                            }
+                            retried.insert(desc.clone(), attempts + 1);
+                            let testfn = match testfn {
+                                Some(f) => StaticTestFn(f),
+                                // Only the test process runs the test.
+                                None => DynTestFn(Box::new(|| unreachable!())),
+                            };
+                            remaining.push(TestDescAndFn { testfn, desc });
+                            continue;
+                        }
+                        stdout.extend_from_slice(
+                            b"note: test was not retried, as dynamically created tests \
+                              can only be re-run with --isolate=process\n",
+                        );
                     }
-                    if res != Err(RecvTimeoutError::Timeout) {
-                        break;
+                    if let Some(mut output) = failed_attempts.remove(&desc) {
+                        output.extend_from_slice(
+                            format!("---- attempt {} ----\n", attempts + 1).as_bytes(),
+                        );
+                        output.extend_from_slice(&stdout);
+                        stdout = output;
                     }
-                } else {
-                    res = rx.recv().map_err(|_| RecvTimeoutError::Disconnected);
-                    break;
+                    // Going over the budget is not retried, as a slow test
+                    // would only be reported as flaky.
+                    let result = check_time_budget(opts, result, &exec_time);
+                    let result = match result {
+                        TrOk if attempts > 0 => TrFlaky(attempts + 1),
+                        result => result,
+                    };
+                    callback(TeResult(desc, result, exec_time, stdout))?;
                 }
             }
-
-            let (desc, result, stdout) = res.unwrap();
-            running_tests.remove(&desc);
-
-            callback(TeResult(desc, result, stdout))?;
-            pending -= 1;
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => panic!("test monitor channel disconnected"),
         }
     }
 
     if opts.bench_benchmarks {
         // All benchmarks run at the end, in serial.
         for b in filtered_benchs {
-            callback(TeWait(b.desc.clone()))?;
+            let desc = b.desc.clone();
+            callback(TeWait(desc.clone()))?;
             run_test(opts, false, b, tx.clone());
-            let (test, result, stdout) = rx.recv().unwrap();
-            callback(TeResult(test, result, stdout))?;
+            // Tests abandoned after timing out may still report in here.
+            let (test, result, exec_time, stdout) = loop {
+                let msg = rx.recv().unwrap();
+                if msg.0 == desc {
+                    break msg;
+                }
+            };
+            callback(TeResult(test, result, exec_time, stdout))?;
+        }
+    }
+
+    if let Some(teardown) = suite_teardown {
+        if let Err(e) = catch_unwind(teardown) {
+            callback(TeSuiteTeardownFailed(panic_message(&*e).to_owned()))?;
         }
     }
     Ok(())
 }
 
+// Tests which pass but go over the `--fail-slow` budget fail instead.
+fn check_time_budget(
+    opts: &TestOpts,
+    result: TestResult,
+    exec_time: &Option<TestExecTime>,
+) -> TestResult {
+    match (opts.fail_slow, exec_time) {
+        (Some(limit), &Some(ref time)) if result == TrOk && time.0 > limit => TrFailedMsg(format!(
+            "test took {}, more than the {} allowed by --fail-slow",
+            time,
+            TestExecTime(limit)
+        )),
+        _ => result,
+    }
+}
+
+// Per-test `#[test_timeout]` attributes take precedence over `--test-timeout`.
+fn hard_timeout(opts: &TestOpts, desc: &TestDesc) -> Option<Duration> {
+    desc.timeout.map(Duration::from_secs).or(opts.test_timeout)
+}
+
 #[allow(deprecated)]
 fn get_concurrency() -> usize {
     return match env::var("RUST_TEST_THREADS") {
@@ -1361,9 +2309,60 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
     // Sort the tests alphabetically
     filtered.sort_by(|t1, t2| t1.desc.name.as_slice().cmp(t2.desc.name.as_slice()));
 
+    // Keep only this shard's tests. Dealing them out round-robin from the
+    // sorted list keeps the partition deterministic and spreads the tests of
+    // each module across all shards.
+    if opts.shard_count > 1 {
+        filtered = filtered
+            .into_iter()
+            .enumerate()
+            .filter(|&(i, _)| i % opts.shard_count == opts.shard_index)
+            .map(|(_, test)| test)
+            .collect();
+    }
+
     filtered
 }
 
+// An explicit seed implies `--shuffle`; without one a fresh seed is picked
+// for every run.
+fn get_shuffle_seed(opts: &TestOpts) -> Option<u64> {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    if opts.shuffle_seed.is_some() || !opts.shuffle {
+        return opts.shuffle_seed;
+    }
+
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(since_epoch) = SystemTime::now().duration_since(UNIX_EPOCH) {
+        hasher.write_u64(ns_from_dur(since_epoch));
+    }
+    Some(hasher.finish())
+}
+
+/// Shuffles `tests` in place. The order only depends on `seed` and on the
+/// incoming order, which `filter_tests` has already sorted by name, so a run
+/// can be replayed exactly by passing the same `--shuffle-seed`.
+fn shuffle_tests(seed: u64, tests: &mut [TestDescAndFn]) {
+    // SplitMix64, which is plenty for picking a test order.
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+
+    // Fisher-Yates
+    for i in (1..tests.len()).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        tests.swap(i, j);
+    }
+}
+
 pub fn convert_benchmarks_to_tests(tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
     // convert benchmarks to tests, if we're not benchmarking them
     tests
@@ -1398,7 +2397,7 @@ pub fn run_test(
         && desc.should_panic != ShouldPanic::No;
 
     if force_ignore || desc.ignore || ignore_because_panic_abort {
-        monitor_ch.send((desc, TrIgnored, Vec::new())).unwrap();
+        monitor_ch.send((desc, TrIgnored, None, Vec::new())).unwrap();
         return;
     }
 
@@ -1423,18 +2422,19 @@ pub fn run_test(
                 None
             };
 
-            let result = catch_unwind(AssertUnwindSafe(testfn));
+            let start = Instant::now();
+            let test_result = run_with_fixtures(&desc, testfn);
+            let exec_time = TestExecTime(start.elapsed());
 
             if let Some((printio, panicio)) = oldio {
                 io::set_print(printio);
                 io::set_panic(panicio);
             };
 
-            let test_result = calc_result(&desc, result);
             let stdout = data.lock().unwrap().to_vec();
-            monitor_ch
-                .send((desc.clone(), test_result, stdout))
-                .unwrap();
+            // Nobody is listening any more if this test was abandoned after
+            // exceeding its time limit.
+            let _ = monitor_ch.send((desc.clone(), test_result, Some(exec_time), stdout));
         };
 
         // If the platform is single-threaded we're just going to run
@@ -1451,15 +2451,18 @@ pub fn run_test(
 
     match testfn {
         DynBenchFn(bencher) => {
-            ::bench::benchmark(desc, monitor_ch, opts.nocapture, |harness| {
+            ::bench::benchmark(desc, monitor_ch, opts.nocapture, opts.bench_overrides, |harness| {
                 bencher.run(harness)
             });
         }
         StaticBenchFn(benchfn) => {
-            ::bench::benchmark(desc, monitor_ch, opts.nocapture, |harness| {
+            ::bench::benchmark(desc, monitor_ch, opts.nocapture, opts.bench_overrides, |harness| {
                 (benchfn.clone())(harness)
             });
         }
+        DynTestFn(_) | StaticTestFn(_) if opts.isolation == Isolation::Process => {
+            run_test_in_subprocess(opts, desc, monitor_ch)
+        }
         DynTestFn(f) => {
             let cb = move || __rust_begin_short_backtrace(f);
             run_test_inner(desc, monitor_ch, opts.nocapture, Box::new(cb))
@@ -1473,6 +2476,206 @@ pub fn run_test(
     }
 }
 
+/// Runs a test by re-executing the current binary with `ISOLATED_TEST_ENV` set,
+/// so that aborts, crashes or corrupted global state only affect that test.
+fn run_test_in_subprocess(opts: &TestOpts, desc: TestDesc, monitor_ch: Sender<MonitorMsg>) {
+    let nocapture = opts.nocapture;
+    let timeout = hard_timeout(opts, &desc);
+    let name = desc.name.clone();
+
+    let runtest = move || {
+        let start = Instant::now();
+        let (result, output) = match spawn_isolated_test(&desc, nocapture, timeout) {
+            Ok(res) => res,
+            Err(e) => (
+                TrFailedMsg(format!("could not run test in a separate process: {}", e)),
+                Vec::new(),
+            ),
+        };
+        // Nobody is listening any more if this test was abandoned after
+        // exceeding its time limit.
+        let exec_time = TestExecTime(start.elapsed());
+        let _ = monitor_ch.send((desc, result, Some(exec_time), output));
+    };
+
+    let cfg = thread::Builder::new().name(name.as_slice().to_owned());
+    cfg.spawn(runtest).unwrap();
+}
+
+fn spawn_isolated_test(
+    desc: &TestDesc,
+    nocapture: bool,
+    timeout: Option<Duration>,
+) -> io::Result<(TestResult, Vec<u8>)> {
+    fn read_on_thread<R: Read + Send + 'static>(mut r: R) -> thread::JoinHandle<Vec<u8>> {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = r.read_to_end(&mut buf);
+            buf
+        })
+    }
+
+    // Pass our own arguments along so that custom harnesses (e.g. rustdoc)
+    // build the same list of tests in the child.
+    let mut command = Command::new(env::current_exe()?);
+    command
+        .args(env::args_os().skip(1))
+        .env(ISOLATED_TEST_ENV, desc.name.as_slice());
+    if !nocapture {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+    let mut child = command.spawn()?;
+
+    // Drain the pipes while the test runs so that it can't block on a full one.
+    let stdout = child.stdout.take().map(read_on_thread);
+    let stderr = child.stderr.take().map(read_on_thread);
+
+    let status = match timeout {
+        None => Some(child.wait()?),
+        Some(timeout) => {
+            let deadline = Instant::now() + timeout;
+            loop {
+                if let Some(status) = child.try_wait()? {
+                    break Some(status);
+                }
+                if Instant::now() >= deadline {
+                    child.kill()?;
+                    child.wait()?;
+                    break None;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+        }
+    };
+
+    let mut output = Vec::new();
+    for handle in stdout.into_iter().chain(stderr) {
+        output.extend(handle.join().unwrap_or_default());
+    }
+
+    let result = match status {
+        None => TrTimedOut,
+        Some(status) => match status.code() {
+            Some(TR_OK) => TrOk,
+            _ if desc.allow_fail => TrAllowedFail,
+            Some(TR_FAILED) => TrFailed,
+            Some(TR_FIXTURE_FAILED) => {
+                TrFixtureFailed("setup or teardown of the test process failed".to_owned())
+            }
+            Some(code) => TrFailedMsg(format!(
+                "test process exited unexpectedly with status code {}",
+                code
+            )),
+            None => TrFailedMsg(fmt_abnormal_exit(&status)),
+        },
+    };
+
+    Ok((result, output))
+}
+
+#[cfg(unix)]
+fn fmt_abnormal_exit(status: &ExitStatus) -> String {
+    use std::os::unix::process::ExitStatusExt;
+
+    let signal = match status.signal() {
+        Some(signal) => signal,
+        None => return format!("test process terminated abnormally ({})", status),
+    };
+    let name = match signal {
+        libc::SIGABRT => "SIGABRT",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGILL => "SIGILL",
+        libc::SIGINT => "SIGINT",
+        libc::SIGKILL => "SIGKILL",
+        libc::SIGPIPE => "SIGPIPE",
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGSYS => "SIGSYS",
+        libc::SIGTERM => "SIGTERM",
+        libc::SIGTRAP => "SIGTRAP",
+        _ => return format!("test process terminated by signal {}", signal),
+    };
+    format!("test process terminated by signal {} ({})", signal, name)
+}
+
+#[cfg(not(unix))]
+fn fmt_abnormal_exit(status: &ExitStatus) -> String {
+    format!("test process terminated abnormally ({})", status)
+}
+
+/// Entry point of a test binary re-executed by `run_test_in_subprocess`:
+/// runs the single named test, between the suite fixtures, and reports its
+/// result through the exit code.
+fn run_isolated_test(name: &str, tests: Vec<TestDescAndFn>, options: Options) -> ! {
+    let TestDescAndFn { desc, testfn } = tests
+        .into_iter()
+        .find(|t| t.desc.name.as_slice() == name)
+        .unwrap_or_else(|| panic!("no test named `{}` to run in isolation", name));
+
+    let testfn: Box<dyn FnBox() + Send> = match testfn {
+        StaticTestFn(f) => Box::new(move || __rust_begin_short_backtrace(f)),
+        DynTestFn(f) => Box::new(move || __rust_begin_short_backtrace(f)),
+        StaticBenchFn(..) | DynBenchFn(..) => {
+            panic!("benchmarks can not be run in isolation")
+        }
+    };
+
+    if let Some(setup) = options.suite_setup {
+        if let Err(e) = catch_unwind(setup) {
+            eprintln!("note: suite setup failed: {}", panic_message(&*e));
+            process::exit(TR_FIXTURE_FAILED)
+        }
+    }
+
+    let result = run_with_fixtures(&desc, testfn);
+
+    let result = match options.suite_teardown.map(catch_unwind) {
+        Some(Err(ref e)) if result == TrOk => {
+            TrFixtureFailed(format!("suite teardown failed: {}", panic_message(&**e)))
+        }
+        _ => result,
+    };
+
+    match result {
+        TrOk => process::exit(TR_OK),
+        TrFailedMsg(msg) => {
+            eprintln!("note: {}", msg);
+            process::exit(TR_FAILED)
+        }
+        TrFixtureFailed(msg) => {
+            eprintln!("note: {}", msg);
+            process::exit(TR_FIXTURE_FAILED)
+        }
+        _ => process::exit(TR_FAILED),
+    }
+}
+
+/// Runs a test between its own setup and teardown hooks, if it has any.
+fn run_with_fixtures(desc: &TestDesc, testfn: Box<dyn FnBox() + Send>) -> TestResult {
+    if let Some(setup) = desc.setup {
+        if let Err(e) = catch_unwind(setup) {
+            return TrFixtureFailed(format!("setup failed: {}", panic_message(&*e)));
+        }
+    }
+
+    let result = calc_result(desc, catch_unwind(AssertUnwindSafe(testfn)));
+
+    match desc.teardown.map(catch_unwind) {
+        // A failing test is the more interesting result to report.
+        Some(Err(ref e)) if result == TrOk => {
+            TrFixtureFailed(format!("teardown failed: {}", panic_message(&**e)))
+        }
+        _ => result,
+    }
+}
+
+fn panic_message(err: &(dyn Any + Send)) -> &str {
+    err.downcast_ref::<String>()
+        .map(|e| &**e)
+        .or_else(|| err.downcast_ref::<&'static str>().map(|e| *e))
+        .unwrap_or("Box<Any>")
+}
+
 /// Fixed frame used to clean the backtrace with `RUST_BACKTRACE=1`.
 #[inline(never)]
 fn __rust_begin_short_backtrace<F: FnOnce()>(f: F) {
@@ -1569,7 +2772,44 @@ impl Bencher {
             return;
         }
 
-        self.summary = Some(iter(&mut inner));
+        self.summary = Some(iter_with_config(&mut inner, &self.effective_config()));
+    }
+
+    /// Run the benchmark for `time` before measuring it, so caches, branch
+    /// predictors and lazily initialized state are warm.
+    pub fn warm_up_time(&mut self, time: Duration) -> &mut Bencher {
+        self.config.warm_up_time = time;
+        self
+    }
+
+    /// Keep sampling for at least `time` before accepting the results.
+    pub fn measurement_time(&mut self, time: Duration) -> &mut Bencher {
+        self.config.measurement_time = time;
+        self
+    }
+
+    /// Take `n` samples per round of measurements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is less than 2.
+    pub fn sample_count(&mut self, n: usize) -> &mut Bencher {
+        assert!(n >= 2, "a benchmark needs at least 2 samples");
+        self.config.sample_count = n;
+        self
+    }
+
+    /// Choose how outlying samples are treated before the statistics are
+    /// computed.
+    pub fn outliers(&mut self, policy: OutlierPolicy) -> &mut Bencher {
+        self.config.outliers = policy;
+        self
+    }
+
+    /// The configuration `iter` runs with: the benchmark's own, with any
+    /// command line overrides applied.
+    pub fn effective_config(&self) -> BenchConfig {
+        self.overrides.apply(self.config)
     }
 
     pub fn bench<F>(&mut self, mut f: F) -> Option<stats::Summary>
@@ -1600,6 +2840,18 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
 where
     F: FnMut() -> T,
 {
+    iter_with_config(inner, &BenchConfig::default())
+}
+
+pub fn iter_with_config<T, F>(inner: &mut F, config: &BenchConfig) -> stats::Summary
+where
+    F: FnMut() -> T,
+{
+    let warm_up_start = Instant::now();
+    while warm_up_start.elapsed() < config.warm_up_time {
+        black_box(inner());
+    }
+
     // Initial bench run to get ballpark figure.
     let ns_single = ns_iter_inner(inner, 1);
 
@@ -1616,7 +2868,9 @@ where
     n = cmp::max(1, n);
 
     let mut total_run = Duration::new(0, 0);
-    let samples: &mut [f64] = &mut [0.0_f64; 50];
+    let samples: &mut [f64] = &mut vec![0.0_f64; config.sample_count];
+    // Longest we ever run for is 3s, unless asked to measure for longer.
+    let max_run = cmp::max(Duration::from_secs(3), config.measurement_time);
     loop {
         let loop_start = Instant::now();
 
@@ -1624,7 +2878,7 @@ where
             *p = ns_iter_inner(inner, n) as f64 / n as f64;
         }
 
-        stats::winsorize(samples, 5.0);
+        config.outliers.apply(samples);
         let summ = stats::Summary::new(samples);
 
         for p in &mut *samples {
@@ -1632,22 +2886,21 @@ where
             *p = ns as f64 / (5 * n) as f64;
         }
 
-        stats::winsorize(samples, 5.0);
+        config.outliers.apply(samples);
         let summ5 = stats::Summary::new(samples);
 
         let loop_run = loop_start.elapsed();
 
-        // If we've run for 100ms and seem to have converged to a
+        // If we've run for long enough and seem to have converged to a
         // stable median.
-        if loop_run > Duration::from_millis(100) && summ.median_abs_dev_pct < 1.0
+        if loop_run > config.measurement_time && summ.median_abs_dev_pct < 1.0
             && summ.median - summ5.median < summ5.median_abs_dev
         {
             return summ5;
         }
 
         total_run = total_run + loop_run;
-        // Longest we ever run for is 3s.
-        if total_run > Duration::from_secs(3) {
+        if total_run > max_run {
             return summ5;
         }
 
@@ -1670,16 +2923,25 @@ pub mod bench {
     use std::io;
     use std::sync::{Arc, Mutex};
     use stats;
-    use super::{BenchMode, BenchSamples, Bencher, MonitorMsg, Sender, Sink, TestDesc, TestResult};
+    use super::{BenchConfig, BenchMode, BenchOverrides, BenchSamples, Bencher, MonitorMsg, Sender,
+                Sink, TestDesc, TestResult};
 
-    pub fn benchmark<F>(desc: TestDesc, monitor_ch: Sender<MonitorMsg>, nocapture: bool, f: F)
-    where
+    pub fn benchmark<F>(
+        desc: TestDesc,
+        monitor_ch: Sender<MonitorMsg>,
+        nocapture: bool,
+        overrides: BenchOverrides,
+        f: F,
+    ) where
         F: FnMut(&mut Bencher),
     {
         let mut bs = Bencher {
             mode: BenchMode::Auto,
             summary: None,
+            config: BenchConfig::default(),
+            overrides,
             bytes: 0,
+            elements: 0,
         };
 
         let data = Arc::new(Mutex::new(Vec::new()));
@@ -1706,10 +2968,16 @@ pub mod bench {
             Ok(Some(ns_iter_summ)) => {
                 let ns_iter = cmp::max(ns_iter_summ.median as u64, 1);
                 let mb_s = bs.bytes * 1000 / ns_iter;
+                // Counts of cheap elements overflow u64 once they are
+                // scaled to seconds.
+                let elems_s = bs.elements as u128 * 1_000_000_000 / ns_iter as u128;
 
                 let bs = BenchSamples {
                     ns_iter_summ,
                     mb_s: mb_s as usize,
+                    elems_s: elems_s as usize,
+                    samples: bs.effective_config().sample_count,
+                    baseline: None,
                 };
                 TestResult::TrBench(bs)
             }
@@ -1720,6 +2988,9 @@ pub mod bench {
                 let bs = BenchSamples {
                     ns_iter_summ: stats::Summary::new(samples),
                     mb_s: 0,
+                    elems_s: 0,
+                    samples: 0,
+                    baseline: None,
                 };
                 TestResult::TrBench(bs)
             }
@@ -1727,7 +2998,7 @@ pub mod bench {
         };
 
         let stdout = data.lock().unwrap().to_vec();
-        monitor_ch.send((desc, test_result, stdout)).unwrap();
+        monitor_ch.send((desc, test_result, None, stdout)).unwrap();
     }
 
     pub fn run_once<F>(f: F)
@@ -1737,7 +3008,10 @@ pub mod bench {
         let mut bs = Bencher {
             mode: BenchMode::Single,
             summary: None,
+            config: BenchConfig::default(),
+            overrides: BenchOverrides::default(),
             bytes: 0,
+            elements: 0,
         };
         bs.bench(f);
     }
@@ -1745,12 +3019,15 @@ pub mod bench {
 
 #[cfg(test)]
 mod tests {
-    use test::{filter_tests, parse_opts, run_test, DynTestFn, DynTestName, MetricMap, RunIgnored,
-               ShouldPanic, StaticTestName, TestDesc, TestDescAndFn, TestOpts, TrFailed,
-               TrFailedMsg, TrIgnored, TrOk};
+    use test::{filter_tests, parse_opts, run_test, DynTestFn, DynTestName, Isolation, MetricMap,
+               RunIgnored, ShouldPanic, StaticTestFn, StaticTestName, TestDesc, TestDescAndFn,
+               TestOpts, TrFailed, TrFailedMsg, TrFlaky, TrIgnored, TrOk};
     use std::sync::mpsc::channel;
+    use std::time::Duration;
     use bench;
-    use Bencher;
+    use {check_time_budget, run_tests, run_with_fixtures, shuffle_tests, TeResult, TestExecTime,
+         TrFixtureFailed};
+    use {BenchConfig, BenchMode, BenchOverrides, Bencher, OutlierPolicy};
 
 
     fn one_ignored_one_unignored_test() -> Vec<TestDescAndFn> {
@@ -1761,6 +3038,10 @@ mod tests {
                     ignore: true,
                     should_panic: ShouldPanic::No,
                     allow_fail: false,
+                    timeout: None,
+                    retries: None,
+                    setup: None,
+                    teardown: None,
                 },
                 testfn: DynTestFn(Box::new(move || {})),
             },
@@ -1770,12 +3051,34 @@ mod tests {
                     ignore: false,
                     should_panic: ShouldPanic::No,
                     allow_fail: false,
+                    timeout: None,
+                    retries: None,
+                    setup: None,
+                    teardown: None,
                 },
                 testfn: DynTestFn(Box::new(move || {})),
             },
         ]
     }
 
+    fn numbered_tests() -> Vec<TestDescAndFn> {
+        (0..10)
+            .map(|i| TestDescAndFn {
+                desc: TestDesc {
+                    name: DynTestName(format!("test{}", i)),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    allow_fail: false,
+                    timeout: None,
+                    retries: None,
+                    setup: None,
+                    teardown: None,
+                },
+                testfn: DynTestFn(Box::new(move || {})),
+            })
+            .collect()
+    }
+
     #[test]
     pub fn do_not_run_ignored_tests() {
         fn f() {
@@ -1787,12 +3090,16 @@ mod tests {
                 ignore: true,
                 should_panic: ShouldPanic::No,
                 allow_fail: false,
+                timeout: None,
+                retries: None,
+                setup: None,
+                teardown: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res != TrOk);
     }
 
@@ -1805,12 +3112,16 @@ mod tests {
                 ignore: true,
                 should_panic: ShouldPanic::No,
                 allow_fail: false,
+                timeout: None,
+                retries: None,
+                setup: None,
+                teardown: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrIgnored);
     }
 
@@ -1825,12 +3136,16 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::Yes,
                 allow_fail: false,
+                timeout: None,
+                retries: None,
+                setup: None,
+                teardown: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
@@ -1845,12 +3160,16 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::YesWithMessage("error message"),
                 allow_fail: false,
+                timeout: None,
+                retries: None,
+                setup: None,
+                teardown: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
@@ -1867,12 +3186,16 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::YesWithMessage(expected),
                 allow_fail: false,
+                timeout: None,
+                retries: None,
+                setup: None,
+                teardown: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailedMsg(format!("{} '{}'", failed_msg, expected)));
     }
 
@@ -1885,12 +3208,16 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::Yes,
                 allow_fail: false,
+                timeout: None,
+                retries: None,
+                setup: None,
+                teardown: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailed);
     }
 
@@ -1917,6 +3244,32 @@ mod tests {
         assert_eq!(opts.run_ignored, RunIgnored::Yes);
     }
 
+    #[test]
+    fn parse_test_timeout_flag() {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--test-timeout".to_string(),
+            "30".to_string(),
+        ];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(opts.test_timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn parse_isolate_flag() {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--isolate=process".to_string(),
+        ];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(opts.isolation, Isolation::Process);
+
+        let args = vec!["progname".to_string(), "--isolate=process".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
     #[test]
     pub fn filter_for_ignored_option() {
         // When we run ignored tests the test filter should filter out all the
@@ -1962,6 +3315,10 @@ mod tests {
                         ignore: false,
                         should_panic: ShouldPanic::No,
                         allow_fail: false,
+                        timeout: None,
+                        retries: None,
+                        setup: None,
+                        teardown: None,
                     },
                     testfn: DynTestFn(Box::new(move || {})),
                 })
@@ -2045,6 +3402,270 @@ mod tests {
         assert_eq!(exact.len(), 1);
     }
 
+    #[test]
+    pub fn shards_partition_the_tests() {
+        let mut seen = Vec::new();
+        for shard_index in 0..3 {
+            let opts = TestOpts {
+                shard_index,
+                shard_count: 3,
+                ..TestOpts::new()
+            };
+            let shard = filter_tests(&opts, numbered_tests());
+            assert!(shard.len() == 3 || shard.len() == 4);
+            seen.extend(shard.into_iter().map(|t| t.desc.name.to_string()));
+        }
+
+        seen.sort();
+        let all = filter_tests(&TestOpts::new(), numbered_tests())
+            .into_iter()
+            .map(|t| t.desc.name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(seen, all);
+    }
+
+    #[test]
+    fn parse_shard_flags() {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--shard-index=1".to_string(),
+            "--shard-count=4".to_string(),
+        ];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!((opts.shard_index, opts.shard_count), (1, 4));
+
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--shard-index=4".to_string(),
+            "--shard-count=4".to_string(),
+        ];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    fn parse_retries_flag() {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--retries=2".to_string(),
+            "--fail-flaky".to_string(),
+        ];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(opts.retries, 2);
+        assert!(opts.fail_flaky);
+
+        let args = vec!["progname".to_string(), "--retries=2".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    fn parse_time_flags() {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--report-time".to_string(),
+            "--warn-slow=100".to_string(),
+            "--fail-slow=2000".to_string(),
+        ];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert!(opts.report_time);
+        assert_eq!(opts.warn_slow, Some(Duration::from_millis(100)));
+        assert_eq!(opts.fail_slow, Some(Duration::from_millis(2000)));
+
+        let args = vec!["progname".to_string(), "--report-time".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    fn fail_slow_only_fails_slow_passes() {
+        let opts = TestOpts {
+            fail_slow: Some(Duration::from_millis(100)),
+            ..TestOpts::new()
+        };
+        let fast = Some(TestExecTime(Duration::from_millis(10)));
+        let slow = Some(TestExecTime(Duration::from_millis(150)));
+
+        assert!(check_time_budget(&opts, TrOk, &fast) == TrOk);
+        assert!(check_time_budget(&opts, TrOk, &None) == TrOk);
+        assert!(check_time_budget(&opts, TrFailed, &slow) == TrFailed);
+        match check_time_budget(&opts, TrOk, &slow) {
+            TrFailedMsg(msg) => assert_eq!(
+                msg,
+                "test took 0.150s, more than the 0.100s allowed by --fail-slow"
+            ),
+            _ => panic!("slow test should have failed"),
+        }
+    }
+
+    #[test]
+    fn parse_bench_flags() {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--bench-warm-up=500".to_string(),
+            "--bench-samples=20".to_string(),
+            "--bench-outliers=winsorize=10".to_string(),
+        ];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(
+            opts.bench_overrides,
+            BenchOverrides {
+                warm_up_time: Some(Duration::from_millis(500)),
+                measurement_time: None,
+                sample_count: Some(20),
+                outliers: Some(OutlierPolicy::Winsorize(10.0)),
+            }
+        );
+
+        let bad_args = [
+            "--bench-samples=1",
+            "--bench-outliers=trim",
+            "--bench-outliers=winsorize=50",
+        ];
+        for bad in &bad_args {
+            let args = vec![
+                "progname".to_string(),
+                "-Zunstable-options".to_string(),
+                bad.to_string(),
+            ];
+            assert!(parse_opts(&args).unwrap().is_err());
+        }
+    }
+
+    #[test]
+    fn bench_overrides_take_precedence() {
+        let overrides = BenchOverrides {
+            sample_count: Some(10),
+            ..BenchOverrides::default()
+        };
+        let mut b = Bencher {
+            mode: BenchMode::Auto,
+            summary: None,
+            config: BenchConfig::default(),
+            overrides,
+            bytes: 0,
+            elements: 0,
+        };
+        b.sample_count(100).outliers(OutlierPolicy::Keep);
+
+        let config = b.effective_config();
+        assert_eq!(config.sample_count, 10);
+        assert_eq!(config.outliers, OutlierPolicy::Keep);
+        assert_eq!(config.measurement_time, BenchConfig::default().measurement_time);
+    }
+
+    #[test]
+    fn fixtures_run_around_tests() {
+        use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+        static TEARDOWNS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+        fn broken() {
+            panic!("no database");
+        }
+        fn teardown() {
+            TEARDOWNS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let desc = |setup, teardown| TestDesc {
+            name: StaticTestName("whatever"),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            allow_fail: false,
+            timeout: None,
+            retries: None,
+            setup,
+            teardown,
+        };
+
+        let result = run_with_fixtures(&desc(None, Some(teardown)), Box::new(|| panic!()));
+        assert!(result == TrFailed);
+        assert_eq!(TEARDOWNS.load(Ordering::SeqCst), 1);
+
+        let result = run_with_fixtures(&desc(Some(broken), Some(teardown)), Box::new(|| {}));
+        assert!(result == TrFixtureFailed("setup failed: no database".to_string()));
+        assert_eq!(TEARDOWNS.load(Ordering::SeqCst), 1);
+
+        let result = run_with_fixtures(&desc(None, Some(broken)), Box::new(|| {}));
+        assert!(result == TrFixtureFailed("teardown failed: no database".to_string()));
+    }
+
+    #[test]
+    fn failing_tests_are_retried() {
+        use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+        static RUNS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+        fn passes_on_third_run() {
+            if RUNS.fetch_add(1, Ordering::SeqCst) < 2 {
+                panic!();
+            }
+        }
+
+        let test = TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("flaky"),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+                allow_fail: false,
+                timeout: None,
+                retries: Some(3),
+                setup: None,
+                teardown: None,
+            },
+            testfn: StaticTestFn(passes_on_third_run),
+        };
+        let mut opts = TestOpts::new();
+        opts.run_tests = true;
+        opts.test_threads = Some(1);
+
+        let mut results = Vec::new();
+        run_tests(&opts, vec![test], |event| {
+            if let TeResult(_, result, _, stdout) = event {
+                results.push((result, String::from_utf8(stdout).unwrap()));
+            }
+            Ok(())
+        }).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].0 == TrFlaky(3));
+        // The output of the failed attempts is kept.
+        let output = &results[0].1;
+        assert!(output.contains("---- attempt 1 ----"), "{}", output);
+        assert!(output.contains("---- attempt 2 ----"), "{}", output);
+        assert!(output.contains("---- attempt 3 ----"), "{}", output);
+    }
+
+    #[test]
+    fn dynamic_tests_are_not_retried() {
+        let test = TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("dynamic"),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+                allow_fail: false,
+                timeout: None,
+                retries: Some(3),
+                setup: None,
+                teardown: None,
+            },
+            testfn: DynTestFn(Box::new(|| panic!())),
+        };
+        let mut opts = TestOpts::new();
+        opts.run_tests = true;
+        opts.test_threads = Some(1);
+
+        let mut results = Vec::new();
+        run_tests(&opts, vec![test], |event| {
+            if let TeResult(_, result, _, stdout) = event {
+                results.push((result, String::from_utf8(stdout).unwrap()));
+            }
+            Ok(())
+        }).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].0 == TrFailed);
+        assert!(results[0].1.contains("note: test was not retried"), "{}", results[0].1);
+    }
+
     #[test]
     pub fn sort_tests() {
         let mut opts = TestOpts::new();
@@ -2073,6 +3694,10 @@ mod tests {
                         ignore: false,
                         should_panic: ShouldPanic::No,
                         allow_fail: false,
+                        timeout: None,
+                        retries: None,
+                        setup: None,
+                        teardown: None,
                     },
                     testfn: DynTestFn(Box::new(testfn)),
                 };
@@ -2101,6 +3726,36 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn shuffle_is_reproducible() {
+        fn names(tests: &[TestDescAndFn]) -> Vec<String> {
+            tests.iter().map(|t| t.desc.name.to_string()).collect()
+        }
+
+        let mut first = numbered_tests();
+        shuffle_tests(1, &mut first);
+        let mut second = numbered_tests();
+        shuffle_tests(1, &mut second);
+        let mut other_seed = numbered_tests();
+        shuffle_tests(2, &mut other_seed);
+
+        assert_eq!(names(&first), names(&second));
+        assert!(names(&first) != names(&numbered_tests()));
+        assert!(names(&first) != names(&other_seed));
+    }
+
+    #[test]
+    fn parse_shuffle_seed_flag() {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--shuffle-seed".to_string(),
+            "1234".to_string(),
+        ];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(opts.shuffle_seed, Some(1234));
+    }
+
     #[test]
     pub fn test_metricmap_compare() {
         let mut m1 = MetricMap::new();
@@ -2149,9 +3804,13 @@ mod tests {
             ignore: false,
             should_panic: ShouldPanic::No,
             allow_fail: false,
+            timeout: None,
+            retries: None,
+            setup: None,
+            teardown: None,
         };
 
-        ::bench::benchmark(desc, tx, true, f);
+        ::bench::benchmark(desc, tx, true, BenchOverrides::default(), f);
         rx.recv().unwrap();
     }
 
@@ -2168,9 +3827,13 @@ mod tests {
             ignore: false,
             should_panic: ShouldPanic::No,
             allow_fail: false,
+            timeout: None,
+            retries: None,
+            setup: None,
+            teardown: None,
         };
 
-        ::bench::benchmark(desc, tx, true, f);
+        ::bench::benchmark(desc, tx, true, BenchOverrides::default(), f);
         rx.recv().unwrap();
     }
 }
@@ -325,6 +325,52 @@ pub fn winsorize(samples: &mut [f64], pct: f64) {
     }
 }
 
+/// Two-sided p-value of Welch's t-test on the difference between the means of
+/// two sample sets, each given as its mean, (sample) variance and size.
+///
+/// The t statistic is compared against the normal distribution rather than
+/// Student's t-distribution, which is accurate enough for the dozens of
+/// samples a benchmark collects.
+///
+/// See: <https://en.wikipedia.org/wiki/Welch%27s_t-test>
+pub fn welch_t_test(a: (f64, f64, usize), b: (f64, f64, usize)) -> f64 {
+    let (mean_a, var_a, n_a) = a;
+    let (mean_b, var_b, n_b) = b;
+    if n_a == 0 || n_b == 0 {
+        return 1.0;
+    }
+    let std_err = (var_a / n_a as f64 + var_b / n_b as f64).sqrt();
+    if std_err == 0.0 {
+        return if mean_a == mean_b { 1.0 } else { 0.0 };
+    }
+    let t = (mean_a - mean_b) / std_err;
+    erfc(t.abs() / 2f64.sqrt())
+}
+
+/// Complementary error function, with a fractional error below 1.2e-7.
+///
+/// See: Numerical Recipes in C, 2nd edition, section 6.2
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -1.26551223
+        + t * (1.00002368
+        + t * (0.37409196
+        + t * (0.09678418
+        + t * (-0.18628806
+        + t * (0.27886807
+        + t * (-1.13520398
+        + t * (1.48851587
+        + t * (-0.82215223
+        + t * 0.17087277))))))));
+    let r = t * (-z * z + poly).exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
 // Test vectors generated from R, using the script src/etc/stat-test-vectors.r.
 
 #[cfg(test)]
@@ -903,6 +949,19 @@ mod tests {
     fn test_sum_f64_between_ints_that_sum_to_0() {
         assert_eq!([1e30f64, 1.2f64, -1e30f64].sum(), 1.2);
     }
+
+    #[test]
+    fn test_welch_t_test() {
+        use stats::welch_t_test;
+
+        // Identical sample sets can't be told apart.
+        assert!(welch_t_test((100.0, 4.0, 50), (100.0, 4.0, 50)) > 0.99);
+        // A shift of many standard errors is significant.
+        assert!(welch_t_test((100.0, 4.0, 50), (110.0, 4.0, 50)) < 1.0e-6);
+        // A shift of about one standard error is not.
+        let p = welch_t_test((100.0, 25.0, 50), (101.0, 25.0, 50));
+        assert!(p > 0.05 && p < 0.95);
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Checks that the warnings a query emitted are emitted again when the query
+// is not run again, because nothing it depends on changed. Something else in
+// the crate changes, so this does not rely on the crate as a whole being
+// unchanged.
+
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph
+// compile-pass
+
+#![feature(rustc_attrs)]
+#![allow(warnings)]
+#![warn(unused_mut)]
+
+// `unused_mut` is emitted by the borrow checker, whose result is reused.
+#[rustc_clean(cfg="cfail2", label="BorrowCheck")]
+fn borrowed() -> u32 {
+    let mut x = 1; //~ WARNING variable does not need to be mutable
+    x
+}
+
+#[cfg(cfail1)]
+fn changed() -> u32 {
+    1
+}
+
+#[cfg(cfail2)]
+fn changed() -> u32 {
+    2
+}
+
+fn main() {
+    println!("{}", borrowed() + changed());
+}
@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Checks that the lints emitted outside of queries are emitted again when
+// nothing changed.
+
+// revisions: cfail1 cfail2 cfail3
+// compile-pass
+
+#![allow(warnings)]
+#![warn(non_snake_case, unused_variables)]
+
+fn Foo() {} //~ WARNING function `Foo` should have a snake case name
+
+fn main() {
+    let x = 1; //~ WARNING unused variable: `x`
+    Foo();
+}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// check-stdout
+// error-pattern:test hangs ... FAILED (timed out)
+// compile-flags: --test
+// exec-env:RUST_TEST_THREADS=1
+// ignore-emscripten
+// ignore-wasm32-bare no threads to abandon
+
+#![feature(test_timeout)]
+
+use std::thread;
+use std::time::Duration;
+
+#[test]
+#[test_timeout(1)]
+fn hangs() {
+    loop {
+        thread::sleep(Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(test_fixtures)]
+#![test_setup(start_suite)]
+#![test_teardown(finish_suite)]
+
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+
+fn log(event: &str) {
+    let path = env::var_os("FIXTURES_LOG").unwrap();
+    let mut file = OpenOptions::new().create(true).append(true).open(path).unwrap();
+    writeln!(file, "{}", event).unwrap();
+}
+
+fn start_suite() {
+    log("setup");
+}
+
+fn finish_suite() {
+    log("teardown");
+}
+
+#[test]
+fn a() {}
+
+#[test]
+fn b() {}
@@ -0,0 +1,51 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::env;
+use std::process::{self, Command};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn passes() {}
+
+#[test]
+fn env_not_inherited() {
+    // A test harness started by this test would otherwise try to run this
+    // test in isolation too.
+    assert!(env::var_os("__RUST_TEST_ISOLATED").is_none());
+}
+
+#[test]
+fn panics() {
+    panic!("boom");
+}
+
+#[test]
+fn aborts() {
+    process::abort();
+}
+
+#[test]
+fn killed() {
+    Command::new("kill")
+        .arg("-KILL")
+        .arg(process::id().to_string())
+        .status()
+        .unwrap();
+    loop {
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+#[test]
+fn exits() {
+    process::exit(3);
+}
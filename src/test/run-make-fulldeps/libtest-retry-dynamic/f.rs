@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(test)]
+
+extern crate test;
+
+use std::env;
+use std::fs::File;
+use std::path::PathBuf;
+use test::{DynTestFn, DynTestName, ShouldPanic, TestDesc, TestDescAndFn};
+
+// A custom harness, like rustdoc's, whose test fails on its first attempt.
+fn main() {
+    let marker = PathBuf::from(env::var_os("MARKER").unwrap());
+    let test = TestDescAndFn {
+        desc: TestDesc {
+            name: DynTestName("fails_once".to_string()),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            allow_fail: false,
+            timeout: None,
+            retries: None,
+            setup: None,
+            teardown: None,
+        },
+        testfn: DynTestFn(Box::new(move || {
+            if !marker.exists() {
+                File::create(&marker).unwrap();
+                panic!("first attempt");
+            }
+        })),
+    };
+    let args = env::args().collect::<Vec<_>>();
+    test::test_main(&args, vec![test], test::Options::new());
+}
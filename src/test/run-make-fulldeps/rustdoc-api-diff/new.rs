@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+#![crate_name = "foo"]
+#![feature(non_exhaustive)]
+
+pub enum Exhaustive {
+    A,
+    B,
+}
+
+#[non_exhaustive]
+pub enum Open {
+    A,
+    B,
+}
+
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+pub trait Shape {
+    fn area(&self) -> f64;
+
+    fn name(&self) -> String {
+        String::new()
+    }
+}
+
+pub fn parse(input: u16) -> i32 {
+    input as i32
+}
+
+pub fn added() {}
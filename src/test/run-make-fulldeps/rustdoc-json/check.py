@@ -0,0 +1,71 @@
+#!/usr/bin/env python
+
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+import sys
+import json
+
+with open(sys.argv[1]) as f:
+    doc = json.load(f)
+
+assert doc['format_version'] == 1
+assert doc['crate']['name'] == 'foo'
+index = doc['index']
+
+
+def find(kind, name):
+    found = [i for i in index.values() if i['kind'] == kind and i['name'] == name]
+    assert len(found) == 1, (kind, name, found)
+    return found[0]
+
+
+root = index[doc['root']]
+assert root['kind'] == 'mod' and root['inner']['is_crate']
+
+point = find('struct', 'Point')
+assert point['visibility'] == 'public'
+assert point['docs'] == 'A point, see [`Shape`].'
+shape = find('trait', 'Shape')
+assert [l['id'] for l in point['links']] == [shape['id']]
+assert doc['paths'][point['id']]['path'] == ['foo', 'Point']
+
+# The private field is stripped.
+assert point['inner']['fields_stripped']
+assert sorted(index[f]['name'] for f in point['inner']['fields']) == ['x', 'y']
+x = find('structfield', 'x')
+assert x['inner']['type'] == {'kind': 'generic', 'name': 'T'}
+
+param = point['inner']['generics']['params'][0]
+assert param['name'] == 'T' and param['kind'] == 'type'
+assert param['bounds'][0]['trait']['name'] == 'Copy'
+
+# The trait impl is listed on both the type and the trait.
+impls = [index[i] for i in point['inner']['impls']]
+trait_impl = [i for i in impls if i['inner']['trait'] is not None]
+assert len(trait_impl) == 1
+trait_impl = trait_impl[0]
+assert trait_impl['inner']['trait']['id'] == shape['id']
+assert trait_impl['id'] in shape['inner']['implementors']
+where_clause = trait_impl['inner']['generics']['where_predicates'][0]
+assert [b['trait']['name'] for b in where_clause['bounds']] == ['Copy', 'Into']
+
+area = find('tymethod', 'area')
+assert area['inner']['has_body'] is False
+assert area['inner']['decl']['output'] == {'kind': 'primitive', 'name': 'f64'}
+
+old = find('fn', 'old')
+assert old['deprecation'] == {'since': '0.1.0', 'note': 'use something else'}
+assert doc['paths'][old['id']]['path'] == ['foo', 'inner', 'old']
+arg = old['inner']['decl']['inputs'][0]
+assert arg['name'] == 'x'
+assert arg['type']['kind'] == 'borrowed_ref' and arg['type']['mutable']
+assert arg['type']['type'] == {'kind': 'slice', 'type': {'kind': 'primitive', 'name': 'u8'}}
+assert old['inner']['decl']['output']['name'] == 'Option'
@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+#![crate_name = "foo"]
+
+/// A point, see [`Shape`].
+pub struct Point<T: Copy> {
+    pub x: T,
+    pub y: T,
+    hidden: (),
+}
+
+/// Anything with an area.
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+impl<T> Shape for Point<T> where T: Copy + Into<f64> {
+    fn area(&self) -> f64 {
+        0.0
+    }
+}
+
+pub mod inner {
+    #[deprecated(since = "0.1.0", note = "use something else")]
+    pub fn old(x: &mut [u8]) -> Option<usize> {
+        None
+    }
+}
@@ -0,0 +1,38 @@
+#!/usr/bin/env python
+
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+import glob
+import json
+import os
+import sys
+
+traces = glob.glob(os.path.join(sys.argv[1], 'self_profiler_trace-foo-*.json'))
+assert len(traces) == 1, traces
+with open(traces[0]) as f:
+    events = json.load(f)['traceEvents']
+
+# Every query that starts on a thread ends on it, after the queries it started.
+stacks = {}
+for event in events:
+    stack = stacks.setdefault(event['tid'], [])
+    if event['ph'] == 'B':
+        stack.append(event)
+    else:
+        assert event['ph'] == 'E', event
+        assert stack, 'query ended without starting: %s' % event
+        begin = stack.pop()
+        assert begin['name'] == event['name'], (begin, event)
+        assert begin['ts'] <= event['ts'], (begin, event)
+for tid, stack in stacks.items():
+    assert not stack, 'queries never ended on thread %s: %s' % (tid, stack)
+
+assert any(event['name'] == 'typeck_tables_of' for event in events)
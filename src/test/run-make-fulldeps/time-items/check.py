@@ -0,0 +1,50 @@
+#!/usr/bin/env python
+
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+import sys
+import json
+
+CATEGORIES = ['typeck', 'borrowck', 'mir_opt', 'codegen', 'llvm']
+
+
+def find(items, name):
+    found = [i for i in items if i['item'].split('::')[-1] == name]
+    assert len(found) == 1, (name, found)
+    return found[0]
+
+
+def main():
+    with open(sys.argv[1]) as f:
+        report = json.load(f)
+    items = report['items']
+    assert report['unattributed_llvm'] >= 0
+
+    totals = [item['total'] for item in items]
+    assert totals == sorted(totals, reverse=True), totals
+    for item in items:
+        assert sorted(item['times']) == sorted(CATEGORIES), item
+        assert all(time >= 0 for time in item['times'].values()), item
+        assert abs(sum(item['times'].values()) - item['total']) < 0.001, item
+        assert isinstance(item['memory'], int), item
+
+    # Both instances of `double` are counted for it, and both functions are generated.
+    for name in ['sum', 'sum_u64', 'double']:
+        item = find(items, name)
+        assert item['span']['file_name'] == 'foo.rs', item
+        assert item['times']['typeck'] > 0, item
+    assert find(items, 'double')['times']['codegen'] > 0
+    assert find(items, 'sum')['span']['line_start'] == 13
+    assert find(items, 'double')['span']['line_start'] == 17
+
+
+if __name__ == '__main__':
+    main()
@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+
+pub fn sum(values: &[u32]) -> u32 {
+    values.iter().map(|&value| double(value)).sum()
+}
+
+fn double<T: Copy + ::std::ops::Add<Output = T>>(value: T) -> T {
+    value + value
+}
+
+pub fn sum_u64(values: &[u64]) -> u64 {
+    values.iter().fold(0, |total, &value| total + double(value))
+}
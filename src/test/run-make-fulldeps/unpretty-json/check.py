@@ -0,0 +1,86 @@
+#!/usr/bin/env python
+
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+import sys
+import json
+
+
+def load(path):
+    with open(path) as f:
+        doc = json.load(f)
+    assert doc['version'] == 1
+    assert doc['crate'] == 'foo'
+    return doc
+
+
+def find(items, kind, name):
+    found = [i for i in items if i['kind'] == kind and i['name'] == name]
+    assert len(found) == 1, (kind, name, found)
+    return found[0]
+
+
+hir = load(sys.argv[1])
+items = hir['items']
+
+point = find(items, 'struct', 'Point')
+assert point['def_path'] == 'Point'
+assert point['visibility'] == 'pub'
+assert point['ctor_kind'] == 'struct'
+assert [(f['name'], f['ty'], f['visibility']) for f in point['fields']] == \
+    [('x', 'i32', 'pub'), ('y', 'i32', '')]
+assert point['span']['line_start'] == 13
+
+shape = find(items, 'trait', 'Shape')
+area = find(shape['items'], 'method', 'area')
+assert area['signature']['inputs'] == ['&Self']
+assert area['signature']['output'] == 'f64'
+assert not area['has_default']
+
+impl = [i for i in items if i['kind'] == 'impl']
+assert len(impl) == 1 and impl[0]['self_ty'] == 'Point' and impl[0]['trait'] is None
+new = find(impl[0]['items'], 'method', 'new')
+assert new['signature']['inputs'] == ['i32', 'i32']
+
+sum_fn = find(items, 'fn', 'sum')
+assert sum_fn['signature']['inputs'] == ['&[Point]']
+assert sum_fn['signature']['abi'] == 'Rust'
+
+mir = load(sys.argv[2])
+bodies = dict((b['def_path'], b) for b in mir['bodies'])
+
+body = bodies['sum']
+assert body['kind'] == 'fn' and body['promoted'] is None
+assert body['arg_count'] == 1
+assert body['return_ty'] == 'i32'
+locals_ = body['locals']
+assert locals_[0]['kind'] == 'return'
+assert locals_[1]['kind'] == 'arg' and locals_[1]['name'] == 'points'
+assert locals_[1]['ty'] == '&[Point]'
+assert any(l['kind'] == 'var' and l['name'] == 'total' and l['mutable'] for l in locals_)
+
+blocks = body['basic_blocks']
+for block in blocks:
+    for successor in block['terminator']['successors']:
+        assert 0 <= successor['target'] < len(blocks)
+    for statement in block['statements']:
+        assert 0 <= statement['source_info']['scope'] < len(body['source_scopes'])
+assert any(b['terminator']['kind'] == 'Return' for b in blocks)
+assert any(b['terminator']['kind'] == 'Call' for b in blocks)
+
+# `point.x` reads a field through a reference.
+fields = [p for b in blocks for s in b['statements'] if s['kind'] == 'Assign'
+          for p in [s['rvalue'].get('operand', {}).get('place')] if p]
+assert any([e['kind'] for e in p['projections']] == ['Deref', 'Field'] and p['ty'] == 'i32'
+           for p in fields), fields
+
+mir_sum = load(sys.argv[3])
+assert [b['def_path'] for b in mir_sum['bodies'] if b['kind'] != 'promoted'] == ['sum']
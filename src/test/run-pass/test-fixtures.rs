@@ -0,0 +1,55 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-wasm32-bare compiled with panic=abort by default
+// compile-flags: --test
+// exec-env:RUST_TEST_THREADS=1
+
+#![feature(test_fixtures)]
+#![test_setup(start_suite)]
+#![test_teardown(finish_suite)]
+
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+
+static SUITE_STARTED: AtomicUsize = ATOMIC_USIZE_INIT;
+static ACQUIRED: AtomicUsize = ATOMIC_USIZE_INIT;
+
+fn start_suite() {
+    SUITE_STARTED.store(1, Ordering::SeqCst);
+}
+
+fn finish_suite() {
+    // Every teardown has run, including the one of the panicking test.
+    assert_eq!(ACQUIRED.load(Ordering::SeqCst), 0);
+}
+
+fn acquire() {
+    ACQUIRED.fetch_add(1, Ordering::SeqCst);
+}
+
+fn release() {
+    ACQUIRED.fetch_sub(1, Ordering::SeqCst);
+}
+
+#[test]
+#[test_setup(acquire)]
+#[test_teardown(release)]
+fn runs_between_fixtures() {
+    assert_eq!(SUITE_STARTED.load(Ordering::SeqCst), 1);
+    assert_eq!(ACQUIRED.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+#[should_panic]
+#[test_setup(acquire)]
+#[test_teardown(release)]
+fn teardown_runs_after_panic() {
+    panic!();
+}
@@ -0,0 +1,21 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Functions taking exactly the arguments asked for rank before those whose generic
+// arguments could be instantiated to match.
+
+const QUERY = '&str -> Result<_, _>';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'std::str', 'name': 'parse' },
+        { 'path': 'std::env', 'name': 'var' },
+    ],
+};
@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-order
+
+// `T` has to stand for the same type in all three places.
+
+const QUERY = 'T, T -> T';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'std::cmp', 'name': 'max' },
+        { 'path': 'std::cmp', 'name': 'min' },
+        { 'path': 'std::mem', 'name': 'replace' },
+    ],
+};
@@ -0,0 +1,34 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Compatible doctests are compiled together, but still reported on their own.
+
+// compile-flags:--test -Z unstable-options --combine-doctests --test-args --test-threads=1
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// failure-status: 101
+// rustc-env:RUST_BACKTRACE=0
+
+/// ```
+/// assert_eq!(1 + 1, 2);
+/// ```
+pub struct Passes;
+
+/// ```
+/// panic!("oh no");
+/// ```
+pub struct Fails;
+
+// has its own `main`, so it is compiled on its own
+/// ```
+/// fn main() {
+///     assert!(true);
+/// }
+/// ```
+pub struct Standalone;
@@ -0,0 +1,69 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags:-Z unstable-options --show-coverage
+// compile-pass
+
+//! The crate root counts as a documented item.
+
+/// This struct is documented, but its fields are not.
+///
+/// One of the fields is private, so it isn't counted.
+pub struct SomeStruct {
+    pub some_field: usize,
+    other_field: usize,
+}
+
+impl SomeStruct {
+    /// Method with docs.
+    pub fn this_fn(&self) {}
+
+    // Method without docs.
+    pub fn other_method(&self) {}
+}
+
+// Struct without docs.
+pub struct OtherStruct;
+
+// Function without docs.
+pub fn some_fn() {}
+
+/// Function with docs and an example.
+///
+/// ```
+/// coverage_basic::other_fn();
+/// ```
+pub fn other_fn() {}
+
+pub enum Things {
+    /// Documented variant.
+    Item1,
+    Item2,
+}
+
+/// Documented trait.
+pub trait ThisTrait {
+    /// Documented method.
+    fn test(&self);
+    fn other_test(&self);
+}
+
+// Items of trait impls show the trait's docs, so they are not counted.
+impl ThisTrait for SomeStruct {
+    fn test(&self) {}
+    fn other_test(&self) {}
+}
+
+#[doc(hidden)]
+pub fn hidden() {}
+
+// Tuple fields don't need docs.
+/// Documented tuple struct.
+pub struct Tuple(pub u8);
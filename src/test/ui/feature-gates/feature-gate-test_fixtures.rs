@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// check that #[test_setup] and #[test_teardown] are feature-gated, both on
+// tests and as crate attributes
+
+#![test_setup(prepare)] //~ ERROR the `#[test_setup]` attribute is an experimental feature
+#![test_teardown(prepare)] //~ ERROR the `#[test_teardown]` attribute is an experimental feature
+
+fn prepare() {}
+
+#[test_setup(prepare)] //~ ERROR the `#[test_setup]` attribute is an experimental feature
+#[test_teardown(prepare)] //~ ERROR the `#[test_teardown]` attribute is an experimental feature
+fn slow() {}
+
+fn main() {}
@@ -0,0 +1,21 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --error-format sarif -Zunstable-options
+// compile-pass
+
+// Checks that lints become rules, that the lint level attribute becomes a related location and
+// that the suggestion becomes a fix.
+
+#![warn(unused_parens)]
+
+fn main() {
+    let _a = (1 / (2 + 3));
+}
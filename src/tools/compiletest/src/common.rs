@@ -11,6 +11,7 @@ pub use self::Mode::*;
 
 use std::fmt;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::str::FromStr;
 
 use test::ColorConfig;
@@ -159,7 +160,8 @@ pub struct Config {
     /// The directory where programs should be built
     pub build_base: PathBuf,
 
-    /// The name of the stage being built (stage1, etc)
+    /// The name of the stage being built (stage1, etc), empty when the compiler being tested
+    /// is not being bootstrapped
     pub stage_id: String,
 
     /// The test mode, compile-fail, run-fail, run-pass
@@ -244,6 +246,10 @@ pub struct Config {
     /// mode describing what file the actual ui output will be compared to
     pub compare_mode: Option<CompareMode>,
 
+    /// Directory to record the diagnostic codes triggered by each test in, and to write the
+    /// coverage report of error codes and lints to
+    pub diagnostic_coverage: Option<PathBuf>,
+
     // Configuration for various run-make tests frobbing things like C compilers
     // or querying about various LLVM component information.
     pub cc: String,
@@ -256,6 +262,94 @@ pub struct Config {
     pub nodejs: Option<String>,
 }
 
+impl Config {
+    /// Creates the configuration for running the tests in `src_base` in the given mode with the
+    /// `rustc` at `rustc_path`, putting their output in `build_base`.
+    ///
+    /// The tests are built for the host of that compiler, and the libraries of its sysroot are
+    /// used to run it and the tests. Everything else is left at its default and can be set on
+    /// the returned value; the debugger, LLVM and C toolchain settings are only needed by the
+    /// test suites of the Rust repository itself.
+    pub fn new(mode: Mode, rustc_path: PathBuf, src_base: PathBuf, build_base: PathBuf) -> Config {
+        let version = rustc_output(&rustc_path, &["-vV"]);
+        let host = version
+            .lines()
+            .find(|line| line.starts_with("host: "))
+            .map(|line| line["host: ".len()..].trim().to_owned())
+            .unwrap_or_else(|| panic!("no host in the output of `{} -vV`", rustc_path.display()));
+        let sysroot = PathBuf::from(rustc_output(&rustc_path, &["--print", "sysroot"]).trim());
+
+        Config {
+            bless: false,
+            compile_lib_path: sysroot.join("lib"),
+            run_lib_path: sysroot.join("lib").join("rustlib").join(&host).join("lib"),
+            rustc_path,
+            rustdoc_path: None,
+            lldb_python: "python".to_owned(),
+            docck_python: "python".to_owned(),
+            llvm_filecheck: None,
+            valgrind_path: None,
+            force_valgrind: false,
+            src_base,
+            build_base,
+            stage_id: String::new(),
+            mode,
+            run_ignored: false,
+            filter: None,
+            filter_exact: false,
+            logfile: None,
+            runtool: None,
+            host_rustcflags: None,
+            target_rustcflags: None,
+            target: host.clone(),
+            host,
+            gdb: None,
+            gdb_version: None,
+            gdb_native_rust: false,
+            lldb_version: None,
+            lldb_native_rust: false,
+            llvm_version: None,
+            system_llvm: false,
+            android_cross_path: PathBuf::new(),
+            adb_path: "(none)".to_owned(),
+            adb_test_dir: "(none)".to_owned(),
+            adb_device_status: false,
+            lldb_python_dir: None,
+            verbose: false,
+            quiet: false,
+            color: ColorConfig::AutoColor,
+            remote_test_client: None,
+            compare_mode: None,
+            diagnostic_coverage: None,
+            cc: "cc".to_owned(),
+            cxx: "c++".to_owned(),
+            cflags: String::new(),
+            ar: "ar".to_owned(),
+            linker: None,
+            llvm_components: String::new(),
+            llvm_cxxflags: String::new(),
+            nodejs: None,
+        }
+    }
+}
+
+/// Runs `rustc` with the given arguments and returns what it printed.
+fn rustc_output(rustc_path: &Path, args: &[&str]) -> String {
+    let output = Command::new(rustc_path)
+        .args(args)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run `{}`: {}", rustc_path.display(), e));
+    if !output.status.success() {
+        panic!(
+            "`{} {}` failed:\n{}",
+            rustc_path.display(),
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    String::from_utf8(output.stdout).unwrap()
+}
+
 #[derive(Debug, Clone)]
 pub struct TestPaths {
     pub file: PathBuf,         // e.g., compile-test/foo/bar/baz.rs
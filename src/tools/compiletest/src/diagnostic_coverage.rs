@@ -0,0 +1,359 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The report of which error codes and lints are exercised by tests, written when
+//! `--diagnostic-coverage DIR` is passed.
+//!
+//! Every ui and compile-fail test that is compiled with JSON diagnostics leaves a record in
+//! `DIR/tests` listing the codes the compiler emitted for it. At the end of each run, all records
+//! in the directory are combined into `DIR/report.txt`, so running several suites with the same
+//! directory gives a report covering all of them. Tests skipped because they are up to date keep
+//! their record from an earlier run; delete the directory to start from scratch.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde_json;
+
+use common::{Config, TestPaths};
+use errors::{Error, ErrorKind};
+
+#[derive(Serialize, Deserialize)]
+struct TestRecord {
+    /// The test file relative to the parent of the suite directory, like `ui/foo.rs#rev`.
+    test: String,
+    /// The codes of all diagnostics the compiler emitted, error codes and lint names alike.
+    codes: BTreeSet<String>,
+    /// Whether the test has `//~ ERROR` annotations but none of them mention an error code.
+    annotations_without_codes: bool,
+}
+
+/// Records the diagnostic codes emitted when compiling a test, along with whether its error
+/// annotations name any error code.
+pub fn record(
+    config: &Config,
+    testpaths: &TestPaths,
+    revision: Option<&str>,
+    codes: BTreeSet<String>,
+    expected_errors: &[Error],
+) {
+    let dir = match config.diagnostic_coverage {
+        Some(ref dir) => dir,
+        None => return,
+    };
+
+    let suite = config.src_base.file_name().unwrap();
+    let mut test = Path::new(suite)
+        .join(&testpaths.relative_dir)
+        .join(testpaths.file.file_name().unwrap())
+        .to_str()
+        .unwrap()
+        .replace('\\', "/");
+    if let Some(revision) = revision {
+        test.push('#');
+        test.push_str(revision);
+    }
+
+    let error_annotations: Vec<_> = expected_errors
+        .iter()
+        .filter(|error| error.kind == Some(ErrorKind::Error))
+        .collect();
+    let annotations_without_codes = !error_annotations.is_empty()
+        && !error_annotations.iter().any(|error| mentions_error_code(&error.msg));
+
+    let path = dir.join("tests").join(format!("{}.json", test.replace('#', ".")));
+    let record = TestRecord { test, codes, annotations_without_codes };
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, serde_json::to_string(&record).unwrap())
+        .unwrap_or_else(|e| panic!("failed to write {}: {}", path.display(), e));
+}
+
+/// Combines all the records in the `--diagnostic-coverage` directory into `report.txt` there.
+///
+/// The error codes and lints registered by the compiler are only known when testing from a
+/// checkout of the Rust repository; otherwise only the codes that are triggered get listed.
+pub fn write_report(config: &Config) {
+    let dir = match config.diagnostic_coverage {
+        Some(ref dir) => dir,
+        None => return,
+    };
+
+    let mut records = vec![];
+    collect_records(&dir.join("tests"), &mut records);
+
+    let mut triggered: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for record in &records {
+        for code in &record.codes {
+            triggered.entry(code).or_default().push(&record.test);
+        }
+    }
+
+    let (error_codes, lints) = match config.find_rust_src_root() {
+        Some(root) => registered_diagnostics(&root.join("src")),
+        None => (BTreeSet::new(), BTreeSet::new()),
+    };
+    let untested_codes: Vec<_> =
+        error_codes.iter().filter(|code| !triggered.contains_key(&code[..])).collect();
+    let untested_lints: Vec<_> =
+        lints.iter().filter(|lint| !triggered.contains_key(&lint[..])).collect();
+
+    let mut report = String::new();
+    writeln!(
+        report,
+        "{} of {} registered error codes and {} of {} lints are triggered by the {} tests \
+         recorded.",
+        error_codes.len() - untested_codes.len(),
+        error_codes.len(),
+        lints.len() - untested_lints.len(),
+        lints.len(),
+        records.len()
+    ).unwrap();
+
+    writeln!(report, "\nError codes not triggered by any test:").unwrap();
+    for code in &untested_codes {
+        writeln!(report, "    {}", code).unwrap();
+    }
+    writeln!(report, "\nLints not triggered by any test:").unwrap();
+    for lint in &untested_lints {
+        writeln!(report, "    {}", lint).unwrap();
+    }
+    writeln!(report, "\nTests whose `//~ ERROR` annotations do not mention an error code:")
+        .unwrap();
+    for record in records.iter().filter(|record| record.annotations_without_codes) {
+        writeln!(report, "    {}", record.test).unwrap();
+    }
+    writeln!(report, "\nTests triggering each error code and lint:").unwrap();
+    for (code, tests) in &triggered {
+        writeln!(report, "    {}: {}", code, tests.join(", ")).unwrap();
+    }
+
+    let path = dir.join("report.txt");
+    fs::write(&path, report)
+        .unwrap_or_else(|e| panic!("failed to write {}: {}", path.display(), e));
+    println!(
+        "diagnostic coverage: {} error codes and {} lints are not triggered by any test; \
+         see {}",
+        untested_codes.len(),
+        untested_lints.len(),
+        path.display()
+    );
+}
+
+fn collect_records(dir: &Path, records: &mut Vec<TestRecord>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let mut paths: Vec<PathBuf> = entries.map(|entry| entry.unwrap().path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_records(&path, records);
+        } else if path.extension().map_or(false, |ext| ext == "json") {
+            let contents = fs::read_to_string(&path).unwrap();
+            match serde_json::from_str(&contents) {
+                Ok(record) => records.push(record),
+                Err(e) => println!("ignoring malformed record {}: {}", path.display(), e),
+            }
+        }
+    }
+}
+
+fn mentions_error_code(msg: &str) -> bool {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"\bE\d{4}\b").unwrap();
+    }
+    RE.is_match(msg)
+}
+
+/// Finds the error codes registered in the `diagnostics.rs` files of the compiler crates, and the
+/// names of the lints declared with `declare_lint!` or `declare_tool_lint!`, as they appear in
+/// diagnostics.
+fn registered_diagnostics(src: &Path) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut error_codes = BTreeSet::new();
+    let mut lints = BTreeSet::new();
+
+    let mut crates: Vec<PathBuf> = fs::read_dir(src)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.is_dir() && path.file_name().unwrap().to_str().unwrap().starts_with("lib")
+        })
+        .collect();
+    crates.sort();
+
+    for krate in crates {
+        walk_rust_files(&krate, &mut |file, contents| {
+            let filename = file.file_name().unwrap();
+            if filename == "diagnostics.rs" || filename == "diagnostic_list.rs" {
+                scan_error_codes(contents, &mut error_codes);
+            }
+            scan_lints(contents, &mut lints);
+        });
+    }
+
+    (error_codes, lints)
+}
+
+fn walk_rust_files(dir: &Path, f: &mut FnMut(&Path, &str)) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            walk_rust_files(&path, f);
+        } else if path.extension().map_or(false, |ext| ext == "rs") {
+            f(&path, &fs::read_to_string(&path).unwrap());
+        }
+    }
+}
+
+/// Collects the codes of `register_long_diagnostics!` and `register_diagnostics!`, which start
+/// their line, like `E0001: r##"` or `E0002, // comment`.
+fn scan_error_codes(contents: &str, codes: &mut BTreeSet<String>) {
+    lazy_static! {
+        static ref REGISTERED_CODE_RE: Regex = Regex::new(r"^\s*(E\d{4})\s*[:,]").unwrap();
+    }
+
+    // The long explanations mention other codes and contain code examples, so they are skipped.
+    let mut inside_long_diag = false;
+    for line in contents.lines() {
+        if inside_long_diag {
+            inside_long_diag = !line.contains("\"##");
+            continue;
+        }
+
+        if let Some(captures) = REGISTERED_CODE_RE.captures(line) {
+            codes.insert(captures[1].to_owned());
+        }
+
+        // A short explanation can open and close on the same line.
+        inside_long_diag = match line.find("r##\"") {
+            Some(start) => !line[start + "r##\"".len()..].contains("\"##"),
+            None => false,
+        };
+    }
+}
+
+/// Collects the names of the lints declared like `declare_lint! { pub UNUSED_IMPORTS, ... }`,
+/// and of tool lints declared like `declare_tool_lint! { pub clippy::TEST_LINT, ... }`.
+fn scan_lints(contents: &str, lints: &mut BTreeSet<String>) {
+    for &(mac, tool) in &[("declare_lint!", false), ("declare_tool_lint!", true)] {
+        for (i, _) in contents.match_indices(mac) {
+            let rest = contents[i + mac.len()..]
+                .trim_start()
+                .trim_start_matches(|c| c == '{' || c == '(')
+                .trim_start();
+            let rest = if rest.starts_with("pub ") { rest[4..].trim_start() } else { rest };
+            let name: String = rest
+                .chars()
+                .take_while(|&c| {
+                    c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' ||
+                        (tool && (c.is_ascii_lowercase() || c == ':'))
+                })
+                .collect();
+            // Skips the macros' own definitions and mentions of them in comments.
+            if !name.is_empty() && rest[name.len()..].starts_with(',') &&
+                name.contains("::") == tool
+            {
+                lints.insert(name.to_lowercase());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mentions_error_code, scan_error_codes, scan_lints};
+    use std::collections::BTreeSet;
+
+    fn set(items: &[&str]) -> BTreeSet<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn error_codes_of_long_diagnostics() {
+        let contents = r####"
+register_long_diagnostics! {
+
+E0001: r##"
+This pattern will never be reached, see also E0004.
+
+E0010: is not registered here, it only starts a line of the explanation.
+
+```compile_fail,E0004
+enum Terminator { HastaLaVistaBaby, TalkToMyHand }
+```
+"##,
+
+E0002: r##"
+Like E0001, but for empty matches.
+"##,
+
+}
+
+register_diagnostics! {
+    E0003, // mentions E0009 in a comment
+    E0005,
+}
+"####;
+        let mut codes = BTreeSet::new();
+        scan_error_codes(contents, &mut codes);
+        assert_eq!(codes, set(&["E0001", "E0002", "E0003", "E0005"]));
+    }
+
+    #[test]
+    fn one_line_long_diagnostics() {
+        let contents = r####"
+register_long_diagnostics! {
+E0001: r##"Never reached, see E0004."##,
+E0002: r##"Like E0001."##,
+}
+"####;
+        let mut codes = BTreeSet::new();
+        scan_error_codes(contents, &mut codes);
+        assert_eq!(codes, set(&["E0001", "E0002"]));
+    }
+
+    #[test]
+    fn declared_lints() {
+        let contents = r#"
+declare_lint! {
+    pub UNUSED_IMPORTS,
+    Warn,
+    "imports that are never used"
+}
+
+declare_lint!(WHILE_TRUE, Warn, "suggest using `loop { }` instead of `while true { }`");
+
+declare_tool_lint!(pub clippy::TEST_LINT, Warn, "Warn about stuff");
+
+// Lints are declared with `declare_lint!`.
+macro_rules! declare_lint {
+    ($vis: vis $NAME: ident, $Level: ident, $desc: expr) => ();
+}
+macro_rules! declare_tool_lint {
+    ($vis: vis $tool: ident ::$NAME: ident, $Level: ident, $desc: expr) => ();
+}
+"#;
+        let mut lints = BTreeSet::new();
+        scan_lints(contents, &mut lints);
+        assert_eq!(lints, set(&["clippy::test_lint", "unused_imports", "while_true"]));
+    }
+
+    #[test]
+    fn annotations_mentioning_error_codes() {
+        assert!(mentions_error_code("mismatched types [E0308]"));
+        assert!(mentions_error_code("E0599"));
+        assert!(!mentions_error_code("mismatched types"));
+        assert!(!mentions_error_code("E03080 is not an error code"));
+    }
+}
@@ -785,7 +785,7 @@ impl Config {
         line.starts_with(prefix) && line.as_bytes().get(prefix.len()) == Some(&b'-')
     }
 
-    fn parse_name_directive(&self, line: &str, directive: &str) -> bool {
+    pub fn parse_name_directive(&self, line: &str, directive: &str) -> bool {
         // Ensure the directive is a whole word. Do not match "ignore-x86" when
         // the line says "ignore-x86_64".
         line.starts_with(directive) && match line.as_bytes().get(directive.len()) {
@@ -805,6 +805,8 @@ impl Config {
         }
     }
 
+    /// Returns the root of the checkout of the Rust source tree the tests are in, if they are in
+    /// one.
     pub fn find_rust_src_root(&self) -> Option<PathBuf> {
         let mut path = self.src_base.clone();
         let path_postfix = Path::new("src/etc/lldb_batchmode.py");
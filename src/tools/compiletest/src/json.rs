@@ -11,6 +11,7 @@
 use errors::{Error, ErrorKind};
 use runtest::ProcRes;
 use serde_json;
+use std::collections::BTreeSet;
 use std::path::Path;
 use std::str::FromStr;
 
@@ -94,6 +95,27 @@ pub fn extract_rendered(output: &str, proc_res: &ProcRes) -> String {
         .collect()
 }
 
+/// Returns the codes of all the diagnostics in `output`, including those of sub-diagnostics.
+/// Lines that are not JSON diagnostics are skipped.
+pub fn diagnostic_codes(output: &str) -> BTreeSet<String> {
+    fn push_codes(diagnostic: &Diagnostic, codes: &mut BTreeSet<String>) {
+        if let Some(ref code) = diagnostic.code {
+            codes.insert(code.code.clone());
+        }
+        for child in &diagnostic.children {
+            push_codes(child, codes);
+        }
+    }
+
+    let mut codes = BTreeSet::new();
+    for line in output.lines().filter(|line| line.starts_with('{')) {
+        if let Ok(diagnostic) = serde_json::from_str::<Diagnostic>(line) {
+            push_codes(&diagnostic, &mut codes);
+        }
+    }
+    codes
+}
+
 pub fn parse_output(file_name: &str, output: &str, proc_res: &ProcRes) -> Vec<Error> {
     output
         .lines()
@@ -267,3 +289,34 @@ fn push_backtrace(
         push_backtrace(expected_errors, previous_expansion, file_name);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::diagnostic_codes;
+
+    fn diagnostic(level: &str, code: Option<&str>, children: &[String]) -> String {
+        let code = match code {
+            Some(code) => format!(r#"{{"code":"{}","explanation":null}}"#, code),
+            None => "null".to_string(),
+        };
+        format!(
+            r#"{{"message":"","code":{},"level":"{}","spans":[],"children":[{}],"rendered":null}}"#,
+            code,
+            level,
+            children.join(",")
+        )
+    }
+
+    #[test]
+    fn codes_of_nested_diagnostics() {
+        let note = diagnostic("note", Some("E0599"), &[]);
+        let help = diagnostic("help", None, &[diagnostic("note", Some("unused_imports"), &[])]);
+        let output = [
+            "not a diagnostic".to_string(),
+            diagnostic("error", Some("E0308"), &[note, help]),
+            diagnostic("warning", None, &[]),
+        ].join("\n");
+        let codes: Vec<_> = diagnostic_codes(&output).into_iter().collect();
+        assert_eq!(codes, ["E0308", "E0599", "unused_imports"]);
+    }
+}
@@ -15,6 +15,7 @@ use common::{Codegen, CodegenUnits, DebugInfoBoth, DebugInfoGdb, DebugInfoLldb,
 use common::{CompileFail, Pretty, RunFail, RunPass, RunPassValgrind};
 use common::{Config, TestPaths};
 use common::{Incremental, MirOpt, RunMake, Ui};
+use diagnostic_coverage;
 use diff;
 use errors::{self, Error, ErrorKind};
 use filetime::FileTime;
@@ -339,6 +340,9 @@ impl<'test> TestCx<'test> {
 
         let output_to_check = self.get_output(&proc_res);
         let expected_errors = errors::load_errors(&self.testpaths.file, self.revision);
+        if self.props.error_patterns.is_empty() {
+            self.record_diagnostic_codes(&proc_res, &expected_errors);
+        }
         if !expected_errors.is_empty() {
             if !self.props.error_patterns.is_empty() {
                 self.fatal("both error pattern and expected errors specified");
@@ -351,6 +355,22 @@ impl<'test> TestCx<'test> {
         self.check_forbid_output(&output_to_check, &proc_res);
     }
 
+    /// Records the codes of the diagnostics the compiler emitted as JSON for this test, when a
+    /// `--diagnostic-coverage` report was asked for.
+    fn record_diagnostic_codes(&self, proc_res: &ProcRes, expected_errors: &[Error]) {
+        // The compare modes run the same tests again and would only overwrite the records.
+        if self.config.diagnostic_coverage.is_none() || self.config.compare_mode.is_some() {
+            return;
+        }
+        diagnostic_coverage::record(
+            self.config,
+            self.testpaths,
+            self.revision,
+            json::diagnostic_codes(&proc_res.stderr),
+            expected_errors,
+        );
+    }
+
     fn run_rfail_test(&self) {
         let proc_res = self.compile_test();
 
@@ -1879,10 +1899,7 @@ impl<'test> TestCx<'test> {
                 self.fatal("no NodeJS binary found (--nodejs)");
             }
 
-            let src = self.config.src_base
-                .parent().unwrap() // chop off `run-pass`
-                .parent().unwrap() // chop off `test`
-                .parent().unwrap(); // chop off `src`
+            let src = self.config.find_rust_src_root().expect("Could not find Rust source root");
             args.push(src.join("src/etc/wasm32-shim.js").display().to_string());
         }
 
@@ -2131,7 +2148,7 @@ impl<'test> TestCx<'test> {
         if self.props.check_test_line_numbers_match {
             self.check_rustdoc_test_option(proc_res);
         } else {
-            let root = self.config.find_rust_src_root().unwrap();
+            let root = self.config.find_rust_src_root().expect("Could not find Rust source root");
             let res = self.cmd2procres(
                 Command::new(&self.config.docck_python)
                     .arg(root.join("src/etc/htmldocck.py"))
@@ -2546,16 +2563,6 @@ impl<'test> TestCx<'test> {
 
     fn run_rmake_test(&self) {
         let cwd = env::current_dir().unwrap();
-        let src_root = self
-            .config
-            .src_base
-            .parent()
-            .unwrap()
-            .parent()
-            .unwrap()
-            .parent()
-            .unwrap();
-        let src_root = cwd.join(&src_root);
 
         let tmpdir = cwd.join(self.output_base_name());
         if tmpdir.exists() {
@@ -2581,7 +2588,6 @@ impl<'test> TestCx<'test> {
             .stderr(Stdio::piped())
             .env("TARGET", &self.config.target)
             .env("PYTHON", &self.config.docck_python)
-            .env("S", src_root)
             .env("RUST_BUILD_STAGE", &self.config.stage_id)
             .env("RUSTC", cwd.join(&self.config.rustc_path))
             .env("TMPDIR", &tmpdir)
@@ -2598,6 +2604,10 @@ impl<'test> TestCx<'test> {
             .env_remove("MFLAGS")
             .env_remove("CARGO_MAKEFLAGS");
 
+        if let Some(src_root) = self.config.find_rust_src_root() {
+            cmd.env("S", cwd.join(src_root));
+        }
+
         if let Some(ref rustdoc) = self.config.rustdoc_path {
             cmd.env("RUSTDOC", cwd.join(rustdoc));
         }
@@ -2695,6 +2705,11 @@ impl<'test> TestCx<'test> {
         let proc_res = self.compile_test();
         self.check_if_test_should_compile(&proc_res);
 
+        let expected_errors = errors::load_errors(&self.testpaths.file, self.revision);
+        if !explicit {
+            self.record_diagnostic_codes(&proc_res, &expected_errors);
+        }
+
         let expected_stderr = self.load_expected_output(UI_STDERR);
         let expected_stdout = self.load_expected_output(UI_STDOUT);
         let expected_fixed = self.load_expected_output(UI_FIXED);
@@ -2766,8 +2781,6 @@ impl<'test> TestCx<'test> {
             );
         }
 
-        let expected_errors = errors::load_errors(&self.testpaths.file, self.revision);
-
         if self.should_run_successfully() {
             let proc_res = self.exec_compiled_test();
 
@@ -2830,24 +2843,53 @@ impl<'test> TestCx<'test> {
             .read_to_string(&mut test_file_contents)
             .unwrap();
         if let Some(idx) = test_file_contents.find("// END RUST SOURCE") {
-            let (_, tests_text) = test_file_contents.split_at(idx + "// END_RUST SOURCE".len());
+            let (rust_source, tests_text) =
+                test_file_contents.split_at(idx + "// END_RUST SOURCE".len());
             let tests_text_str = String::from(tests_text);
             let mut curr_test: Option<&str> = None;
             let mut curr_test_contents = vec![ExpectedLine::Elision];
+            // With `--bless`, the tests section is rebuilt as it is checked, with the lines of
+            // each block whose MIR differs replaced by the actual MIR.
+            let mut blessed_lines = vec![];
+            let mut curr_test_lines = vec![];
+            let mut blessed_any = false;
             for l in tests_text_str.lines() {
                 debug!("line: {:?}", l);
                 if l.starts_with("// START ") {
                     let (_, t) = l.split_at("// START ".len());
                     curr_test = Some(t);
+                    blessed_lines.push(l.to_string());
+                    continue;
                 } else if l.starts_with("// END") {
                     let (_, t) = l.split_at("// END ".len());
                     if Some(t) != curr_test {
                         panic!("mismatched START END test name");
                     }
-                    self.compare_mir_test_output(curr_test.unwrap(), &curr_test_contents);
+                    let test_name = curr_test.unwrap();
+                    match self.compare_mir_test_output(test_name, &curr_test_contents) {
+                        Ok(()) => blessed_lines.extend(curr_test_lines.drain(..)),
+                        Err(ref msg) if !self.config.bless => {
+                            println!("To update references, rerun the tests and pass the \
+                                      `--bless` flag");
+                            panic!("{}", msg);
+                        }
+                        Err(_) => {
+                            println!("\nThe actual MIR of `{}` differed from the expected MIR.",
+                                     test_name);
+                            curr_test_lines.clear();
+                            blessed_lines.extend(
+                                self.dumped_mir_lines(test_name)
+                                    .into_iter()
+                                    .map(|line| format!("// {}", line)),
+                            );
+                            blessed_any = true;
+                        }
+                    }
+                    blessed_lines.push(l.to_string());
                     curr_test = None;
                     curr_test_contents.clear();
                     curr_test_contents.push(ExpectedLine::Elision);
+                    continue;
                 } else if l.is_empty() {
                     // ignore
                 } else if l.starts_with("//") && l.split_at("//".len()).1.trim() == "..." {
@@ -2856,6 +2898,27 @@ impl<'test> TestCx<'test> {
                     let (_, test_content) = l.split_at("// ".len());
                     curr_test_contents.push(ExpectedLine::Text(test_content));
                 }
+
+                if curr_test.is_some() {
+                    curr_test_lines.push(l.to_string());
+                } else {
+                    blessed_lines.push(l.to_string());
+                }
+            }
+
+            if blessed_any {
+                let mut blessed = format!("{}{}", rust_source, blessed_lines.join("\n"));
+                if tests_text.ends_with('\n') {
+                    blessed.push('\n');
+                }
+                if let Err(e) = fs::write(&self.testpaths.file, blessed) {
+                    self.fatal(&format!(
+                        "failed to write blessed MIR to `{}`: {}",
+                        self.testpaths.file.display(),
+                        e
+                    ));
+                }
+                println!("Blessed MIR saved to {}", self.testpaths.file.display());
             }
         }
     }
@@ -2878,12 +2941,12 @@ impl<'test> TestCx<'test> {
         }
     }
 
-    fn compare_mir_test_output(&self, test_name: &str, expected_content: &[ExpectedLine<&str>]) {
+    /// Reads the MIR dumped for `test_name`, checking that it comes from this run of the test.
+    fn read_mir_dump(&self, test_name: &str) -> String {
         let mut output_file = PathBuf::new();
         output_file.push(self.get_mir_dump_dir());
         output_file.push(test_name);
-        debug!("comparing the contests of: {:?}", output_file);
-        debug!("with: {:?}", expected_content);
+        debug!("reading the contents of: {:?}", output_file);
         if !output_file.exists() {
             panic!(
                 "Output file `{}` from test does not exist",
@@ -2895,6 +2958,29 @@ impl<'test> TestCx<'test> {
         let mut dumped_file = fs::File::open(output_file.clone()).unwrap();
         let mut dumped_string = String::new();
         dumped_file.read_to_string(&mut dumped_string).unwrap();
+        dumped_string
+    }
+
+    /// Returns the lines of the MIR dumped for `test_name` the way `--bless` writes them into
+    /// the test file: without comments, blank lines or trailing whitespace.
+    fn dumped_mir_lines(&self, test_name: &str) -> Vec<String> {
+        self.read_mir_dump(test_name)
+            .lines()
+            .map(|l| nocomment_mir_line(l).trim_right().to_string())
+            .filter(|l| !l.is_empty())
+            .collect()
+    }
+
+    /// Checks the MIR dumped for `test_name` against the expected lines, returning a description
+    /// of the first mismatch.
+    fn compare_mir_test_output(
+        &self,
+        test_name: &str,
+        expected_content: &[ExpectedLine<&str>],
+    ) -> Result<(), String> {
+        debug!("comparing the contents of: {:?}", test_name);
+        debug!("with: {:?}", expected_content);
+        let dumped_string = self.read_mir_dump(test_name);
         let mut dumped_lines = dumped_string
             .lines()
             .map(|l| nocomment_mir_line(l))
@@ -2934,14 +3020,14 @@ impl<'test> TestCx<'test> {
                 .map(|l| f(l))
                 .collect::<Vec<_>>()
                 .join("\n");
-            panic!(
+            format!(
                 "Did not find expected line, error: {}\n\
                  Expected Line: {:?}\n\
                  Test Name: {}\n\
                  Expected:\n{}\n\
                  Actual:\n{}",
                 extra_msg, expected_line, test_name, expected_content, normalize_all
-            );
+            )
         };
 
         // We expect each non-empty line to appear consecutively, non-consecutive lines
@@ -2957,7 +3043,7 @@ impl<'test> TestCx<'test> {
 
                     if !compare(expected_line, dumped_line) {
                         error!("{:?}", start_block_line);
-                        error(
+                        return Err(error(
                             expected_line,
                             format!(
                                 "Mismatch in lines\n\
@@ -2966,7 +3052,7 @@ impl<'test> TestCx<'test> {
                                 start_block_line.unwrap_or("None"),
                                 dumped_line
                             ),
-                        );
+                        ));
                     }
                 }
                 Some(&ExpectedLine::Elision) => {
@@ -2986,13 +3072,17 @@ impl<'test> TestCx<'test> {
                             }
                         }
                         if !found {
-                            error(expected_line, "ran out of mir dump to match against".into());
+                            return Err(error(
+                                expected_line,
+                                "ran out of mir dump to match against".into(),
+                            ));
                         }
                     }
                 }
                 None => {}
             }
         }
+        Ok(())
     }
 
     fn get_mir_dump_dir(&self) -> PathBuf {
@@ -3004,67 +3094,14 @@ impl<'test> TestCx<'test> {
     }
 
     fn normalize_output(&self, output: &str, custom_rules: &[(String, String)]) -> String {
-        let parent_dir = self.testpaths.file.parent().unwrap();
         let cflags = self.props.compile_flags.join(" ");
         let json = cflags.contains("--error-format json")
             || cflags.contains("--error-format pretty-json")
             || cflags.contains("--error-format=json")
-            || cflags.contains("--error-format=pretty-json");
-        let parent_dir_str = if json {
-            parent_dir.display().to_string().replace("\\", "\\\\")
-        } else {
-            parent_dir.display().to_string()
-        };
-
-        let mut normalized = output.replace(&parent_dir_str, "$DIR");
-
-        // Paths into the libstd/libcore
-        let src_dir = self.config.src_base.parent().unwrap().parent().unwrap();
-        let src_dir_str = if json {
-            src_dir.display().to_string().replace("\\", "\\\\")
-        } else {
-            src_dir.display().to_string()
-        };
-        normalized = normalized.replace(&src_dir_str, "$SRC_DIR");
-
-        // Paths into the build directory
-        let test_build_dir = &self.config.build_base;
-        let parent_build_dir = test_build_dir.parent().unwrap().parent().unwrap().parent().unwrap();
-
-        // eg. /home/user/rust/build/x86_64-unknown-linux-gnu/test/ui
-        normalized = normalized.replace(test_build_dir.to_str().unwrap(), "$TEST_BUILD_DIR");
-        // eg. /home/user/rust/build
-        normalized = normalized.replace(&parent_build_dir.to_str().unwrap(), "$BUILD_DIR");
-
-        // Paths into lib directory.
-        let mut lib_dir = parent_build_dir.parent().unwrap().to_path_buf();
-        lib_dir.push("lib");
-        normalized = normalized.replace(&lib_dir.to_str().unwrap(), "$LIB_DIR");
-
-        if json {
-            // escaped newlines in json strings should be readable
-            // in the stderr files. There's no point int being correct,
-            // since only humans process the stderr files.
-            // Thus we just turn escaped newlines back into newlines.
-            normalized = normalized.replace("\\n", "\n");
-        }
-
-        // If there are `$SRC_DIR` normalizations with line and column numbers, then replace them
-        // with placeholders as we do not want tests needing updated when compiler source code
-        // changes.
-        // eg. $SRC_DIR/libcore/mem.rs:323:14 becomes $SRC_DIR/libcore/mem.rs:LL:COL
-        normalized = Regex::new("SRC_DIR(.+):\\d+:\\d+").unwrap()
-            .replace_all(&normalized, "SRC_DIR$1:LL:COL").into_owned();
-
-        normalized = normalized.replace("\\\\", "\\") // denormalize for paths on windows
-              .replace("\\", "/") // normalize for paths on windows
-              .replace("\r\n", "\n") // normalize for linebreaks on windows
-              .replace("\t", "\\t"); // makes tabs visible
-        for rule in custom_rules {
-            let re = Regex::new(&rule.0).expect("bad regex in custom normalization rule");
-            normalized = re.replace_all(&normalized, &rule.1[..]).into_owned();
-        }
-        normalized
+            || cflags.contains("--error-format=pretty-json")
+            || cflags.contains("--error-format sarif")
+            || cflags.contains("--error-format=sarif");
+        normalize_output(&self.config, &self.testpaths, json, output, custom_rules)
     }
 
     fn expected_output_path(&self, kind: &str) -> PathBuf {
@@ -3307,6 +3344,83 @@ where
     }
 }
 
+/// Normalizes compiler output the way it is stored in the expected output files of a test: the
+/// directory of the test becomes `$DIR`, the build directory `$TEST_BUILD_DIR`, line endings and
+/// path separators are made uniform, and then `custom_rules` (from the `normalize-stderr` and
+/// `normalize-stdout` directives) are applied. `json` says whether the output is JSON, where paths
+/// are escaped.
+///
+/// When testing from a checkout of the Rust source tree, paths into it (`$SRC_DIR`) and into its
+/// build directory (`$BUILD_DIR`, `$LIB_DIR`) are normalized as well.
+pub fn normalize_output(
+    config: &Config,
+    testpaths: &TestPaths,
+    json: bool,
+    output: &str,
+    custom_rules: &[(String, String)],
+) -> String {
+    let path_str = |path: &Path| if json {
+        path.display().to_string().replace("\\", "\\\\")
+    } else {
+        path.display().to_string()
+    };
+
+    let parent_dir = testpaths.file.parent().unwrap();
+    let mut normalized = output.replace(&path_str(parent_dir), "$DIR");
+
+    // Paths into the libstd/libcore
+    let rust_src_root = config.find_rust_src_root();
+    if let Some(ref rust_src_root) = rust_src_root {
+        normalized = normalized.replace(&path_str(&rust_src_root.join("src")), "$SRC_DIR");
+    }
+
+    // Paths into the build directory
+    let test_build_dir = &config.build_base;
+
+    // eg. /home/user/rust/build/x86_64-unknown-linux-gnu/test/ui
+    normalized = normalized.replace(test_build_dir.to_str().unwrap(), "$TEST_BUILD_DIR");
+
+    let parent_build_dir = test_build_dir
+        .parent()
+        .and_then(Path::parent)
+        .and_then(Path::parent)
+        .filter(|_| rust_src_root.is_some());
+    if let Some(parent_build_dir) = parent_build_dir {
+        // eg. /home/user/rust/build
+        normalized = normalized.replace(&parent_build_dir.to_str().unwrap(), "$BUILD_DIR");
+
+        // Paths into lib directory.
+        if let Some(root) = parent_build_dir.parent() {
+            normalized = normalized.replace(&root.join("lib").to_str().unwrap(), "$LIB_DIR");
+        }
+    }
+
+    if json {
+        // escaped newlines in json strings should be readable
+        // in the stderr files. There's no point int being correct,
+        // since only humans process the stderr files.
+        // Thus we just turn escaped newlines back into newlines.
+        normalized = normalized.replace("\\n", "\n");
+    }
+
+    // If there are `$SRC_DIR` normalizations with line and column numbers, then replace them
+    // with placeholders as we do not want tests needing updated when compiler source code
+    // changes.
+    // eg. $SRC_DIR/libcore/mem.rs:323:14 becomes $SRC_DIR/libcore/mem.rs:LL:COL
+    normalized = Regex::new("SRC_DIR(.+):\\d+:\\d+").unwrap()
+        .replace_all(&normalized, "SRC_DIR$1:LL:COL").into_owned();
+
+    normalized = normalized.replace("\\\\", "\\") // denormalize for paths on windows
+          .replace("\\", "/") // normalize for paths on windows
+          .replace("\r\n", "\n") // normalize for linebreaks on windows
+          .replace("\t", "\\t"); // makes tabs visible
+    for rule in custom_rules {
+        let re = Regex::new(&rule.0).expect("bad regex in custom normalization rule");
+        normalized = re.replace_all(&normalized, &rule.1[..]).into_owned();
+    }
+    normalized
+}
+
 fn normalize_mir_line(line: &str) -> String {
     nocomment_mir_line(line).replace(char::is_whitespace, "")
 }